@@ -1,6 +1,13 @@
 use crate::cli::{Args, GraphQCommand};
-use crate::cmds::{QueryBehaviour, handle_console_cmd, handle_query_cmd};
+use crate::cmds::{
+    BaselineSettings, BenchmarkReportOutput, ExecutionSettings, LoadTestSettings, ParamSetsInput,
+    QueryBehaviour, QueryParamsInput, QuerySource, handle_console_cmd, handle_query_cmd,
+    handle_schema_cmd,
+};
+use crate::config::{get_config_file_path, read_profile};
+use crate::domain::{OutputFormat, QueryLanguage, ResultsFormat, RetryPolicy};
 use crate::error::AppError;
+use crate::repository::ConnectionSettings;
 use crate::view::ConsoleConfig;
 use clap::Parser;
 use etcetera::BaseStrategy;
@@ -8,13 +15,32 @@ use etcetera::BaseStrategy;
 pub async fn run() -> Result<(), AppError> {
     let xdg = etcetera::choose_base_strategy()?;
     crate::logging::setup(&xdg)?;
-    let args = Args::parse();
+    let mut args = Args::parse();
+    if args.no_color {
+        colored::control::set_override(false);
+    }
+
+    let profile = match &args.profile {
+        Some(name) => Some((
+            name.as_str(),
+            read_profile(get_config_file_path(&xdg.config_dir()), name)?,
+        )),
+        None => None,
+    };
+    let connection_settings =
+        ConnectionSettings::resolve(profile.as_ref().map(|(n, p)| (*n, p)), args.read_only)?;
 
     if args.debug {
+        // a profile can turn read-only mode on as well
+        args.read_only = connection_settings.read_only();
         print!("DEBUG INFO\n{args}");
+        if profile.is_some() {
+            print!("{connection_settings}");
+        }
         return Ok(());
     }
 
+    let on_error = args.on_error();
     match args.command {
         GraphQCommand::Console {
             page_results,
@@ -27,21 +53,45 @@ pub async fn run() -> Result<(), AppError> {
                 write_results,
                 results_directory,
                 results_format,
+                display_format: OutputFormat::default(),
+                query_language: QueryLanguage::default(),
+                read_only: connection_settings.read_only(),
+                query_timeout: args.timeout,
+                retry_policy: RetryPolicy::new(args.retries),
+                on_error,
                 history_file_path: xdg.data_dir().join("grafq").join("history.txt"),
             };
 
-            handle_console_cmd(console_config).await?;
+            handle_console_cmd(console_config, connection_settings).await?;
         }
         GraphQCommand::Query {
             query,
+            file,
+            language,
+            params,
+            params_file,
+            plan,
+            plan_details,
+            plan_format,
             page_results,
             benchmark,
             bench_num_runs,
             bench_num_warmup_runs,
+            bench_output,
+            concurrency,
+            duration,
+            rate,
+            save_baseline,
+            compare_baseline,
+            fail_if_slower,
+            bench_params,
+            bench_params_order,
             print_query,
             write_results,
             results_directory,
             results_format,
+            table,
+            output_format,
         } => {
             if benchmark && write_results {
                 return Err(AppError::InvalidCLIUsage(
@@ -49,10 +99,129 @@ pub async fn run() -> Result<(), AppError> {
                 ));
             }
 
-            let behaviour = if benchmark {
+            if bench_output.is_some() && !benchmark {
+                return Err(AppError::InvalidCLIUsage(
+                    "a benchmark report can only be written when benchmarking",
+                ));
+            }
+
+            let load_test =
+                (concurrency.is_some() || duration.is_some() || rate.is_some()).then(|| {
+                    LoadTestSettings {
+                        concurrency: concurrency.unwrap_or_default(),
+                        duration,
+                        rate,
+                    }
+                });
+
+            if load_test.is_some() && !benchmark {
+                return Err(AppError::InvalidCLIUsage(
+                    "load test options (concurrency, duration, rate) can only be used when benchmarking",
+                ));
+            }
+
+            if load_test.is_some() && bench_output.is_some() {
+                return Err(AppError::InvalidCLIUsage(
+                    "benchmark reports aren't supported for load tests",
+                ));
+            }
+
+            let uses_baselines = save_baseline.is_some() || compare_baseline.is_some();
+            if (uses_baselines || fail_if_slower.is_some()) && !benchmark {
+                return Err(AppError::InvalidCLIUsage(
+                    "baselines can only be saved or compared when benchmarking",
+                ));
+            }
+
+            if uses_baselines && load_test.is_some() {
+                return Err(AppError::InvalidCLIUsage(
+                    "baselines aren't supported for load tests",
+                ));
+            }
+
+            if bench_params.is_some() && !benchmark {
+                return Err(AppError::InvalidCLIUsage(
+                    "parameter sets can only be used when benchmarking",
+                ));
+            }
+
+            if bench_params.is_some() && load_test.is_some() {
+                return Err(AppError::InvalidCLIUsage(
+                    "parameter sets aren't supported for load tests",
+                ));
+            }
+
+            if fail_if_slower.is_some() && compare_baseline.is_none() {
+                return Err(AppError::InvalidCLIUsage(
+                    "--fail-if-slower needs a baseline to compare with (via --compare-baseline)",
+                ));
+            }
+
+            // each concurrent task needs a connection of its own
+            let connection_settings = match &load_test {
+                Some(l) => connection_settings.with_max_connections(l.concurrency.value().into()),
+                None => connection_settings,
+            };
+
+            if language == QueryLanguage::Gremlin
+                && (!params.is_empty() || params_file.is_some() || bench_params.is_some())
+            {
+                return Err(AppError::InvalidCLIUsage(
+                    "query parameters are not supported for gremlin queries",
+                ));
+            }
+
+            if plan.is_some() && (benchmark || write_results) {
+                return Err(AppError::InvalidCLIUsage(
+                    "cannot show a query plan while benchmarking or writing results",
+                ));
+            }
+
+            if plan.is_some() && language == QueryLanguage::Gremlin {
+                return Err(AppError::InvalidCLIUsage(
+                    "query plans are only supported for cypher queries",
+                ));
+            }
+
+            if output_format.is_some()
+                && (page_results || write_results || benchmark || plan.is_some())
+            {
+                return Err(AppError::InvalidCLIUsage(
+                    "an output format can only be used when results are printed to stdout (not when paging, writing, benchmarking, or showing a query plan)",
+                ));
+            }
+
+            if table.is_some() && !(write_results && results_format == ResultsFormat::Sqlite) {
+                return Err(AppError::InvalidCLIUsage(
+                    "a table name can only be used when writing results in the sqlite format",
+                ));
+            }
+
+            let behaviour = if let Some(mode) = plan {
+                QueryBehaviour::Plan {
+                    mode,
+                    details: plan_details,
+                    format: plan_format,
+                }
+            } else if benchmark {
                 QueryBehaviour::Benchmark {
                     num_runs: bench_num_runs,
                     warmup_runs: bench_num_warmup_runs,
+                    report: bench_output.map(|format| BenchmarkReportOutput {
+                        results_directory,
+                        format,
+                    }),
+                    load_test,
+                    baseline: BaselineSettings {
+                        directory: xdg.data_dir().join("grafq").join("baselines"),
+                        save: save_baseline,
+                        compare: compare_baseline,
+                        fail_if_slower,
+                    },
+                    param_sets: bench_params.map(|path| ParamSetsInput {
+                        path,
+                        order: bench_params_order,
+                    }),
                 }
             } else {
                 QueryBehaviour::Normal {
@@ -60,10 +229,42 @@ pub async fn run() -> Result<(), AppError> {
                     write_results,
                     results_directory,
                     results_format,
+                    table,
+                    output_format: output_format.unwrap_or_default(),
                 }
             };
 
-            handle_query_cmd(query, behaviour, print_query).await?;
+            let source = match (query, file) {
+                (Some(query), _) if query == "-" => QuerySource::Stdin,
+                (Some(query), _) => QuerySource::Argument(query),
+                (None, Some(path)) => QuerySource::File(path),
+                (None, None) => {
+                    return Err(AppError::InvalidCLIUsage(
+                        "either a query or a query file needs to be provided",
+                    ));
+                }
+            };
+
+            handle_query_cmd(
+                connection_settings,
+                source,
+                language,
+                QueryParamsInput {
+                    params,
+                    params_file,
+                },
+                behaviour,
+                print_query,
+                ExecutionSettings {
+                    timeout: args.timeout,
+                    retry_policy: RetryPolicy::new(args.retries),
+                    on_error,
+                },
+            )
+            .await?;
+        }
+        GraphQCommand::Schema { format } => {
+            handle_schema_cmd(connection_settings, format).await?;
         }
     }
 
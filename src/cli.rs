@@ -1,5 +1,9 @@
 use crate::config::DEFAULT_RESULTS_DIR;
-use crate::domain::{BenchmarkNumRuns, ResultsFormat};
+use crate::domain::{
+    BaselineName, BenchmarkNumRuns, BenchmarkReportFormat, LoadTestConcurrency, LoadTestDuration,
+    LoadTestRate, OnError, OutputFormat, ParamSetOrder, PlanFormat, PlanMode, QueryLanguage,
+    QueryParam, QueryTimeout, ResultsFormat, SchemaFormat, SlowdownThreshold, TableName,
+};
 use clap::{Parser, Subcommand};
 use std::path::PathBuf;
 
@@ -11,9 +15,54 @@ pub struct Args {
     /// Output debug information without doing anything
     #[arg(long = "debug", global = true)]
     pub debug: bool,
+    /// Connection profile to use (from grafq's config file)
+    #[arg(long = "profile", value_name = "NAME", global = true)]
+    pub profile: Option<String>,
+    /// Reject queries that write to the database
+    #[arg(long = "read-only", global = true)]
+    pub read_only: bool,
+    /// Cancel queries (on the server as well) that run for longer than this (eg. 500ms, 30s, 2m)
+    #[arg(long = "timeout", value_name = "DURATION", global = true)]
+    pub timeout: Option<QueryTimeout>,
+    /// Number of times to retry read queries that fail with transient errors (eg. throttling)
+    #[arg(
+        long = "retries",
+        value_name = "NUMBER",
+        default_value_t = 3,
+        global = true
+    )]
+    pub retries: u16,
+    /// Stop running a script's statements once one of them fails (the default)
+    #[arg(
+        long = "stop-on-error",
+        global = true,
+        overrides_with = "continue_on_error"
+    )]
+    pub stop_on_error: bool,
+    /// Keep running a script's statements even if some of them fail
+    #[arg(
+        long = "continue-on-error",
+        global = true,
+        overrides_with = "stop_on_error"
+    )]
+    pub continue_on_error: bool,
+    /// Don't use colors in output (also turned off when $NO_COLOR is set)
+    #[arg(long = "no-color", global = true)]
+    pub no_color: bool,
+}
+
+impl Args {
+    pub fn on_error(&self) -> OnError {
+        if self.continue_on_error {
+            OnError::Continue
+        } else {
+            OnError::Stop
+        }
+    }
 }
 
 #[derive(Subcommand, Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum GraphQCommand {
     /// Open grafq's console
     #[command()]
@@ -47,9 +96,35 @@ pub enum GraphQCommand {
         /// Display results via a pager ("less", by default, can be overridden by $GRAFQ_PAGER)
         #[arg(short = 'p', long = "page-results")]
         page_results: bool,
-        /// Cypher query to execute
-        #[arg()]
-        query: String,
+        /// Query to execute ("-" reads it from stdin); can contain several ";"-separated statements
+        #[arg(required_unless_present = "file", conflicts_with = "file")]
+        query: Option<String>,
+        /// File to read the query (or several ";"-separated statements) from
+        #[arg(long = "file", value_name = "FILE")]
+        file: Option<PathBuf>,
+        /// Language the query is written in
+        #[arg(
+            short = 'l',
+            long = "lang",
+            value_name = "LANGUAGE",
+            default_value = "cypher"
+        )]
+        language: QueryLanguage,
+        /// Query parameter, as key=value (values are parsed as JSON, falling back to strings)
+        #[arg(long = "param", value_name = "KEY=VALUE")]
+        params: Vec<QueryParam>,
+        /// JSON file containing an object of query parameters
+        #[arg(long = "params-file", value_name = "FILE")]
+        params_file: Option<PathBuf>,
+        /// Show the query's plan instead of its results ("profile" executes the query to gather runtime statistics)
+        #[arg(long = "plan", value_name = "MODE")]
+        plan: Option<PlanMode>,
+        /// Request a detailed plan (AWS Neptune only; executes the query)
+        #[arg(long = "plan-details")]
+        plan_details: bool,
+        /// Format to print the query plan in
+        #[arg(long = "plan-format", value_name = "FORMAT", default_value = "tree")]
+        plan_format: PlanFormat,
         /// Whether to benchmark the query
         #[arg(short = 'b', long = "bench")]
         benchmark: bool,
@@ -69,6 +144,42 @@ pub enum GraphQCommand {
             value_name = "NUMBER"
         )]
         bench_num_warmup_runs: u16,
+        /// Write a benchmark report (with every run's latency) to the results directory in this
+        /// format
+        #[arg(long = "bench-output", value_name = "FORMAT")]
+        bench_output: Option<BenchmarkReportFormat>,
+        /// Number of tasks to run the query from at the same time (turns the benchmark into a load
+        /// test)
+        #[arg(long = "concurrency", value_name = "NUMBER")]
+        concurrency: Option<LoadTestConcurrency>,
+        /// How long to run a load test for (eg. 30s, 5m); the number of benchmark runs is used
+        /// otherwise
+        #[arg(long = "duration", value_name = "DURATION")]
+        duration: Option<LoadTestDuration>,
+        /// Number of queries to start per second during a load test, across all tasks
+        #[arg(long = "rate", value_name = "QPS")]
+        rate: Option<LoadTestRate>,
+        /// Save the benchmark as a baseline with this name (replacing an existing one)
+        #[arg(long = "save-baseline", value_name = "NAME")]
+        save_baseline: Option<BaselineName>,
+        /// Compare the benchmark with the baseline with this name
+        #[arg(long = "compare-baseline", value_name = "NAME")]
+        compare_baseline: Option<BaselineName>,
+        /// Exit with code 3 if the median latency is significantly higher than the compared
+        /// baseline's by more than this percentage (eg. 20%)
+        #[arg(long = "fail-if-slower", value_name = "PERCENTAGE")]
+        fail_if_slower: Option<SlowdownThreshold>,
+        /// File with a parameter set per row (.csv with a header of parameter keys, or .jsonl with
+        /// an object per line) for benchmark runs to go through
+        #[arg(long = "bench-params", value_name = "FILE")]
+        bench_params: Option<PathBuf>,
+        /// Order in which benchmark runs go through parameter sets
+        #[arg(
+            long = "bench-params-order",
+            value_name = "ORDER",
+            default_value = "cycle"
+        )]
+        bench_params_order: ParamSetOrder,
         /// Print query
         #[arg(short = 'P', long = "print-query")]
         print_query: bool,
@@ -91,11 +202,30 @@ pub enum GraphQCommand {
             default_value = "json"
         )]
         results_format: ResultsFormat,
+        /// Table to write results to (when writing results in the sqlite format); named after the
+        /// query's id in the "queries" table otherwise
+        #[arg(long = "table", value_name = "NAME")]
+        table: Option<TableName>,
+        /// Format to print results to stdout in (for formats other than "table", status messages like "No results" go to stderr)
+        #[arg(short = 'o', long = "output-format", value_name = "FORMAT")]
+        output_format: Option<OutputFormat>,
+    },
+    /// Show the labels, relationship types, properties, indexes, and constraints in the database
+    #[command()]
+    Schema {
+        /// Format to print the schema in
+        #[arg(long = "format", value_name = "FORMAT", default_value = "table")]
+        format: SchemaFormat,
     },
 }
 
 impl std::fmt::Display for Args {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let timeout_info = self
+            .timeout
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "none".to_string());
+
         let output = match &self.command {
             GraphQCommand::Console {
                 page_results,
@@ -105,11 +235,19 @@ impl std::fmt::Display for Args {
             } => format!(
                 "
 command:                    console
+read only:                  {}
+timeout:                    {}
+retries:                    {}
+on error:                   {}
 display results via pager:  {}
 write results:              {}
 results directory:          {}
 results format:             {}
 ",
+                self.read_only,
+                timeout_info,
+                self.retries,
+                self.on_error(),
                 page_results,
                 write_results,
                 results_directory.to_string_lossy(),
@@ -118,31 +256,90 @@ results format:             {}
             GraphQCommand::Query {
                 page_results,
                 query,
+                file,
+                language,
+                params,
+                params_file,
+                plan,
+                plan_details,
+                plan_format,
                 benchmark,
                 bench_num_runs,
                 bench_num_warmup_runs,
+                bench_output,
+                concurrency,
+                duration,
+                rate,
+                save_baseline,
+                compare_baseline,
+                fail_if_slower,
+                bench_params,
+                bench_params_order,
                 print_query,
                 write_results,
                 results_directory,
                 results_format,
+                table,
+                output_format,
             } => {
                 let benchmark_info = match benchmark {
                     true => Some(format!(
                         r#"
 benchmark num runs:         {}
-benchmark num warmup runs:  {}"#,
-                        bench_num_runs, bench_num_warmup_runs,
+benchmark num warmup runs:  {}
+benchmark output:           {}"#,
+                        bench_num_runs,
+                        bench_num_warmup_runs,
+                        bench_output
+                            .as_ref()
+                            .map(|f| format!("{} (in {})", f, results_directory.to_string_lossy()))
+                            .unwrap_or_else(|| "none".to_string()),
                     )),
                     false => None,
                 };
 
-                let query_info = if query.as_str() == "-" {
-                    "
+                let load_test_info =
+                    if concurrency.is_some() || duration.is_some() || rate.is_some() {
+                        Some(format!(
+                            r#"
+load test concurrency:      {}
+load test duration:         {}
+load test rate:             {}"#,
+                            concurrency.unwrap_or_default(),
+                            duration
+                                .map(|d| d.to_string())
+                                .unwrap_or_else(|| "none".to_string()),
+                            rate.map(|r| r.to_string())
+                                .unwrap_or_else(|| "none".to_string()),
+                        ))
+                    } else {
+                        None
+                    };
+
+                let mut baseline_info = String::new();
+                if let Some(name) = save_baseline {
+                    baseline_info.push_str(&format!("\nsave baseline:              {name}"));
+                }
+                if let Some(name) = compare_baseline {
+                    baseline_info.push_str(&format!("\ncompare baseline:           {name}"));
+                }
+                if let Some(threshold) = fail_if_slower {
+                    baseline_info.push_str(&format!("\nfail if slower by:          {threshold}"));
+                }
+                if let Some(path) = bench_params {
+                    baseline_info.push_str(&format!(
+                        "\nbenchmark params file:      {}\nbenchmark params order:     {}",
+                        path.to_string_lossy(),
+                        bench_params_order
+                    ));
+                }
+
+                let query_info = match (query.as_deref(), file) {
+                    (Some("-"), _) => "
 query:                      -
 "
-                    .to_string()
-                } else {
-                    format!(
+                    .to_string(),
+                    (Some(query), _) => format!(
                         r#"
 query:
 ---
@@ -150,18 +347,57 @@ query:
 ---
 "#,
                         query
-                    )
+                    ),
+                    (None, Some(path)) => format!(
+                        "
+query file:                 {}
+",
+                        path.to_string_lossy()
+                    ),
+                    (None, None) => String::new(),
                 };
 
+                let mut params_info = String::new();
+                if !params.is_empty() {
+                    params_info.push_str(&format!(
+                        "params:                     {}\n",
+                        params
+                            .iter()
+                            .map(|p| p.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ));
+                }
+                if let Some(path) = params_file {
+                    params_info.push_str(&format!(
+                        "params file:                {}\n",
+                        path.to_string_lossy()
+                    ));
+                }
+
+                if let Some(mode) = plan {
+                    params_info.push_str(&format!(
+                        "plan:                       {}
+plan details:               {}
+plan format:                {}
+",
+                        mode, plan_details, plan_format
+                    ));
+                }
+
                 let output_info = if *write_results {
                     format!(
                         "
 write results:              true
 results directory:          {}
 results format:             {}
-",
+{}",
                         results_directory.to_string_lossy(),
-                        results_format
+                        results_format,
+                        table
+                            .as_ref()
+                            .map(|t| format!("results table:              {t}\n"))
+                            .unwrap_or_default(),
                     )
                 } else {
                     r#"
@@ -169,21 +405,49 @@ write results:              false
 "#
                     .to_string()
                 };
+                let output_info = match output_format {
+                    Some(format) => {
+                        format!("{output_info}output format:              {format}\n")
+                    }
+                    None => output_info,
+                };
 
                 format!(
                     r#"
 command:                    query
+read only:                  {}
+timeout:                    {}
+retries:                    {}
+on error:                   {}
+query language:             {}
 display results via pager:  {}
-benchmark:                  {}{}
-print query:                {}{}{}"#,
+benchmark:                  {}{}{}{}
+print query:                {}
+{}{}{}"#,
+                    self.read_only,
+                    timeout_info,
+                    self.retries,
+                    self.on_error(),
+                    language,
                     page_results,
                     benchmark,
                     benchmark_info.unwrap_or_default(),
+                    load_test_info.unwrap_or_default(),
+                    baseline_info,
                     print_query,
-                    output_info,
+                    params_info,
+                    output_info.trim_start_matches('\n'),
                     query_info,
                 )
             }
+            GraphQCommand::Schema { format } => format!(
+                "
+command:                    schema
+read only:                  {}
+format:                     {}
+",
+                self.read_only, format
+            ),
         };
 
         f.write_str(&output)
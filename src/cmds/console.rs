@@ -1,4 +1,4 @@
-use crate::repository::{DbClientError, get_db_client};
+use crate::repository::{ConnectionSettings, DbClientError, get_db_client};
 use crate::utils::get_pager;
 use crate::view::{Console, ConsoleConfig};
 use anyhow::Context;
@@ -11,8 +11,11 @@ pub enum ConsoleCmdError {
     Uncategorised(#[from] anyhow::Error),
 }
 
-pub async fn handle_console_cmd(config: ConsoleConfig) -> Result<(), ConsoleCmdError> {
-    let db_client = get_db_client().await?;
+pub async fn handle_console_cmd(
+    config: ConsoleConfig,
+    connection_settings: ConnectionSettings,
+) -> Result<(), ConsoleCmdError> {
+    let db_client = get_db_client(connection_settings).await?;
     db_client.verify_connectivity().await?;
 
     if let Some(parent) = config.history_file_path.parent() {
@@ -0,0 +1,484 @@
+use super::{ExecutionSettings, QueryCmdError};
+use crate::domain::{
+    BenchmarkNumRuns, BenchmarkStats, LoadTestConcurrency, LoadTestDuration, LoadTestRate,
+    LoadTestSample, LoadTestSummary, QueryLanguage, QueryParams, QueryTimeout,
+};
+use crate::repository::QueryExecutor;
+use crate::service::{InterruptedQuery, Interruption, cancel_on_server};
+use crate::view::{format_latency, get_histogram, get_latency_statistics};
+use colored::Colorize;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::Duration;
+use tokio::sync::{Mutex, mpsc};
+use tokio::task::JoinSet;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+/// How queries are run when a benchmark is a load test.
+pub struct LoadTestSettings {
+    pub concurrency: LoadTestConcurrency,
+    /// Runs queries for this long; the benchmark's number of runs is used otherwise
+    pub duration: Option<LoadTestDuration>,
+    pub rate: Option<LoadTestRate>,
+}
+
+/// Runs a query from several tasks at once, all sharing the same client, and prints a summary of
+/// every second of the test, followed by one for the whole test.
+///
+/// Queries aren't retried, so that failures show up in the error rate.
+pub(super) async fn load_test_query<D: QueryExecutor + Send + 'static>(
+    db_client: Arc<D>,
+    language: &QueryLanguage,
+    query: &str,
+    params: &QueryParams,
+    num_runs: BenchmarkNumRuns,
+    load_test: LoadTestSettings,
+    settings: &ExecutionSettings,
+) -> Result<(), QueryCmdError> {
+    let limit = match load_test.duration {
+        Some(duration) => format!("for {duration}"),
+        None => format!("{} queries", num_runs.value()),
+    };
+    let rate = load_test
+        .rate
+        .map(|r| format!(", at {r}"))
+        .unwrap_or_default();
+    println!(
+        "{}\n",
+        format!(
+            "Load testing ({limit}, {} concurrent tasks{rate}) ...",
+            load_test.concurrency
+        )
+        .yellow()
+        .bold()
+    );
+
+    let start = Instant::now();
+    let deadline = load_test.duration.map(|d| start + d.value());
+    // without a duration, tasks stop once the number of runs has been shared out between them
+    let remaining = match load_test.duration {
+        Some(_) => None,
+        None => Some(Arc::new(AtomicU32::new(u32::from(num_runs.value())))),
+    };
+    let pacer = load_test.rate.map(|r| {
+        let mut interval = tokio::time::interval(r.interval());
+        interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Arc::new(Mutex::new(interval))
+    });
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let mut workers = JoinSet::new();
+    for _ in 0..load_test.concurrency.value() {
+        let worker = Worker {
+            db_client: Arc::clone(&db_client),
+            language: language.clone(),
+            query: query.to_string(),
+            params: params.clone(),
+            timeout: settings.timeout,
+            deadline,
+            remaining: remaining.clone(),
+            pacer: pacer.clone(),
+        };
+        workers.spawn(worker.run(tx.clone()));
+    }
+    drop(tx);
+
+    print_window_header();
+    let mut samples: Vec<LoadTestSample> = vec![];
+    let mut window_start = 0;
+    let mut second = 0;
+    let mut ticker =
+        tokio::time::interval_at(start + Duration::from_secs(1), Duration::from_secs(1));
+    let interrupted = loop {
+        tokio::select! {
+            sample = rx.recv() => match sample {
+                Some(sample) => samples.push(sample),
+                None => break false,
+            },
+            _ = ticker.tick() => {
+                second += 1;
+                let window = LoadTestSummary::new(&samples[window_start..], Duration::from_secs(1));
+                print_window(&format!("{second}s"), &window);
+                window_start = samples.len();
+            },
+            Ok(()) = tokio::signal::ctrl_c() => {
+                workers.abort_all();
+                break true;
+            },
+        }
+    };
+    let elapsed = start.elapsed();
+
+    let partial_window = elapsed.saturating_sub(Duration::from_secs(second));
+    if window_start < samples.len() && !partial_window.is_zero() {
+        let window = LoadTestSummary::new(&samples[window_start..], partial_window);
+        print_window(&format!("{:.1}s", elapsed.as_secs_f64()), &window);
+    }
+
+    print_summary(&samples, elapsed);
+
+    if interrupted {
+        return Err(QueryCmdError::QueryInterrupted(InterruptedQuery {
+            reason: Interruption::CtrlC,
+            server_cancellation: cancel_on_server(db_client.as_ref(), language, query, elapsed)
+                .await,
+        }));
+    }
+
+    Ok(())
+}
+
+struct Worker<D> {
+    db_client: Arc<D>,
+    language: QueryLanguage,
+    query: String,
+    params: QueryParams,
+    timeout: Option<QueryTimeout>,
+    deadline: Option<Instant>,
+    remaining: Option<Arc<AtomicU32>>,
+    pacer: Option<Arc<Mutex<Interval>>>,
+}
+
+impl<D: QueryExecutor> Worker<D> {
+    async fn run(self, tx: mpsc::UnboundedSender<LoadTestSample>) {
+        loop {
+            if self.is_done() {
+                break;
+            }
+            if let Some(remaining) = &self.remaining
+                && remaining
+                    .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                    .is_err()
+            {
+                break;
+            }
+            if let Some(pacer) = &self.pacer {
+                pacer.lock().await.tick().await;
+                if self.is_done() {
+                    break;
+                }
+            }
+
+            let query_start = Instant::now();
+            let execution = self
+                .db_client
+                .execute(&self.language, &self.query, &self.params);
+            let result = match self.timeout {
+                Some(timeout) => tokio::time::timeout(timeout.value(), execution)
+                    .await
+                    .unwrap_or_else(|_| Err(anyhow::anyhow!(Interruption::TimedOut(timeout)))),
+                None => execution.await,
+            };
+
+            let sample = LoadTestSample {
+                latency: query_start.elapsed(),
+                error: result.err().map(|e| format!("{e:#}")),
+            };
+            if tx.send(sample).is_err() {
+                break;
+            }
+        }
+    }
+
+    fn is_done(&self) -> bool {
+        self.deadline.is_some_and(|d| Instant::now() >= d)
+    }
+}
+
+fn print_window_header() {
+    println!(
+        "{}",
+        format!(
+            "{:>6}  {:>8}  {:>9}  {:>7}  {:>11}  {:>11}  {:>11}",
+            "time", "queries", "qps", "errors", "p50", "p95", "p99"
+        )
+        .bold()
+    );
+}
+
+fn print_window(time: &str, window: &LoadTestSummary) {
+    let percentile = |f: fn(&BenchmarkStats) -> Duration| {
+        window
+            .latency
+            .as_ref()
+            .map(|l| format_latency(f(l)))
+            .unwrap_or_else(|| "-".to_string())
+    };
+
+    let errors = format!("{:>7}", window.num_errors);
+    println!(
+        "{:>6}  {:>8}  {:>9.1}  {}  {:>11}  {:>11}  {:>11}",
+        time,
+        window.num_queries,
+        window.throughput(),
+        if window.num_errors > 0 {
+            errors.red()
+        } else {
+            errors.normal()
+        },
+        percentile(|l| l.median),
+        percentile(|l| l.p95),
+        percentile(|l| l.p99),
+    );
+}
+
+fn print_summary(samples: &[LoadTestSample], elapsed: Duration) {
+    let summary = LoadTestSummary::new(samples, elapsed);
+
+    print!(
+        "
+{}
+queries:      {}
+duration:     {}
+throughput:   {}
+errors:       {}
+",
+        "Statistics:".yellow().bold(),
+        summary.num_queries.to_string().cyan(),
+        format!("{:.3}s", elapsed.as_secs_f64()).cyan(),
+        format!("{:.1} qps", summary.throughput()).cyan(),
+        format!(
+            "{} ({:.2}%)",
+            summary.num_errors,
+            summary.error_rate() * 100.0
+        )
+        .cyan(),
+    );
+
+    if let Some(latency) = &summary.latency {
+        let times: Vec<Duration> = samples
+            .iter()
+            .filter(|s| s.error.is_none())
+            .map(|s| s.latency)
+            .collect();
+
+        print!(
+            "{}
+
+{}
+{}
+",
+            get_latency_statistics(latency),
+            "Distribution:".yellow().bold(),
+            get_histogram(&times),
+        );
+    }
+
+    let mut errors: HashMap<&str, usize> = HashMap::new();
+    for error in samples.iter().filter_map(|s| s.error.as_deref()) {
+        *errors.entry(error).or_default() += 1;
+    }
+    if !errors.is_empty() {
+        let mut errors: Vec<_> = errors.into_iter().collect();
+        errors.sort_by(|(a_error, a_count), (b_error, b_count)| {
+            b_count.cmp(a_count).then(a_error.cmp(b_error))
+        });
+
+        println!("\n{}", "Errors:".yellow().bold());
+        for (error, count) in errors {
+            println!("{:>6}  {}", count.to_string().red(), error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        OnError, PlanMode, QueryPlan, QueryResults, RetryPolicy, RowSink, Schema, SchemaNames,
+    };
+    use std::str::FromStr;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Takes a while to run each query, and keeps track of how many run at once.
+    #[derive(Default)]
+    struct SlowExecutor {
+        fail: bool,
+        calls: AtomicUsize,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    impl QueryExecutor for SlowExecutor {
+        async fn execute_query(
+            &self,
+            query: &str,
+            params: &QueryParams,
+        ) -> anyhow::Result<QueryResults> {
+            let mut rows = vec![];
+            self.stream_query(query, params, &mut rows).await?;
+            Ok(rows.into())
+        }
+
+        async fn stream_query<S: RowSink>(
+            &self,
+            _query: &str,
+            _params: &QueryParams,
+            sink: &mut S,
+        ) -> anyhow::Result<u64> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            if self.fail {
+                anyhow::bail!("connection reset");
+            }
+            sink.write_row(serde_json::json!({"n": 1}))?;
+            Ok(1)
+        }
+
+        async fn execute_gremlin_query(&self, _query: &str) -> anyhow::Result<QueryResults> {
+            unimplemented!()
+        }
+
+        async fn explain_query(
+            &self,
+            _query: &str,
+            _params: &QueryParams,
+            _mode: PlanMode,
+            _details: bool,
+        ) -> anyhow::Result<QueryPlan> {
+            unimplemented!()
+        }
+
+        async fn begin_transaction(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn commit_transaction(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn rollback_transaction(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn in_transaction(&self) -> bool {
+            false
+        }
+
+        async fn cancel_query(
+            &self,
+            _language: &QueryLanguage,
+            _query: &str,
+            _running_for: Duration,
+        ) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+
+        async fn fetch_schema_names(&self) -> anyhow::Result<SchemaNames> {
+            unimplemented!()
+        }
+
+        async fn fetch_schema(&self) -> anyhow::Result<Schema> {
+            unimplemented!()
+        }
+
+        fn db_uri(&self) -> String {
+            "bolt://127.0.0.1:7687".to_string()
+        }
+    }
+
+    fn worker(db_client: &Arc<SlowExecutor>, num_runs: u32) -> Worker<SlowExecutor> {
+        Worker {
+            db_client: Arc::clone(db_client),
+            language: QueryLanguage::Cypher,
+            query: "MATCH (n) RETURN count(n)".to_string(),
+            params: QueryParams::default(),
+            timeout: None,
+            deadline: None,
+            remaining: Some(Arc::new(AtomicU32::new(num_runs))),
+            pacer: None,
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[tokio::test]
+    async fn load_test_runs_queries_from_all_tasks_at_once() -> anyhow::Result<()> {
+        // GIVEN
+        let db_client = Arc::new(SlowExecutor::default());
+        let load_test = LoadTestSettings {
+            concurrency: LoadTestConcurrency::from_str("4").map_err(anyhow::Error::msg)?,
+            duration: None,
+            rate: None,
+        };
+        let settings = ExecutionSettings {
+            timeout: None,
+            retry_policy: RetryPolicy::default(),
+            on_error: OnError::default(),
+        };
+
+        // WHEN
+        load_test_query(
+            Arc::clone(&db_client),
+            &QueryLanguage::Cypher,
+            "MATCH (n) RETURN count(n)",
+            &QueryParams::default(),
+            BenchmarkNumRuns::from_str("12").map_err(anyhow::Error::msg)?,
+            load_test,
+            &settings,
+        )
+        .await?;
+
+        // THEN
+        assert_eq!(db_client.calls.load(Ordering::SeqCst), 12);
+        assert_eq!(db_client.max_running.load(Ordering::SeqCst), 4);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn worker_sends_a_sample_per_query() {
+        // GIVEN
+        let db_client = Arc::new(SlowExecutor::default());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        // WHEN
+        worker(&db_client, 3).run(tx).await;
+
+        // THEN
+        let mut samples = vec![];
+        while let Some(sample) = rx.recv().await {
+            samples.push(sample);
+        }
+        assert_eq!(samples.len(), 3);
+        assert!(samples.iter().all(|s| s.error.is_none()));
+        assert!(
+            samples
+                .iter()
+                .all(|s| s.latency >= Duration::from_millis(20))
+        );
+    }
+
+    #[tokio::test]
+    async fn worker_reports_failed_queries_as_errors() {
+        // GIVEN
+        let db_client = Arc::new(SlowExecutor {
+            fail: true,
+            ..SlowExecutor::default()
+        });
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        // WHEN
+        worker(&db_client, 2).run(tx).await;
+
+        // THEN
+        let mut errors = vec![];
+        while let Some(sample) = rx.recv().await {
+            errors.push(sample.error);
+        }
+        assert_eq!(
+            errors,
+            [
+                Some("connection reset".to_string()),
+                Some("connection reset".to_string())
+            ]
+        );
+    }
+}
@@ -1,5 +1,9 @@
 mod console;
+mod load_test;
 mod query;
+mod schema;
 
 pub use console::*;
+pub use load_test::*;
 pub use query::*;
+pub use schema::*;
@@ -1,66 +1,361 @@
-use crate::domain::{BenchmarkNumRuns, QueryResults, ResultsFormat};
-use crate::repository::{DbClient, DbClientError, QueryExecutor, get_db_client};
+use super::{LoadTestSettings, load_test_query};
+use crate::cypher::{Statement, split_script};
+use crate::domain::{
+    BaselineComparison, BaselineName, BenchmarkNumRuns, BenchmarkReport, BenchmarkReportFormat,
+    BenchmarkStats, OnError, OutputFormat, Pager, ParamSetOrder, ParamSets, PlanFormat, PlanMode,
+    QueryLanguage, QueryParam, QueryParams, QueryTimeout, ResultsFormat, RetryPolicy, RowSink,
+    SlowdownThreshold, TableName,
+};
+use crate::repository::{
+    ConnectionSettings, DbClient, DbClientError, QueryExecutor, get_db_client,
+};
+use crate::service::{
+    BenchmarkSettings, InterruptedQuery, Interruption, PagedResults, QueryOutcome, ResultsFile,
+    Retry, read_baseline, run_benchmark, run_interruptible, save_baseline, stream_with_retries,
+    warm_up, write_benchmark_report,
+};
 use crate::utils::get_pager;
-use crate::view::get_results;
+use crate::view::{
+    OutputWriter, get_baseline_comparison, get_benchmark_summary, get_plan_tree,
+    print_benchmark_event,
+};
 use anyhow::Context;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
 use std::io::Read;
 use std::path::PathBuf;
-use std::time::Instant;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub enum QueryBehaviour {
+    Plan {
+        mode: PlanMode,
+        details: bool,
+        format: PlanFormat,
+    },
     Benchmark {
         num_runs: BenchmarkNumRuns,
         warmup_runs: u16,
+        report: Option<BenchmarkReportOutput>,
+        load_test: Option<LoadTestSettings>,
+        baseline: BaselineSettings,
+        param_sets: Option<ParamSetsInput>,
     },
     Normal {
         page_results: bool,
         write_results: bool,
         results_directory: PathBuf,
         results_format: ResultsFormat,
+        table: Option<TableName>,
+        output_format: OutputFormat,
     },
 }
 
+/// Where to write a benchmark's report, if at all.
+pub struct BenchmarkReportOutput {
+    pub results_directory: PathBuf,
+    pub format: BenchmarkReportFormat,
+}
+
+/// Baselines to save a benchmark as, and to compare it with.
+pub struct BaselineSettings {
+    pub directory: PathBuf,
+    pub save: Option<BaselineName>,
+    pub compare: Option<BaselineName>,
+    /// Fails the benchmark if it's significantly slower than the compared baseline by more than this
+    pub fail_if_slower: Option<SlowdownThreshold>,
+}
+
+/// A file of parameter sets (CSV or JSON lines) for benchmark runs to go through.
+pub struct ParamSetsInput {
+    pub path: PathBuf,
+    pub order: ParamSetOrder,
+}
+
+impl ParamSetsInput {
+    /// The parameters passed individually, or via the params file, are bound to every set as well.
+    fn resolve(&self, base: &QueryParams) -> anyhow::Result<ParamSets> {
+        let path = self.path.to_string_lossy();
+        let contents = std::fs::read_to_string(&self.path)
+            .with_context(|| format!(r#"couldn't read parameter sets file "{path}""#))?;
+
+        let param_sets = match self.path.extension().and_then(|e| e.to_str()) {
+            Some("csv") => ParamSets::from_csv(&contents, self.order),
+            Some("jsonl") | Some("ndjson") => ParamSets::from_jsonl(&contents, self.order),
+            _ => anyhow::bail!(
+                r#"parameter sets file "{path}" needs to have a .csv or .jsonl extension"#
+            ),
+        }
+        .with_context(|| format!(r#"parameter sets file "{path}" is invalid"#))?;
+
+        Ok(param_sets.with_base(base))
+    }
+}
+
+pub struct QueryParamsInput {
+    pub params: Vec<QueryParam>,
+    pub params_file: Option<PathBuf>,
+}
+
+impl QueryParamsInput {
+    /// Parameters from the params file are overridden by the ones passed individually.
+    fn resolve(self) -> anyhow::Result<QueryParams> {
+        let mut query_params = match &self.params_file {
+            Some(path) => {
+                let contents = std::fs::read_to_string(path).with_context(|| {
+                    format!(r#"couldn't read params file "{}""#, path.to_string_lossy())
+                })?;
+
+                QueryParams::from_json(&contents)
+                    .map_err(|e| anyhow::anyhow!(e))
+                    .with_context(|| {
+                        format!(r#"params file "{}" is invalid"#, path.to_string_lossy())
+                    })?
+            }
+            None => QueryParams::default(),
+        };
+
+        for param in self.params {
+            query_params.insert(param);
+        }
+
+        Ok(query_params)
+    }
+}
+
+/// Where the query to execute comes from.
+pub enum QuerySource {
+    Argument(String),
+    Stdin,
+    File(PathBuf),
+}
+
+impl QuerySource {
+    fn read(self) -> anyhow::Result<String> {
+        match self {
+            QuerySource::Argument(query) => Ok(query),
+            QuerySource::Stdin => {
+                let mut buffer = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut buffer)
+                    .context("couldn't read query from stdin")?;
+                Ok(buffer.trim().to_string())
+            }
+            QuerySource::File(path) => {
+                let contents = std::fs::read_to_string(&path).with_context(|| {
+                    format!(r#"couldn't read query file "{}""#, path.to_string_lossy())
+                })?;
+                Ok(contents.trim().to_string())
+            }
+        }
+    }
+}
+
+/// Limits on how queries are executed.
+pub struct ExecutionSettings {
+    pub timeout: Option<QueryTimeout>,
+    pub retry_policy: RetryPolicy,
+    pub on_error: OnError,
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum QueryCmdError {
     #[error("couldn't build db client")]
     CouldntBuildDbClient(#[from] DbClientError),
+    #[error("query doesn't contain any statements")]
+    NoStatements,
+    #[error("{action} only works with a single statement, but the query has {found}")]
+    SingleStatementRequired { action: &'static str, found: usize },
+    #[error("{}; {}", .0.reason, .0.server_cancellation_summary())]
+    QueryInterrupted(InterruptedQuery),
+    #[error("statement {number}/{total} (line {line}) failed")]
+    StatementFailed {
+        number: usize,
+        total: usize,
+        line: usize,
+        #[source]
+        source: Box<QueryCmdError>,
+    },
+    #[error("{failed} of {total} statements failed")]
+    StatementsFailed { failed: usize, total: usize },
+    #[error(
+        r#"benchmark regressed: median latency is {:.2}% higher than in baseline "{baseline}" (threshold: {threshold})"#,
+        change * 100.0
+    )]
+    BenchmarkRegressed {
+        baseline: String,
+        change: f64,
+        threshold: SlowdownThreshold,
+    },
     #[error(transparent)]
     Uncategorised(#[from] anyhow::Error),
 }
 
 pub async fn handle_query_cmd(
-    query: String,
+    connection_settings: ConnectionSettings,
+    source: QuerySource,
+    language: QueryLanguage,
+    params_input: QueryParamsInput,
     behaviour: QueryBehaviour,
     print_query: bool,
+    settings: ExecutionSettings,
 ) -> Result<(), QueryCmdError> {
-    let db_client = get_db_client().await?;
-
-    let query = if query == "-" {
-        let mut buffer = String::new();
-        std::io::stdin()
-            .read_to_string(&mut buffer)
-            .context("couldn't read query from stdin")?;
-        buffer.trim().to_string()
-    } else {
-        query
-    };
+    let params = params_input.resolve()?;
+    let db_client = get_db_client(connection_settings).await?;
+
+    let query = source.read()?;
+    let statements = split_script(&query, &language);
+    if statements.is_empty() {
+        return Err(QueryCmdError::NoStatements);
+    }
 
     if print_query {
-        println!(
+        let header = format!(
             r#"---
 {query}
 ---
 "#
         );
+        // like status messages, this mustn't get mixed up with results printed in other formats
+        match &behaviour {
+            QueryBehaviour::Normal { output_format, .. }
+                if *output_format != OutputFormat::Table =>
+            {
+                eprintln!("{header}")
+            }
+            _ => println!("{header}"),
+        }
     }
     match behaviour {
+        QueryBehaviour::Plan {
+            mode,
+            details,
+            format,
+        } => {
+            let query = single_statement(&statements, "showing a query plan")?;
+            let plan = match run_interruptible(
+                &db_client,
+                &language,
+                query,
+                settings.timeout,
+                db_client.explain_query(query, &params, mode, details),
+            )
+            .await
+            {
+                QueryOutcome::Finished(plan) => plan?,
+                QueryOutcome::Interrupted(i) => return Err(QueryCmdError::QueryInterrupted(i)),
+            };
+
+            match format {
+                PlanFormat::Tree => println!("{}", get_plan_tree(&plan)),
+                PlanFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&plan)
+                        .context("couldn't serialize query plan to JSON")?
+                ),
+            }
+        }
         QueryBehaviour::Benchmark {
             num_runs,
             warmup_runs,
+            report: report_output,
+            load_test,
+            baseline,
+            param_sets,
         } => {
-            benchmark_query(&db_client, &query, num_runs, warmup_runs).await?;
+            let query = single_statement(&statements, "benchmarking")?;
+            let param_sets = match param_sets {
+                Some(input) => input.resolve(&params)?,
+                None => ParamSets::single(params.clone()),
+            };
+
+            let benchmark_settings = BenchmarkSettings {
+                num_runs,
+                num_warmup_runs: warmup_runs,
+                timeout: settings.timeout,
+                retry_policy: settings.retry_policy,
+            };
+
+            if let Some(load_test) = load_test {
+                if let Some(interruption) = warm_up(
+                    &db_client,
+                    &language,
+                    query,
+                    &param_sets,
+                    &benchmark_settings,
+                    |e| print_benchmark_event(&e),
+                )
+                .await?
+                {
+                    return Err(QueryCmdError::QueryInterrupted(interruption));
+                }
+                return load_test_query(
+                    Arc::new(db_client),
+                    &language,
+                    query,
+                    &params,
+                    num_runs,
+                    load_test,
+                    &settings,
+                )
+                .await;
+            }
+
+            // a missing baseline should be reported before spending time on the benchmark
+            let compared_baseline = baseline
+                .compare
+                .as_ref()
+                .map(|name| read_baseline(&baseline.directory, name).map(|b| (name, b)))
+                .transpose()?;
+
+            let report = benchmark_query(
+                &db_client,
+                &language,
+                query,
+                &param_sets,
+                &benchmark_settings,
+            )
+            .await?;
+
+            if let Some(output) = report_output {
+                let report_path = write_benchmark_report(
+                    &report,
+                    &output.results_directory,
+                    &output.format,
+                    Utc::now(),
+                )?;
+                println!(
+                    "\n{}",
+                    format!(
+                        "Wrote benchmark report to {}",
+                        report_path.to_string_lossy()
+                    )
+                    .yellow()
+                );
+            }
+
+            let regression = match compared_baseline {
+                Some((name, compared)) => {
+                    compare_with_baseline(name, &compared, &report, baseline.fail_if_slower)
+                }
+                None => None,
+            };
+
+            if let Some(name) = &baseline.save {
+                let baseline_path = save_baseline(&report, &baseline.directory, name)?;
+                println!(
+                    "\n{}",
+                    format!(
+                        r#"Saved benchmark as baseline "{name}" to {}"#,
+                        baseline_path.to_string_lossy()
+                    )
+                    .yellow()
+                );
+            }
+
+            if let Some(err) = regression {
+                return Err(err);
+            }
         }
 
         QueryBehaviour::Normal {
@@ -68,119 +363,355 @@ pub async fn handle_query_cmd(
             write_results,
             results_directory,
             results_format,
+            table,
+            output_format,
         } => {
             let pager = if page_results {
                 Some(get_pager()?)
             } else {
                 None
             };
-
-            let results = db_client.execute_query(&query).await?;
-            let results = match results {
-                QueryResults::Empty => {
-                    println!("No results");
-                    return Ok(());
-                }
-                QueryResults::NonEmpty(res) => res,
+            let output = ResultsOutput {
+                pager: pager.as_ref(),
+                write_results,
+                results_directory: &results_directory,
+                results_format: &results_format,
+                table: table.as_ref(),
+                output_format,
+                reference_time: Utc::now(),
             };
 
-            if write_results {
-                let results_file_path = crate::service::write_results(
-                    &results,
-                    &results_directory,
-                    &results_format,
-                    Utc::now(),
+            if let [statement] = statements.as_slice() {
+                let streamed = run_statement(
+                    &db_client,
+                    &language,
+                    statement.text,
+                    &params,
+                    &settings,
+                    &output,
+                    None,
                 )
-                .context("couldn't write results")?;
-                println!("Wrote results to {}", results_file_path.to_string_lossy());
+                .await?;
 
-                if let Some(pager) = pager {
-                    crate::service::page_results(&results_file_path, &pager)?;
+                if streamed.num_rows == 0 {
+                    output.print_status("No results");
+                    return Ok(());
                 }
-            } else if let Some(pager) = pager {
-                let temp_results_directory = tempfile::tempdir()
-                    .context("couldn't create temporary directory for paging results")?;
-                let results_file_path = crate::service::write_results(
-                    &results,
-                    &temp_results_directory,
-                    &results_format,
-                    Utc::now(),
-                )
-                .context("couldn't write results to temporary location")?;
 
-                crate::service::page_results(&results_file_path, &pager)?;
-            } else {
-                let results_str = get_results(&results);
-                println!("{}", results_str);
+                if let Some(path) = &streamed.results_path {
+                    println!("Wrote results to {}", path.to_string_lossy());
+                }
+
+                return Ok(());
             }
+
+            run_statements(
+                &db_client,
+                &language,
+                &statements,
+                &params,
+                &settings,
+                &output,
+            )
+            .await?;
         }
     }
 
     Ok(())
 }
 
-async fn benchmark_query(
+fn single_statement<'a>(
+    statements: &[Statement<'a>],
+    action: &'static str,
+) -> Result<&'a str, QueryCmdError> {
+    match statements {
+        [statement] => Ok(statement.text),
+        _ => Err(QueryCmdError::SingleStatementRequired {
+            action,
+            found: statements.len(),
+        }),
+    }
+}
+
+/// Where the results of statements go.
+struct ResultsOutput<'a> {
+    pager: Option<&'a Pager>,
+    write_results: bool,
+    results_directory: &'a PathBuf,
+    results_format: &'a ResultsFormat,
+    table: Option<&'a TableName>,
+    output_format: OutputFormat,
+    reference_time: DateTime<Utc>,
+}
+
+impl ResultsOutput<'_> {
+    /// Status messages go to stderr when results are printed to stdout in a format other than a
+    /// table, so that they don't get mixed up with the results.
+    fn print_status(&self, message: impl std::fmt::Display) {
+        if self.output_format == OutputFormat::Table {
+            println!("{message}");
+        } else {
+            eprintln!("{message}");
+        }
+    }
+}
+
+struct StatementResults {
+    num_rows: u64,
+    results_path: Option<PathBuf>,
+}
+
+/// Runs statements in order, each with its own results. Interrupting a statement via Ctrl+C stops
+/// the remaining ones from running, regardless of the error policy.
+async fn run_statements(
     db_client: &DbClient,
-    query: &str,
-    num_runs: BenchmarkNumRuns,
-    num_warmup_runs: u16,
-) -> anyhow::Result<()> {
-    if num_warmup_runs > 0 {
-        println!(
-            "{}",
-            format!("Warming up ({num_warmup_runs} runs) ...")
+    language: &QueryLanguage,
+    statements: &[Statement<'_>],
+    params: &QueryParams,
+    settings: &ExecutionSettings,
+    output: &ResultsOutput<'_>,
+) -> Result<(), QueryCmdError> {
+    let total = statements.len();
+    let mut failed = 0;
+
+    for (i, statement) in statements.iter().enumerate() {
+        let number = i + 1;
+        if i > 0 {
+            output.print_status("");
+        }
+        output.print_status(
+            format!("Statement {number}/{total} (line {})", statement.line)
                 .yellow()
-                .bold()
+                .bold(),
         );
-    }
-    for i in 0..num_warmup_runs {
+
         let start = Instant::now();
-        db_client
-            .execute_query(query)
-            .await
-            .with_context(|| format!("couldn't get results for warmup run #{}", i + 1))?;
-        let elapsed = start.elapsed().as_millis();
-        println!("run {:03}:      {}", i + 1, format!("{}ms", elapsed).cyan(),);
+        let result = run_statement(
+            db_client,
+            language,
+            statement.text,
+            params,
+            settings,
+            output,
+            Some(number.to_string()),
+        )
+        .await;
+        let elapsed = start.elapsed();
+
+        let err = match result {
+            Ok(streamed) => {
+                if streamed.num_rows == 0 {
+                    output.print_status("No results");
+                }
+                if let Some(path) = &streamed.results_path {
+                    println!("Wrote results to {}", path.to_string_lossy());
+                }
+                let rows = match streamed.num_rows {
+                    1 => "1 row".to_string(),
+                    n => format!("{n} rows"),
+                };
+                output.print_status(format!("{rows}, took {} ms", elapsed.as_millis()).cyan());
+                continue;
+            }
+            Err(e) => e,
+        };
+
+        let cancelled =
+            matches!(&err, QueryCmdError::QueryInterrupted(i) if i.reason == Interruption::CtrlC);
+        if cancelled || settings.on_error == OnError::Stop {
+            return Err(QueryCmdError::StatementFailed {
+                number,
+                total,
+                line: statement.line,
+                source: Box::new(err),
+            });
+        }
+
+        eprintln!("{}", format!("Error: {:#}", anyhow::Error::from(err)).red());
+        failed += 1;
     }
 
-    if num_warmup_runs > 0 {
-        println!();
+    if failed > 0 {
+        return Err(QueryCmdError::StatementsFailed { failed, total });
     }
 
-    println!(
+    Ok(())
+}
+
+/// Streams a statement's results to the pager, a results file, or stdout (in the output format).
+async fn run_statement(
+    db_client: &DbClient,
+    language: &QueryLanguage,
+    query: &str,
+    params: &QueryParams,
+    settings: &ExecutionSettings,
+    output: &ResultsOutput<'_>,
+    results_file_suffix: Option<String>,
+) -> Result<StatementResults, QueryCmdError> {
+    let mut results_file = output.write_results.then(|| {
+        let file = ResultsFile::new(
+            output.results_directory,
+            output.results_format,
+            output.reference_time,
+        )
+        .with_query(query)
+        .with_db_uri(db_client.db_uri())
+        .with_table(output.table.cloned());
+        match &results_file_suffix {
+            Some(suffix) => file.with_name_suffix(suffix.as_str()),
+            None => file,
+        }
+    });
+    let mut paged_results = output
+        .pager
+        .map(|p| PagedResults::new(p, output.results_format));
+    let mut stdout = (!output.write_results && output.pager.is_none())
+        .then(|| OutputWriter::new(output.output_format, std::io::stdout().lock()));
+
+    let mut sink = (&mut results_file, (&mut paged_results, &mut stdout));
+    let outcome = run_interruptible(
+        db_client,
+        language,
+        query,
+        settings.timeout,
+        stream_with_retries(
+            db_client,
+            language,
+            query,
+            params,
+            &mut sink,
+            settings.retry_policy,
+            print_retry,
+        ),
+    )
+    .await;
+    let mut finished = sink.finish();
+    if let Some(paged_results) = paged_results.as_mut() {
+        finished = finished.and(paged_results.wait().await);
+    }
+    let num_rows = match outcome {
+        QueryOutcome::Finished(streamed) => streamed?.value,
+        QueryOutcome::Interrupted(i) => return Err(QueryCmdError::QueryInterrupted(i)),
+    };
+    finished.context("couldn't write results")?;
+
+    Ok(StatementResults {
+        num_rows,
+        results_path: results_file
+            .as_ref()
+            .and_then(|f| f.path())
+            .map(|p| p.to_path_buf()),
+    })
+}
+
+async fn benchmark_query(
+    db_client: &DbClient,
+    language: &QueryLanguage,
+    query: &str,
+    params: &ParamSets,
+    settings: &BenchmarkSettings,
+) -> Result<BenchmarkReport, QueryCmdError> {
+    let benchmark = run_benchmark(db_client, language, query, params, settings, |e| {
+        print_benchmark_event(&e)
+    })
+    .await?;
+
+    let times: Vec<Duration> = benchmark.runs.iter().map(|r| r.latency).collect();
+    let statistics = BenchmarkStats::new(&times);
+
+    if let Some(interruption) = benchmark.interruption {
+        if let Some(statistics) = statistics {
+            println!(
+                "\n{}",
+                format!(
+                    "Benchmark interrupted after {} of {} runs",
+                    benchmark.runs.len(),
+                    settings.num_runs
+                )
+                .yellow()
+            );
+            print!(
+                "{}",
+                get_benchmark_summary(&statistics, &benchmark.runs, params)
+            );
+        }
+        return Err(QueryCmdError::QueryInterrupted(interruption));
+    }
+
+    let statistics =
+        statistics.context("couldn't compute statistics as there were no benchmark runs")?;
+    print!(
         "{}",
-        format!("Benchmarking ({} runs) ...", num_runs.value())
-            .yellow()
-            .bold()
+        get_benchmark_summary(&statistics, &benchmark.runs, params)
     );
 
-    let mut times = vec![];
-    for i in 0..num_runs.value() {
-        let start = Instant::now();
-        db_client
-            .execute_query(query)
-            .await
-            .with_context(|| format!("couldn't execute query for benchmark run #{}", i + 1))?;
-        let elapsed = start.elapsed().as_millis();
-        println!("run {:03}:      {}", i + 1, format!("{}ms", elapsed).cyan(),);
-        times.push(elapsed);
-    }
+    Ok(BenchmarkReport {
+        query: query.to_string(),
+        language: language.to_string(),
+        backend: db_client.backend().to_string(),
+        db_uri: db_client.db_uri(),
+        started_at: benchmark.started_at.to_rfc3339(),
+        finished_at: Utc::now().to_rfc3339(),
+        num_warmup_runs: settings.num_warmup_runs,
+        param_sets: if params.is_from_file() {
+            params.to_json()
+        } else {
+            vec![]
+        },
+        statistics,
+        runs: benchmark.runs,
+    })
+}
+
+/// Prints how a benchmark compares with a baseline, and returns an error if it regressed beyond
+/// the threshold.
+fn compare_with_baseline(
+    name: &BaselineName,
+    baseline: &BenchmarkReport,
+    report: &BenchmarkReport,
+    fail_if_slower: Option<SlowdownThreshold>,
+) -> Option<QueryCmdError> {
+    println!(
+        "\n{}",
+        format!(
+            r#"Comparison with baseline "{name}" (from {}):"#,
+            baseline.started_at
+        )
+        .yellow()
+        .bold()
+    );
 
-    if let (Some(min), Some(max)) = (times.iter().min(), times.iter().max()) {
-        let mean = times.iter().sum::<u128>() / times.len() as u128;
-        print!(
-            "
-{}
-min:          {}
-max:          {}
-mean:         {}
-",
-            "Statistics:".yellow().bold(),
-            format!("{}ms", min).cyan(),
-            format!("{}ms", max).cyan(),
-            format!("{}ms", mean).cyan(),
+    let mut differences = vec![];
+    if baseline.query != report.query {
+        differences.push("query");
+    }
+    if baseline.backend != report.backend || baseline.db_uri != report.db_uri {
+        differences.push("database");
+    }
+    if !differences.is_empty() {
+        println!(
+            "{}",
+            format!(
+                "note: the baseline was recorded with a different {}",
+                differences.join(" and ")
+            )
+            .yellow()
         );
     }
 
-    Ok(())
+    let comparison = BaselineComparison::new(baseline, report);
+    println!("{}", get_baseline_comparison(&comparison));
+
+    let threshold = fail_if_slower?;
+    comparison
+        .regression(threshold)
+        .map(|change| QueryCmdError::BenchmarkRegressed {
+            baseline: name.to_string(),
+            change,
+            threshold,
+        })
+}
+
+fn print_retry(retry: &Retry) {
+    eprintln!("{}", retry.to_string().yellow());
 }
@@ -0,0 +1,31 @@
+use crate::domain::SchemaFormat;
+use crate::repository::{ConnectionSettings, DbClientError, QueryExecutor, get_db_client};
+use crate::view::get_schema_tables;
+use anyhow::Context;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SchemaCmdError {
+    #[error("couldn't build db client")]
+    CouldntBuildDbClient(#[from] DbClientError),
+    #[error(transparent)]
+    Uncategorised(#[from] anyhow::Error),
+}
+
+pub async fn handle_schema_cmd(
+    connection_settings: ConnectionSettings,
+    format: SchemaFormat,
+) -> Result<(), SchemaCmdError> {
+    let db_client = get_db_client(connection_settings).await?;
+
+    let schema = db_client.fetch_schema().await?;
+
+    match format {
+        SchemaFormat::Table => print!("{}", get_schema_tables(&schema)?),
+        SchemaFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&schema).context("couldn't serialize schema to JSON")?
+        ),
+    }
+
+    Ok(())
+}
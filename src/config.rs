@@ -1 +1,40 @@
+use crate::domain::{ConnectionProfile, ProfilesConfig};
+use std::path::{Path, PathBuf};
+
 pub const DEFAULT_RESULTS_DIR: &str = ".grafq";
+const CONFIG_FILE_NAME: &str = "config.toml";
+
+pub fn get_config_file_path(config_dir: &Path) -> PathBuf {
+    config_dir.join("grafq").join(CONFIG_FILE_NAME)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("couldn't read config file: {0}")]
+    CouldntReadFile(String, #[source] std::io::Error),
+    #[error("config file is invalid: {0}")]
+    InvalidConfig(String, #[source] Box<toml::de::Error>),
+    #[error(r#"profile "{0}" is not defined in the config file"#)]
+    ProfileNotFound(String, Vec<String>),
+}
+
+pub fn read_profile<P>(config_file_path: P, name: &str) -> Result<ConnectionProfile, ConfigError>
+where
+    P: AsRef<Path>,
+{
+    let path = config_file_path.as_ref();
+    let path_str = path.to_string_lossy().to_string();
+
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| ConfigError::CouldntReadFile(path_str.clone(), e))?;
+
+    let config: ProfilesConfig =
+        toml::from_str(&contents).map_err(|e| ConfigError::InvalidConfig(path_str, Box::new(e)))?;
+
+    config.get(name).cloned().ok_or_else(|| {
+        ConfigError::ProfileNotFound(
+            name.to_string(),
+            config.names().iter().map(|n| n.to_string()).collect(),
+        )
+    })
+}
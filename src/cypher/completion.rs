@@ -0,0 +1,189 @@
+use super::{Token, TokenKind, tokenize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Label,
+    RelationshipType,
+    PropertyKey,
+    /// Keywords and function names
+    Keyword,
+}
+
+/// What can be completed at the end of a partially typed query.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompletionContext<'a> {
+    pub kind: CompletionKind,
+    /// Byte offset of the text the completion replaces
+    pub start: usize,
+    pub prefix: &'a str,
+}
+
+/// Returns what can be completed at the end of a partially typed query, if anything.
+///
+/// Names following ":" are labels, unless they're inside "[...]", where they're relationship
+/// types; names following "." are property keys. Any other word is completed as a keyword or
+/// function. Nothing is completed inside strings, comments, or map values.
+pub fn completion_context(input: &str) -> Option<CompletionContext<'_>> {
+    let tokens = tokenize(input);
+    let last = tokens.last()?;
+
+    let (start, prefix, end) = match last.kind {
+        TokenKind::Word => (last.start, last.text, tokens.len() - 1),
+        TokenKind::Punctuation if matches!(last.text, ":" | "." | "|" | "&") => {
+            (input.len(), "", tokens.len())
+        }
+        _ => return None,
+    };
+
+    let preceding = &tokens[..end];
+    let kind = match previous_token(preceding) {
+        Some((i, p)) if p.is_punctuation(":") || follows_type(preceding, i) => {
+            match innermost_bracket(&preceding[..i]) {
+                None | Some("(") => Some(CompletionKind::Label),
+                Some("[") => Some(CompletionKind::RelationshipType),
+                _ => None,
+            }
+        }
+        Some((_, p)) if p.is_punctuation(".") => Some(CompletionKind::PropertyKey),
+        _ => None,
+    };
+
+    let kind = match kind {
+        Some(kind) => kind,
+        None if !prefix.is_empty() => CompletionKind::Keyword,
+        None => return None,
+    };
+
+    Some(CompletionContext {
+        kind,
+        start,
+        prefix,
+    })
+}
+
+/// Returns the last token (with its index) that isn't whitespace.
+fn previous_token<'t, 'a>(tokens: &'t [Token<'a>]) -> Option<(usize, &'t Token<'a>)> {
+    tokens
+        .iter()
+        .enumerate()
+        .rev()
+        .find(|(_, t)| t.kind != TokenKind::Whitespace)
+}
+
+/// Whether the "|" or "&" at `index` combines labels or relationship types, eg. (n:A&B),
+/// [:KNOWS|LIKES].
+fn follows_type(tokens: &[Token], index: usize) -> bool {
+    let mut index = index;
+    loop {
+        let token = &tokens[index];
+        if !(token.is_punctuation("|") || token.is_punctuation("&")) {
+            return false;
+        }
+
+        let Some((name_index, name)) = previous_token(&tokens[..index]) else {
+            return false;
+        };
+        if !matches!(name.kind, TokenKind::Word | TokenKind::QuotedIdentifier) {
+            return false;
+        }
+
+        match previous_token(&tokens[..name_index]) {
+            Some((_, t)) if t.is_punctuation(":") => return true,
+            Some((i, _)) => index = i,
+            None => return false,
+        }
+    }
+}
+
+fn innermost_bracket<'a>(tokens: &[Token<'a>]) -> Option<&'a str> {
+    let mut brackets = vec![];
+    for token in tokens.iter().filter(|t| t.kind == TokenKind::Punctuation) {
+        match token.text {
+            "(" | "[" | "{" => brackets.push(token.text),
+            ")" | "]" | "}" => {
+                brackets.pop();
+            }
+            _ => {}
+        }
+    }
+
+    brackets.pop()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn describe(input: &str) -> String {
+        match completion_context(input) {
+            Some(c) => format!("{:?}({}) at {}", c.kind, c.prefix, c.start),
+            None => "none".to_string(),
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn completion_context_works() {
+        // GIVEN
+        let inputs = [
+            "MATCH (n:",
+            "MATCH (n:Per",
+            "MATCH (n:Person)-[:",
+            "MATCH (n:Person)-[r:ACTED_IN|DIR",
+            "MATCH (n:Person:Actor&",
+            "MATCH (n) WHERE n:Act",
+            "MATCH (n) RETURN n.",
+            "MATCH (n) RETURN n.na",
+            "MATCH (n) RET",
+            "mat",
+            "MATCH (n) WHERE n.name = toL",
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| format!("{i} -> {}", describe(i)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @"
+        MATCH (n: -> Label() at 9
+        MATCH (n:Per -> Label(Per) at 9
+        MATCH (n:Person)-[: -> RelationshipType() at 19
+        MATCH (n:Person)-[r:ACTED_IN|DIR -> RelationshipType(DIR) at 29
+        MATCH (n:Person:Actor& -> Label() at 22
+        MATCH (n) WHERE n:Act -> Label(Act) at 18
+        MATCH (n) RETURN n. -> PropertyKey() at 19
+        MATCH (n) RETURN n.na -> PropertyKey(na) at 19
+        MATCH (n) RET -> Keyword(RET) at 10
+        mat -> Keyword(mat) at 0
+        MATCH (n) WHERE n.name = toL -> Keyword(toL) at 25
+        ");
+    }
+
+    #[test]
+    fn completion_context_is_empty_where_nothing_can_be_completed() {
+        // GIVEN
+        let inputs = [
+            "",
+            "MATCH (n) ",
+            "MATCH (n {name:",
+            "RETURN 'MAT",
+            "// MAT",
+            "RETURN $na",
+            "RETURN [x IN range(1, 3) | ",
+            "MATCH (n) RETURN 1",
+        ];
+
+        // WHEN
+        let result: Vec<String> = inputs.iter().map(|i| describe(i)).collect();
+
+        // THEN
+        assert!(result.iter().all(|r| r == "none"), "{result:?}");
+    }
+}
@@ -0,0 +1,122 @@
+/// Cypher's reserved words and the keywords that make up its clauses (in uppercase).
+pub const KEYWORDS: [&str; 61] = [
+    "ALL",
+    "AND",
+    "AS",
+    "ASC",
+    "ASCENDING",
+    "BY",
+    "CALL",
+    "CASE",
+    "CONSTRAINT",
+    "CONTAINS",
+    "CREATE",
+    "CSV",
+    "DATABASE",
+    "DELETE",
+    "DESC",
+    "DESCENDING",
+    "DETACH",
+    "DISTINCT",
+    "DROP",
+    "ELSE",
+    "END",
+    "ENDS",
+    "EXISTS",
+    "EXPLAIN",
+    "FALSE",
+    "FOR",
+    "FOREACH",
+    "FROM",
+    "HEADERS",
+    "IF",
+    "IN",
+    "INDEX",
+    "IS",
+    "LIMIT",
+    "LOAD",
+    "MATCH",
+    "MERGE",
+    "NOT",
+    "NULL",
+    "ON",
+    "OPTIONAL",
+    "OR",
+    "ORDER",
+    "PROFILE",
+    "REMOVE",
+    "REQUIRE",
+    "RETURN",
+    "SET",
+    "SHOW",
+    "SKIP",
+    "STARTS",
+    "THEN",
+    "TRUE",
+    "UNION",
+    "UNIQUE",
+    "UNWIND",
+    "USE",
+    "WHEN",
+    "WHERE",
+    "WITH",
+    "XOR",
+];
+
+pub fn is_keyword(word: &str) -> bool {
+    KEYWORDS.iter().any(|k| k.eq_ignore_ascii_case(word))
+}
+
+/// Commonly used built-in Cypher functions.
+pub const FUNCTIONS: [&str; 50] = [
+    "abs",
+    "avg",
+    "ceil",
+    "coalesce",
+    "collect",
+    "count",
+    "date",
+    "datetime",
+    "duration",
+    "elementId",
+    "endNode",
+    "exists",
+    "floor",
+    "head",
+    "id",
+    "keys",
+    "labels",
+    "last",
+    "left",
+    "length",
+    "max",
+    "min",
+    "nodes",
+    "percentileCont",
+    "percentileDisc",
+    "properties",
+    "rand",
+    "range",
+    "reduce",
+    "relationships",
+    "replace",
+    "reverse",
+    "right",
+    "round",
+    "size",
+    "split",
+    "sqrt",
+    "startNode",
+    "substring",
+    "sum",
+    "tail",
+    "timestamp",
+    "toBoolean",
+    "toFloat",
+    "toInteger",
+    "toLower",
+    "toString",
+    "toUpper",
+    "trim",
+    "type",
+];
@@ -0,0 +1,11 @@
+mod completion;
+mod keywords;
+mod statements;
+mod tokenizer;
+mod writes;
+
+pub use completion::*;
+pub use keywords::*;
+pub use statements::*;
+pub use tokenizer::*;
+pub use writes::*;
@@ -0,0 +1,268 @@
+use super::{TokenKind, tokenize};
+use crate::domain::QueryLanguage;
+
+/// A statement in a script of `;`-separated statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Statement<'a> {
+    /// The statement, without surrounding whitespace and the terminating `;`
+    pub text: &'a str,
+    /// Line (starting at 1) the statement starts on
+    pub line: usize,
+}
+
+/// Splits input into the statements it contains.
+///
+/// Only semicolons outside of strings, comments, and quoted identifiers end a statement. Statements
+/// made up only of whitespace and comments (eg. a comment after the last `;`) are dropped.
+pub fn split_statements(input: &str) -> Vec<Statement<'_>> {
+    let mut statements = vec![];
+    let mut start = 0;
+    let mut has_content = false;
+
+    let mut push = |start: usize, end: usize, has_content: bool| {
+        if !has_content {
+            return;
+        }
+        let raw = &input[start..end];
+        let leading = raw.len() - raw.trim_start().len();
+        let text = raw.trim();
+        let line = input[..start + leading].matches('\n').count() + 1;
+        statements.push(Statement { text, line });
+    };
+
+    for token in tokenize(input) {
+        if token.kind == TokenKind::Punctuation && token.text == ";" {
+            push(start, token.start, has_content);
+            start = token.start + token.text.len();
+            has_content = false;
+        } else if !token.is_trivia() {
+            has_content = true;
+        }
+    }
+    push(start, input.len(), has_content);
+
+    statements
+}
+
+/// Splits input into the statements it contains, if it's in Cypher; Gremlin scripts are run whole
+/// (without a terminating `;`), as semicolons can also appear in their closures and strings.
+pub fn split_script<'a>(input: &'a str, language: &QueryLanguage) -> Vec<Statement<'a>> {
+    match language {
+        QueryLanguage::Cypher => split_statements(input),
+        QueryLanguage::Gremlin => {
+            let text = input.trim();
+            let text = text.strip_suffix(';').unwrap_or(text).trim_end();
+            if text.is_empty() {
+                return vec![];
+            }
+            let leading = input.len() - input.trim_start().len();
+            let line = input[..leading].matches('\n').count() + 1;
+            vec![Statement { text, line }]
+        }
+    }
+}
+
+/// Whether input is ready to be executed: either its last statement is terminated by a `;`, or all
+/// of its brackets, strings, comments, and quoted identifiers are closed.
+pub fn is_input_complete(input: &str) -> bool {
+    let tokens = tokenize(input);
+
+    let Some(last) = tokens
+        .iter()
+        .rev()
+        .find(|t| t.kind != TokenKind::Whitespace)
+    else {
+        return true;
+    };
+    if last.is_punctuation(";") {
+        return true;
+    }
+    if !is_token_closed(last.kind, last.text) {
+        return false;
+    }
+
+    let mut depth: i64 = 0;
+    for token in tokens.iter().filter(|t| t.kind == TokenKind::Punctuation) {
+        match token.text {
+            "(" | "[" | "{" => depth += 1,
+            ")" | "]" | "}" => depth -= 1,
+            _ => {}
+        }
+    }
+
+    // a stray closing bracket won't be balanced by more input
+    depth <= 0
+}
+
+/// Only the last token can be left open, as unterminated tokens extend to the end of the input.
+fn is_token_closed(kind: TokenKind, text: &str) -> bool {
+    match kind {
+        TokenKind::String => {
+            let mut chars = text.chars();
+            let Some(quote) = chars.next() else {
+                return true;
+            };
+            let mut escaped = false;
+            for c in chars {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    c if c == quote => return true,
+                    _ => {}
+                }
+            }
+            false
+        }
+        TokenKind::Comment => !text.starts_with("/*") || (text.len() >= 4 && text.ends_with("*/")),
+        // a doubled backtick escapes a backtick, so a closed identifier has an even number of them
+        TokenKind::QuotedIdentifier => text.matches('`').count().is_multiple_of(2),
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn describe(input: &str) -> String {
+        split_statements(input)
+            .iter()
+            .map(|s| format!("{}: {}", s.line, s.text))
+            .collect::<Vec<_>>()
+            .join("\n---\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn split_statements_splits_on_semicolons() {
+        // GIVEN
+        let input =
+            "CREATE (:Person {name: 'a'});\nMATCH (n)\nRETURN n;\n\nMATCH (n) RETURN count(n)";
+
+        // WHEN
+        let result = describe(input);
+
+        // THEN
+        assert_snapshot!(result, @"
+        1: CREATE (:Person {name: 'a'})
+        ---
+        2: MATCH (n)
+        RETURN n
+        ---
+        5: MATCH (n) RETURN count(n)
+        ");
+    }
+
+    #[test]
+    fn split_statements_ignores_semicolons_in_strings_comments_and_identifiers() {
+        // GIVEN
+        let input = r#"RETURN 'a;b', "c\";d" AS `e;f` // g;h
+/* i;
+j; */ ;
+RETURN 1;"#;
+
+        // WHEN
+        let result = describe(input);
+
+        // THEN
+        assert_snapshot!(result, @r#"
+        1: RETURN 'a;b', "c\";d" AS `e;f` // g;h
+        /* i;
+        j; */
+        ---
+        4: RETURN 1
+        "#);
+    }
+
+    #[test]
+    fn split_statements_drops_empty_and_comment_only_statements() {
+        // GIVEN
+        let input = ";; RETURN 1 ;  ;\n// the end\n";
+
+        // WHEN
+        let result = describe(input);
+
+        // THEN
+        assert_snapshot!(result, @"1: RETURN 1");
+    }
+
+    #[test]
+    fn split_script_runs_gremlin_scripts_whole() {
+        // GIVEN
+        let input =
+            "\n  g.V().hasLabel('a;b').map { it.get(); it.get() }.toList(); g.V().count();\n";
+
+        // WHEN
+        let result = split_script(input, &QueryLanguage::Gremlin);
+
+        // THEN
+        assert_eq!(
+            result,
+            [Statement {
+                text: "g.V().hasLabel('a;b').map { it.get(); it.get() }.toList(); g.V().count()",
+                line: 2,
+            }]
+        );
+        assert!(split_script(" \n ", &QueryLanguage::Gremlin).is_empty());
+    }
+
+    #[test]
+    fn is_input_complete_accepts_balanced_or_terminated_input() {
+        // GIVEN
+        let inputs = [
+            "",
+            "MATCH (n) RETURN n",
+            "MATCH (n:Person {name: 'a(b'})\nRETURN n",
+            "MATCH (n // unclosed ( in a comment\n) RETURN n",
+            "MATCH (n:Person\n{name: $name RETURN n;",
+            "RETURN 'it\\'s'",
+            "RETURN n.`odd``name`",
+            "RETURN 1)",
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| is_input_complete(i))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert!(result.iter().all(|r| *r), "{result:?}");
+    }
+
+    #[test]
+    fn is_input_complete_waits_for_unclosed_brackets_strings_and_comments() {
+        // GIVEN
+        let inputs = [
+            "MATCH (n:Person",
+            "MATCH (n:Person {name: 'a",
+            "RETURN 'it\\'s",
+            "RETURN \"a;",
+            "MATCH (n) /* a comment;",
+            "RETURN n.`odd``",
+            "UNWIND [1, 2,",
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| is_input_complete(i))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert!(result.iter().all(|r| !*r), "{result:?}");
+    }
+
+    #[test]
+    fn split_statements_returns_nothing_for_blank_input() {
+        // GIVEN
+        // WHEN
+        // THEN
+        assert!(split_statements("").is_empty());
+        assert!(split_statements("  \n // comment").is_empty());
+    }
+}
@@ -0,0 +1,263 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    Whitespace,
+    Comment,
+    /// Keywords, identifiers, and the segments of function/procedure names
+    Word,
+    /// Identifiers wrapped in backticks
+    QuotedIdentifier,
+    String,
+    Number,
+    Parameter,
+    Punctuation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    pub text: &'a str,
+    /// Byte offset of the token in the input
+    pub start: usize,
+}
+
+impl Token<'_> {
+    pub fn is_trivia(&self) -> bool {
+        matches!(self.kind, TokenKind::Whitespace | TokenKind::Comment)
+    }
+
+    pub fn is_word(&self, word: &str) -> bool {
+        self.kind == TokenKind::Word && self.text.eq_ignore_ascii_case(word)
+    }
+
+    pub fn is_punctuation(&self, punctuation: &str) -> bool {
+        self.kind == TokenKind::Punctuation && self.text == punctuation
+    }
+}
+
+/// Splits a Cypher query into tokens.
+///
+/// Tokenizing never fails: unterminated strings, comments, and quoted identifiers extend to the end
+/// of the input, so that partially typed queries can be tokenized as well. Concatenating the text of
+/// all tokens gives back the input.
+pub fn tokenize(input: &str) -> Vec<Token<'_>> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+
+    while let Some((start, c)) = chars.next() {
+        let next = chars.peek().map(|(_, c)| *c);
+
+        let kind = match c {
+            c if c.is_whitespace() => {
+                while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                TokenKind::Whitespace
+            }
+            '/' if next == Some('/') => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                TokenKind::Comment
+            }
+            '/' if next == Some('*') => {
+                chars.next();
+                let mut previous = None;
+                for (_, c) in chars.by_ref() {
+                    if previous == Some('*') && c == '/' {
+                        break;
+                    }
+                    previous = Some(c);
+                }
+                TokenKind::Comment
+            }
+            '\'' | '"' => {
+                let mut escaped = false;
+                for (_, ch) in chars.by_ref() {
+                    match ch {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        ch if ch == c => break,
+                        _ => {}
+                    }
+                }
+                TokenKind::String
+            }
+            '`' => {
+                // a doubled backtick escapes a backtick inside the identifier
+                while let Some((_, ch)) = chars.next() {
+                    if ch == '`' && chars.next_if(|(_, c)| *c == '`').is_none() {
+                        break;
+                    }
+                }
+                TokenKind::QuotedIdentifier
+            }
+            '$' if next.is_some_and(is_word_char) => {
+                while chars.next_if(|(_, c)| is_word_char(*c)).is_some() {}
+                TokenKind::Parameter
+            }
+            c if c.is_ascii_digit() => {
+                while chars.next_if(|(_, c)| is_word_char(*c)).is_some() {}
+                // a "." is only part of the number if a digit follows it, so that ranges like
+                // "1..3" are not swallowed
+                let mut lookahead = chars.clone();
+                if lookahead.next().is_some_and(|(_, c)| c == '.')
+                    && lookahead.next().is_some_and(|(_, c)| c.is_ascii_digit())
+                {
+                    chars.next();
+                    while chars.next_if(|(_, c)| is_word_char(*c)).is_some() {}
+                }
+                TokenKind::Number
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                while chars.next_if(|(_, c)| is_word_char(*c)).is_some() {}
+                TokenKind::Word
+            }
+            _ => TokenKind::Punctuation,
+        };
+
+        let end = chars.peek().map(|(i, _)| *i).unwrap_or(input.len());
+        tokens.push(Token {
+            kind,
+            text: &input[start..end],
+            start,
+        });
+    }
+
+    tokens
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn describe(input: &str) -> String {
+        tokenize(input)
+            .iter()
+            .filter(|t| t.kind != TokenKind::Whitespace)
+            .map(|t| format!("{:?}({})", t.kind, t.text))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn tokenize_splits_a_query_into_tokens() {
+        // GIVEN
+        let query = "MATCH (n:Person {name: $name}) WHERE n.age >= 30.5 RETURN n.`full name`";
+
+        // WHEN
+        let result = describe(query);
+
+        // THEN
+        assert_snapshot!(result, @"
+        Word(MATCH)
+        Punctuation(()
+        Word(n)
+        Punctuation(:)
+        Word(Person)
+        Punctuation({)
+        Word(name)
+        Punctuation(:)
+        Parameter($name)
+        Punctuation(})
+        Punctuation())
+        Word(WHERE)
+        Word(n)
+        Punctuation(.)
+        Word(age)
+        Punctuation(>)
+        Punctuation(=)
+        Number(30.5)
+        Word(RETURN)
+        Word(n)
+        Punctuation(.)
+        QuotedIdentifier(`full name`)
+        ");
+    }
+
+    #[test]
+    fn tokenize_keeps_keywords_inside_strings_and_comments_together() {
+        // GIVEN
+        let query = r#"RETURN 'it\'s a "DELETE"' // CREATE
+/* SET
+   REMOVE */ "MERGE""#;
+
+        // WHEN
+        let result = describe(query);
+
+        // THEN
+        assert_snapshot!(result, @r#"
+        Word(RETURN)
+        String('it\'s a "DELETE"')
+        Comment(// CREATE)
+        Comment(/* SET
+           REMOVE */)
+        String("MERGE")
+        "#);
+    }
+
+    #[test]
+    fn tokenize_does_not_treat_ranges_as_decimals() {
+        // GIVEN
+        let query = "MATCH p = ()-[*1..3]->() RETURN 1.5e3";
+
+        // WHEN
+        let result = describe(query);
+
+        // THEN
+        assert_snapshot!(result, @"
+        Word(MATCH)
+        Word(p)
+        Punctuation(=)
+        Punctuation(()
+        Punctuation())
+        Punctuation(-)
+        Punctuation([)
+        Punctuation(*)
+        Number(1)
+        Punctuation(.)
+        Punctuation(.)
+        Number(3)
+        Punctuation(])
+        Punctuation(-)
+        Punctuation(>)
+        Punctuation(()
+        Punctuation())
+        Word(RETURN)
+        Number(1.5e3)
+        ");
+    }
+
+    #[test]
+    fn tokenize_extends_unterminated_tokens_to_the_end_of_input() {
+        // GIVEN
+        let query = "RETURN `odd``name`, 'unterminated";
+
+        // WHEN
+        let result = describe(query);
+
+        // THEN
+        assert_snapshot!(result, @"
+        Word(RETURN)
+        QuotedIdentifier(`odd``name`)
+        Punctuation(,)
+        String('unterminated)
+        ");
+    }
+
+    #[test]
+    fn tokenize_preserves_the_input() {
+        // GIVEN
+        let query = "MATCH (n) // 🦀 comment\nWHERE n.name = \"über\" RETURN n";
+
+        // WHEN
+        let result: String = tokenize(query).iter().map(|t| t.text).collect();
+
+        // THEN
+        assert_eq!(result, query);
+    }
+}
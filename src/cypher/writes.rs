@@ -0,0 +1,325 @@
+use super::{Token, TokenKind, tokenize};
+
+const WRITE_CLAUSES: [&str; 6] = ["CREATE", "MERGE", "SET", "DELETE", "REMOVE", "DROP"];
+
+/// Prefixes (lowercased) of well-known procedures that write to the database.
+const WRITE_PROCEDURE_PREFIXES: [&str; 17] = [
+    "apoc.atomic.",
+    "apoc.create.",
+    "apoc.cypher.doit",
+    "apoc.cypher.runschema",
+    "apoc.cypher.runwrite",
+    "apoc.do.",
+    "apoc.import.",
+    "apoc.merge.",
+    "apoc.nodes.delete",
+    "apoc.periodic.",
+    "apoc.refactor.",
+    "apoc.schema.assert",
+    "apoc.trigger.",
+    "db.create.",
+    "db.index.fulltext.createnodeindex",
+    "db.index.fulltext.createrelationshipindex",
+    "db.index.fulltext.drop",
+];
+
+/// Keywords that are followed by an expression rather than a clause.
+const EXPRESSION_KEYWORDS: [&str; 18] = [
+    "AND", "AS", "BY", "CASE", "DISTINCT", "ELSE", "IN", "IS", "NOT", "OR", "RETURN", "THEN",
+    "UNWIND", "WHEN", "WHERE", "WITH", "XOR", "YIELD",
+];
+
+const GREMLIN_WRITE_STEPS: [&str; 6] = ["addE", "addV", "drop", "mergeE", "mergeV", "property"];
+
+/// A clause (or procedure call/traversal step) that writes to the database.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WriteClause {
+    pub clause: String,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl std::fmt::Display for WriteClause {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            r#""{}" (line {}, column {})"#,
+            self.clause, self.line, self.column
+        )
+    }
+}
+
+/// Returns the first write clause in a Cypher query, if any.
+///
+/// Keywords are only considered when they appear where a clause can start, so labels, property
+/// keys, map keys, strings, and comments that happen to be spelled like a write clause are ignored.
+/// Procedure calls are flagged if they match a list of well-known write procedures, or if they run
+/// a Graph Data Science algorithm in its "write" or "mutate" mode.
+pub fn find_write_clause(query: &str) -> Option<WriteClause> {
+    let tokens: Vec<Token> = tokenize(query)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+
+    for (i, token) in tokens.iter().enumerate() {
+        if token.kind != TokenKind::Word {
+            continue;
+        }
+
+        let previous = i.checked_sub(1).and_then(|p| tokens.get(p));
+        let next = tokens.get(i + 1);
+
+        if !can_start_clause(previous) || next.is_some_and(|n| n.is_punctuation(":")) {
+            continue;
+        }
+
+        let keyword = token.text.to_ascii_uppercase();
+        let clause = match keyword.as_str() {
+            k if WRITE_CLAUSES.contains(&k) => {
+                if k == "DELETE" && previous.is_some_and(|p| p.is_word("DETACH")) {
+                    Some("DETACH DELETE".to_string())
+                } else {
+                    Some(keyword)
+                }
+            }
+            "LOAD" if next.is_some_and(|n| n.is_word("CSV")) => Some("LOAD CSV".to_string()),
+            "CALL" => {
+                let name = procedure_name(&tokens[i + 1..]);
+                is_write_procedure(&name).then(|| format!("CALL {name}"))
+            }
+            _ => None,
+        };
+
+        if let Some(clause) = clause {
+            // "DETACH DELETE" is reported at the position of "DETACH"
+            let start = match clause.as_str() {
+                "DETACH DELETE" => previous.map(|p| p.start).unwrap_or(token.start),
+                _ => token.start,
+            };
+            let (line, column) = position(query, start);
+
+            return Some(WriteClause {
+                clause,
+                line,
+                column,
+            });
+        }
+    }
+
+    None
+}
+
+/// Returns the first step in a Gremlin traversal that writes to the database, if any.
+///
+/// Gremlin's lexical structure is close enough to Cypher's for the same tokenizer to be used.
+pub fn find_gremlin_write_step(query: &str) -> Option<WriteClause> {
+    let tokens: Vec<Token> = tokenize(query)
+        .into_iter()
+        .filter(|t| !t.is_trivia())
+        .collect();
+
+    tokens.windows(3).find_map(|window| {
+        let [previous, step, next] = window else {
+            return None;
+        };
+
+        let is_write_step = previous.is_punctuation(".")
+            && step.kind == TokenKind::Word
+            && GREMLIN_WRITE_STEPS.contains(&step.text)
+            && next.is_punctuation("(");
+
+        is_write_step.then(|| {
+            let (line, column) = position(query, step.start);
+            WriteClause {
+                clause: step.text.to_string(),
+                line,
+                column,
+            }
+        })
+    })
+}
+
+/// Clauses follow the end of a previous clause or expression (including a `*` projection, as in
+/// `WITH *`); words following "(", ",", ".", ":", "=", or keywords that expect an expression (eg.
+/// "WITH", "WHERE") are variables, labels, or property keys.
+fn can_start_clause(previous: Option<&Token>) -> bool {
+    let Some(previous) = previous else {
+        return true;
+    };
+
+    match previous.kind {
+        TokenKind::Word => !EXPRESSION_KEYWORDS.iter().any(|k| previous.is_word(k)),
+        TokenKind::QuotedIdentifier
+        | TokenKind::String
+        | TokenKind::Number
+        | TokenKind::Parameter => true,
+        TokenKind::Punctuation => matches!(previous.text, ")" | "]" | "}" | "{" | ";" | "|" | "*"),
+        TokenKind::Whitespace | TokenKind::Comment => true,
+    }
+}
+
+fn procedure_name(tokens: &[Token]) -> String {
+    let mut name = String::new();
+    for token in tokens {
+        match token.kind {
+            TokenKind::Word => name.push_str(token.text),
+            TokenKind::QuotedIdentifier => name.push_str(token.text.trim_matches('`')),
+            TokenKind::Punctuation if token.text == "." => name.push('.'),
+            _ => break,
+        }
+    }
+
+    name
+}
+
+fn is_write_procedure(name: &str) -> bool {
+    let name = name.to_lowercase();
+    if name.is_empty() {
+        return false;
+    }
+
+    WRITE_PROCEDURE_PREFIXES
+        .iter()
+        .any(|prefix| name.starts_with(prefix))
+        || (name.starts_with("gds.") && (name.ends_with(".write") || name.ends_with(".mutate")))
+}
+
+fn position(query: &str, offset: usize) -> (usize, usize) {
+    let before = &query[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map(|l| l.chars().count())
+        .unwrap_or_default()
+        + 1;
+
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn describe(result: Option<WriteClause>) -> String {
+        result
+            .map(|c| c.to_string())
+            .unwrap_or_else(|| "none".to_string())
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn find_write_clause_allows_read_queries() {
+        // GIVEN
+        let queries = [
+            "MATCH (n:Person) RETURN n.name LIMIT 5",
+            "MATCH (n) WHERE n.name = 'DETACH DELETE n' RETURN n",
+            "MATCH (n:Create)-[:SET]->(m:`DELETE`) RETURN n.remove, m.merge",
+            "RETURN {create: 1, set: [2]} AS delete",
+            "// CREATE (n)\nMATCH (n) /* SET n.x = 1 */ RETURN n",
+            "MATCH (set) WITH set, count(*) AS c RETURN set",
+            "CALL db.labels() YIELD label RETURN label",
+            "CALL { MATCH (n) RETURN n } RETURN n",
+            "CALL gds.pageRank.stream('graph') YIELD nodeId RETURN nodeId",
+        ];
+
+        // WHEN
+        let result: Vec<String> = queries
+            .iter()
+            .map(|q| describe(find_write_clause(q)))
+            .collect();
+
+        // THEN
+        assert!(result.iter().all(|r| r == "none"), "{result:?}");
+    }
+
+    #[test]
+    fn find_write_clause_finds_write_clauses() {
+        // GIVEN
+        let queries = [
+            "CREATE (n:Person {name: 'Alice'})",
+            "MATCH (n) DETACH DELETE n",
+            "match (n)\nwhere n.id = 1\nset n.name = 'Bob'",
+            "MERGE (n:Person {id: 1}) ON CREATE SET n.created = timestamp()",
+            "MATCH (n) REMOVE n:Person",
+            "MATCH (n) FOREACH (x IN [1] | DELETE n)",
+            "LOAD CSV FROM 'file:///people.csv' AS row RETURN row",
+            "MATCH (n) CALL { WITH n DELETE n }",
+            "CALL apoc.create.node(['Person'], {name: 'Alice'})",
+            "CALL apoc.periodic.iterate('MATCH (n) RETURN n', 'DELETE n', {})",
+            "CALL gds.pageRank.write('graph', {writeProperty: 'rank'})",
+            "DROP INDEX person_name",
+            "MATCH (n) RETURN n;\nCREATE (m)",
+            "MATCH (n) WITH * DELETE n",
+            "MATCH (n) WITH * SET n.seen = true",
+            "CALL db.labels() YIELD * CREATE (:X)",
+        ];
+
+        // WHEN
+        let result = queries
+            .iter()
+            .map(|q| describe(find_write_clause(q)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @r#"
+        "CREATE" (line 1, column 1)
+        "DETACH DELETE" (line 1, column 11)
+        "SET" (line 3, column 1)
+        "MERGE" (line 1, column 1)
+        "REMOVE" (line 1, column 11)
+        "DELETE" (line 1, column 31)
+        "LOAD CSV" (line 1, column 1)
+        "DELETE" (line 1, column 25)
+        "CALL apoc.create.node" (line 1, column 1)
+        "CALL apoc.periodic.iterate" (line 1, column 1)
+        "CALL gds.pageRank.write" (line 1, column 1)
+        "DROP" (line 1, column 1)
+        "CREATE" (line 2, column 1)
+        "DELETE" (line 1, column 18)
+        "SET" (line 1, column 18)
+        "CREATE" (line 1, column 26)
+        "#);
+    }
+
+    #[test]
+    fn find_gremlin_write_step_allows_read_traversals() {
+        // GIVEN
+        let query = "g.V().has('name', 'drop').properties('property').values('addV')";
+
+        // WHEN
+        let result = find_gremlin_write_step(query);
+
+        // THEN
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn find_gremlin_write_step_finds_write_steps() {
+        // GIVEN
+        let queries = [
+            "g.V().hasLabel('Person').drop()",
+            "g.addV('Person').property('name', 'Alice')",
+            "g.V(1).as('a').V(2).addE('knows').from('a')",
+        ];
+
+        // WHEN
+        let result = queries
+            .iter()
+            .map(|q| describe(find_gremlin_write_step(q)))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @r#"
+        "drop" (line 1, column 26)
+        "addV" (line 1, column 3)
+        "addE" (line 1, column 21)
+        "#);
+    }
+}
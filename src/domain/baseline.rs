@@ -0,0 +1,374 @@
+use super::BenchmarkReport;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Differences with a p-value below this are considered significant.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Name a benchmark baseline is saved under; it's used as a file name, so it's restricted to
+/// alphanumeric characters, "-", "_", and ".".
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineName(String);
+
+impl BaselineName {
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for BaselineName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for BaselineName {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("name cannot be empty");
+        }
+
+        if s.starts_with('.')
+            || !s
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        {
+            return Err(
+                r#"name can only contain alphanumeric characters, "-", "_", and "." (and cannot start with ".")"#,
+            );
+        }
+
+        Ok(BaselineName(s.to_string()))
+    }
+}
+
+/// How much slower than a baseline a benchmark is allowed to be, as a percentage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SlowdownThreshold(f64);
+
+impl SlowdownThreshold {
+    /// The threshold as a fraction (eg. 0.2 for 20%).
+    pub fn value(&self) -> f64 {
+        self.0 / 100.0
+    }
+}
+
+impl std::fmt::Display for SlowdownThreshold {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+impl FromStr for SlowdownThreshold {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number: f64 = s
+            .trim()
+            .trim_end_matches('%')
+            .trim_end()
+            .parse()
+            .map_err(|_| "value is not a valid percentage (eg. 20%)")?;
+
+        if !number.is_finite() || number < 0.0 {
+            return Err("needs to be a non-negative percentage");
+        }
+
+        Ok(SlowdownThreshold(number))
+    }
+}
+
+/// A latency metric of a benchmark, compared with the same metric of its baseline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricComparison {
+    pub name: &'static str,
+    pub baseline: Duration,
+    pub current: Duration,
+}
+
+impl MetricComparison {
+    /// Relative change from the baseline; positive values mean the benchmark got slower.
+    pub fn change(&self) -> f64 {
+        let baseline = self.baseline.as_secs_f64();
+        if baseline == 0.0 {
+            return 0.0;
+        }
+
+        (self.current.as_secs_f64() - baseline) / baseline
+    }
+}
+
+/// Comparison of a benchmark with a baseline.
+///
+/// Whether the runs of the two differ significantly is determined via a (two-sided) Mann-Whitney U
+/// test, which doesn't assume latencies to be normally distributed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BaselineComparison {
+    pub metrics: Vec<MetricComparison>,
+    pub p_value: f64,
+}
+
+impl BaselineComparison {
+    pub fn new(baseline: &BenchmarkReport, current: &BenchmarkReport) -> Self {
+        let (b, c) = (&baseline.statistics, &current.statistics);
+        let metrics = vec![
+            MetricComparison {
+                name: "mean",
+                baseline: b.mean,
+                current: c.mean,
+            },
+            MetricComparison {
+                name: "median",
+                baseline: b.median,
+                current: c.median,
+            },
+            MetricComparison {
+                name: "p90",
+                baseline: b.p90,
+                current: c.p90,
+            },
+            MetricComparison {
+                name: "p95",
+                baseline: b.p95,
+                current: c.p95,
+            },
+            MetricComparison {
+                name: "p99",
+                baseline: b.p99,
+                current: c.p99,
+            },
+        ];
+
+        let micros = |report: &BenchmarkReport| -> Vec<f64> {
+            report
+                .runs
+                .iter()
+                .map(|r| r.latency.as_secs_f64() * 1e6)
+                .collect()
+        };
+
+        Self {
+            metrics,
+            p_value: mann_whitney_p_value(&micros(baseline), &micros(current)),
+        }
+    }
+
+    pub fn is_significant(&self) -> bool {
+        self.p_value < SIGNIFICANCE_LEVEL
+    }
+
+    /// Returns the change in median latency if the benchmark is significantly slower than the
+    /// baseline by more than the threshold.
+    pub fn regression(&self, threshold: SlowdownThreshold) -> Option<f64> {
+        let change = self
+            .metrics
+            .iter()
+            .find(|m| m.name == "median")
+            .map(MetricComparison::change)?;
+
+        (self.is_significant() && change > threshold.value()).then_some(change)
+    }
+}
+
+/// Two-sided p-value of a Mann-Whitney U test, using the normal approximation (with corrections
+/// for ties and continuity).
+fn mann_whitney_p_value(a: &[f64], b: &[f64]) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 1.0;
+    }
+
+    let mut combined: Vec<(f64, bool)> = a
+        .iter()
+        .map(|&x| (x, true))
+        .chain(b.iter().map(|&x| (x, false)))
+        .collect();
+    combined.sort_by(|x, y| x.0.total_cmp(&y.0));
+
+    let mut rank_sum_a = 0.0;
+    let mut tie_term = 0.0;
+    let mut i = 0;
+    while i < combined.len() {
+        let mut j = i;
+        while j + 1 < combined.len() && combined[j + 1].0 == combined[i].0 {
+            j += 1;
+        }
+
+        let average_rank = (i + j) as f64 / 2.0 + 1.0;
+        let num_tied = (j - i + 1) as f64;
+        tie_term += num_tied.powi(3) - num_tied;
+        rank_sum_a +=
+            average_rank * combined[i..=j].iter().filter(|(_, in_a)| *in_a).count() as f64;
+
+        i = j + 1;
+    }
+
+    let (n1, n2) = (a.len() as f64, b.len() as f64);
+    let n = n1 + n2;
+    let u = rank_sum_a - n1 * (n1 + 1.0) / 2.0;
+    let variance = n1 * n2 / 12.0 * ((n + 1.0) - tie_term / (n * (n - 1.0)));
+    if variance <= 0.0 {
+        return 1.0;
+    }
+
+    let z = ((u - n1 * n2 / 2.0).abs() - 0.5).max(0.0) / variance.sqrt();
+
+    (2.0 * (1.0 - normal_cdf(z))).clamp(0.0, 1.0)
+}
+
+fn normal_cdf(z: f64) -> f64 {
+    0.5 * (1.0 + erf(z / std::f64::consts::SQRT_2))
+}
+
+/// Approximation of the error function (Abramowitz and Stegun, 7.1.26); accurate to within 1.5e-7.
+fn erf(x: f64) -> f64 {
+    let t = 1.0 / (1.0 + 0.327_591_1 * x.abs());
+    let polynomial = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let result = 1.0 - polynomial * (-x * x).exp();
+
+    if x < 0.0 { -result } else { result }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BenchmarkRun, BenchmarkStats};
+
+    fn get_report(latencies_ms: &[u64]) -> BenchmarkReport {
+        let runs: Vec<BenchmarkRun> = latencies_ms
+            .iter()
+            .enumerate()
+            .map(|(i, ms)| BenchmarkRun {
+                run: i as u16 + 1,
+                latency: Duration::from_millis(*ms),
+                retries: 0,
+                param_set: None,
+            })
+            .collect();
+        let times: Vec<Duration> = runs.iter().map(|r| r.latency).collect();
+
+        BenchmarkReport {
+            query: "MATCH (n) RETURN count(n)".to_string(),
+            language: "cypher".to_string(),
+            backend: "neo4j".to_string(),
+            db_uri: "bolt://127.0.0.1:7687".to_string(),
+            started_at: "2025-01-16T12:00:00+00:00".to_string(),
+            finished_at: "2025-01-16T12:00:01+00:00".to_string(),
+            num_warmup_runs: 3,
+            statistics: BenchmarkStats::new(&times).expect("stats should've been computed"),
+            runs,
+            param_sets: vec![],
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn a_significant_slowdown_is_a_regression() {
+        // GIVEN
+        let baseline = get_report(&[10, 11, 12, 10, 11, 12, 10, 11, 12, 11]);
+        let current = get_report(&[15, 16, 14, 15, 16, 14, 15, 16, 14, 15]);
+        let threshold = SlowdownThreshold::from_str("20%").expect("threshold should've parsed");
+
+        // WHEN
+        let result = BaselineComparison::new(&baseline, &current);
+
+        // THEN
+        assert!(result.is_significant());
+        let change = result
+            .regression(threshold)
+            .expect("comparison should've been a regression");
+        assert!((change - 4.0 / 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn an_insignificant_slowdown_is_not_a_regression() {
+        // GIVEN
+        let baseline = get_report(&[10, 30, 12, 25, 11]);
+        let current = get_report(&[14, 28, 13, 26, 15]);
+        let threshold = SlowdownThreshold::from_str("10").expect("threshold should've parsed");
+
+        // WHEN
+        let result = BaselineComparison::new(&baseline, &current);
+
+        // THEN
+        assert!(!result.is_significant());
+        assert_eq!(result.regression(threshold), None);
+    }
+
+    #[test]
+    fn a_significant_slowdown_within_the_threshold_is_not_a_regression() {
+        // GIVEN
+        let baseline = get_report(&[10, 10, 10, 10, 10, 10, 10, 10]);
+        let current = get_report(&[11, 11, 11, 11, 11, 11, 11, 11]);
+        let threshold = SlowdownThreshold::from_str("20%").expect("threshold should've parsed");
+
+        // WHEN
+        let result = BaselineComparison::new(&baseline, &current);
+
+        // THEN
+        assert!(result.is_significant());
+        assert_eq!(result.regression(threshold), None);
+    }
+
+    #[test]
+    fn mann_whitney_p_value_matches_reference_values() {
+        // GIVEN
+        // U = 8, with 4 pairs of tied values
+        let a = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0];
+        let b = [5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 11.0, 12.0];
+
+        // WHEN
+        let result = mann_whitney_p_value(&a, &b);
+
+        // THEN
+        assert!((result - 0.0133).abs() < 1e-4, "p-value was {result}");
+    }
+
+    //-------------//
+    //  FAILURES   //
+    //-------------//
+
+    #[test]
+    fn parsing_invalid_baseline_names_fails() {
+        // GIVEN
+        let inputs = ["", "../main", ".hidden", "with space"];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| BaselineName::from_str(i).is_err())
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(result, vec![true, true, true, true]);
+    }
+
+    #[test]
+    fn parsing_invalid_slowdown_thresholds_fails() {
+        // GIVEN
+        let inputs = ["fast", "-5%", "%"];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| SlowdownThreshold::from_str(i))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(
+            result,
+            vec![
+                Err("value is not a valid percentage (eg. 20%)"),
+                Err("needs to be a non-negative percentage"),
+                Err("value is not a valid percentage (eg. 20%)"),
+            ]
+        );
+    }
+}
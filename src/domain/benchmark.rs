@@ -1,8 +1,18 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
 use std::str::FromStr;
+use std::time::Duration;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BenchmarkNumRuns(u16);
 
+impl Default for BenchmarkNumRuns {
+    fn default() -> Self {
+        BenchmarkNumRuns(5)
+    }
+}
+
 impl BenchmarkNumRuns {
     pub fn value(&self) -> u16 {
         self.0
@@ -28,3 +38,271 @@ impl FromStr for BenchmarkNumRuns {
         Ok(BenchmarkNumRuns(number))
     }
 }
+
+/// Format to write a benchmark's report in.
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum BenchmarkReportFormat {
+    Csv,
+    Json,
+}
+
+impl BenchmarkReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            BenchmarkReportFormat::Csv => "csv",
+            BenchmarkReportFormat::Json => "json",
+        }
+    }
+}
+
+impl std::fmt::Display for BenchmarkReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.extension())
+    }
+}
+
+/// Latency statistics for a set of benchmark runs.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkStats {
+    pub num_runs: usize,
+    #[serde(rename = "min_us", with = "micros")]
+    pub min: Duration,
+    #[serde(rename = "max_us", with = "micros")]
+    pub max: Duration,
+    #[serde(rename = "mean_us", with = "micros")]
+    pub mean: Duration,
+    #[serde(rename = "median_us", with = "micros")]
+    pub median: Duration,
+    #[serde(rename = "p90_us", with = "micros")]
+    pub p90: Duration,
+    #[serde(rename = "p95_us", with = "micros")]
+    pub p95: Duration,
+    #[serde(rename = "p99_us", with = "micros")]
+    pub p99: Duration,
+    #[serde(rename = "std_dev_us", with = "micros")]
+    pub std_dev: Duration,
+    /// Coefficient of variation (standard deviation relative to the mean)
+    pub cv: f64,
+}
+
+impl BenchmarkStats {
+    /// Returns `None` if there are no times.
+    ///
+    /// Percentiles are interpolated linearly between the closest runs, and the standard deviation
+    /// is the sample standard deviation.
+    pub fn new(times: &[Duration]) -> Option<Self> {
+        let mut sorted = times.to_vec();
+        sorted.sort();
+
+        let (min, max) = (*sorted.first()?, *sorted.last()?);
+        let micros: Vec<f64> = sorted.iter().map(|t| t.as_secs_f64() * 1e6).collect();
+        let mean = micros.iter().sum::<f64>() / micros.len() as f64;
+        let variance = if micros.len() > 1 {
+            micros.iter().map(|m| (m - mean).powi(2)).sum::<f64>() / (micros.len() - 1) as f64
+        } else {
+            0.0
+        };
+        let std_dev = variance.sqrt();
+
+        Some(Self {
+            num_runs: sorted.len(),
+            min,
+            max,
+            mean: from_micros(mean),
+            median: percentile(&micros, 50.0),
+            p90: percentile(&micros, 90.0),
+            p95: percentile(&micros, 95.0),
+            p99: percentile(&micros, 99.0),
+            std_dev: from_micros(std_dev),
+            cv: if mean > 0.0 { std_dev / mean } else { 0.0 },
+        })
+    }
+}
+
+/// A benchmark run, timed by its successful attempt.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkRun {
+    pub run: u16,
+    #[serde(rename = "latency_us", with = "micros")]
+    pub latency: Duration,
+    pub retries: u16,
+    /// Parameter set the run used (counting from 1), if the parameters came from a file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub param_set: Option<usize>,
+}
+
+/// Latency statistics of the runs that used a parameter set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSetStats {
+    pub param_set: usize,
+    pub statistics: BenchmarkStats,
+}
+
+impl ParamSetStats {
+    /// Groups runs by the parameter set they used, slowest (by median latency) first.
+    pub fn from_runs(runs: &[BenchmarkRun]) -> Vec<Self> {
+        let mut times: BTreeMap<usize, Vec<Duration>> = BTreeMap::new();
+        for run in runs {
+            if let Some(param_set) = run.param_set {
+                times.entry(param_set).or_default().push(run.latency);
+            }
+        }
+
+        let mut stats: Vec<Self> = times
+            .into_iter()
+            .filter_map(|(param_set, times)| {
+                BenchmarkStats::new(&times).map(|statistics| Self {
+                    param_set,
+                    statistics,
+                })
+            })
+            .collect();
+        stats.sort_by(|a, b| b.statistics.median.cmp(&a.statistics.median));
+
+        stats
+    }
+}
+
+/// Everything needed to compare benchmarks over time.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BenchmarkReport {
+    pub query: String,
+    pub language: String,
+    pub backend: String,
+    pub db_uri: String,
+    pub started_at: String,
+    pub finished_at: String,
+    pub num_warmup_runs: u16,
+    /// Parameter sets the runs went through, if they came from a file
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub param_sets: Vec<Value>,
+    pub statistics: BenchmarkStats,
+    pub runs: Vec<BenchmarkRun>,
+}
+
+/// `sorted` needs to be non-empty.
+fn percentile(sorted: &[f64], percentile: f64) -> Duration {
+    let rank = percentile / 100.0 * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    let value = sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64);
+
+    from_micros(value)
+}
+
+fn from_micros(micros: f64) -> Duration {
+    Duration::from_secs_f64(micros.max(0.0) / 1e6)
+}
+
+/// (De)serializes durations as a whole number of microseconds.
+mod micros {
+    use serde::{Deserialize, Deserializer, Serializer};
+    use std::time::Duration;
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_micros() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        u64::deserialize(deserializer).map(Duration::from_micros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn benchmark_stats_are_computed_correctly() {
+        // GIVEN
+        let times: Vec<Duration> = [12, 15, 11, 30, 14, 13, 16, 12, 14, 100]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+
+        // WHEN
+        let result = BenchmarkStats::new(&times)
+            .map(|s| serde_json::to_string_pretty(&s).expect("stats should've been serialized"));
+
+        // THEN
+        assert_snapshot!(result.unwrap_or_default(), @r#"
+        {
+          "num_runs": 10,
+          "min_us": 11000,
+          "max_us": 100000,
+          "mean_us": 23700,
+          "median_us": 14000,
+          "p90_us": 37000,
+          "p95_us": 68500,
+          "p99_us": 93700,
+          "std_dev_us": 27353,
+          "cv": 1.15417105846185
+        }
+        "#);
+    }
+
+    #[test]
+    fn benchmark_stats_work_for_a_single_run() {
+        // GIVEN
+        let times = [Duration::from_micros(1500)];
+
+        // WHEN
+        let result = BenchmarkStats::new(&times);
+
+        // THEN
+        let stats = result.expect("stats should've been computed");
+        assert_eq!(stats.median, Duration::from_micros(1500));
+        assert_eq!(stats.p99, Duration::from_micros(1500));
+        assert_eq!(stats.std_dev, Duration::ZERO);
+        assert_eq!(stats.cv, 0.0);
+    }
+
+    #[test]
+    fn runs_are_grouped_by_param_set_slowest_first() {
+        // GIVEN
+        let runs: Vec<BenchmarkRun> = [(1, 10), (2, 50), (1, 12), (2, 40), (3, 20)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (param_set, ms))| BenchmarkRun {
+                run: i as u16 + 1,
+                latency: Duration::from_millis(ms),
+                retries: 0,
+                param_set: Some(param_set),
+            })
+            .collect();
+
+        // WHEN
+        let result = ParamSetStats::from_runs(&runs);
+
+        // THEN
+        let result: Vec<_> = result
+            .iter()
+            .map(|s| {
+                (
+                    s.param_set,
+                    s.statistics.num_runs,
+                    s.statistics.median.as_millis(),
+                )
+            })
+            .collect();
+        assert_eq!(result, vec![(2, 2, 45), (3, 1, 20), (1, 2, 11)]);
+    }
+
+    //-------------//
+    //  FAILURES   //
+    //-------------//
+
+    #[test]
+    fn benchmark_stats_need_at_least_one_run() {
+        // GIVEN
+        // WHEN
+        let result = BenchmarkStats::new(&[]);
+
+        // THEN
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,35 @@
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Default, PartialEq, clap::ValueEnum)]
+pub enum QueryLanguage {
+    #[default]
+    Cypher,
+    Gremlin,
+}
+
+impl QueryLanguage {
+    pub fn name(&self) -> &'static str {
+        match self {
+            QueryLanguage::Cypher => "cypher",
+            QueryLanguage::Gremlin => "gremlin",
+        }
+    }
+}
+
+impl FromStr for QueryLanguage {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "cypher" => Ok(Self::Cypher),
+            "gremlin" => Ok(Self::Gremlin),
+            _ => Err("invalid query language provided; allowed values: [cypher, gremlin]"),
+        }
+    }
+}
+
+impl std::fmt::Display for QueryLanguage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
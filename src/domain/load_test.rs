@@ -0,0 +1,216 @@
+use super::BenchmarkStats;
+use super::timeout::{format_duration, parse_duration};
+use std::str::FromStr;
+use std::time::Duration;
+
+/// Number of tasks that run a query at the same time during a load test.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadTestConcurrency(u16);
+
+impl LoadTestConcurrency {
+    pub fn value(&self) -> u16 {
+        self.0
+    }
+}
+
+impl Default for LoadTestConcurrency {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+impl std::fmt::Display for LoadTestConcurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for LoadTestConcurrency {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number: u16 = s.parse().map_err(|_| "value is not a valid number")?;
+
+        if number == 0 {
+            return Err("needs to be greater than 0");
+        }
+
+        Ok(LoadTestConcurrency(number))
+    }
+}
+
+/// How long a load test keeps running queries for; uses the same format as query timeouts.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadTestDuration(Duration);
+
+impl LoadTestDuration {
+    pub fn value(&self) -> Duration {
+        self.0
+    }
+}
+
+impl std::fmt::Display for LoadTestDuration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_duration(self.0))
+    }
+}
+
+impl FromStr for LoadTestDuration {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(LoadTestDuration)
+    }
+}
+
+/// Number of queries per second a load test starts, across all of its tasks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LoadTestRate(u32);
+
+impl LoadTestRate {
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / f64::from(self.0))
+    }
+}
+
+impl std::fmt::Display for LoadTestRate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} qps", self.0)
+    }
+}
+
+impl FromStr for LoadTestRate {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let number: u32 = s.parse().map_err(|_| "value is not a valid number")?;
+
+        if number == 0 {
+            return Err("needs to be greater than 0");
+        }
+
+        Ok(LoadTestRate(number))
+    }
+}
+
+/// A query run during a load test.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadTestSample {
+    pub latency: Duration,
+    pub error: Option<String>,
+}
+
+/// Throughput, errors, and latency of the queries that finished within a span of time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadTestSummary {
+    pub num_queries: usize,
+    pub num_errors: usize,
+    pub elapsed: Duration,
+    /// Latency of the queries that succeeded
+    pub latency: Option<BenchmarkStats>,
+}
+
+impl LoadTestSummary {
+    pub fn new(samples: &[LoadTestSample], elapsed: Duration) -> Self {
+        let times: Vec<Duration> = samples
+            .iter()
+            .filter(|s| s.error.is_none())
+            .map(|s| s.latency)
+            .collect();
+
+        Self {
+            num_queries: samples.len(),
+            num_errors: samples.len() - times.len(),
+            elapsed,
+            latency: BenchmarkStats::new(&times),
+        }
+    }
+
+    pub fn throughput(&self) -> f64 {
+        match self.elapsed.as_secs_f64() {
+            0.0 => 0.0,
+            secs => self.num_queries as f64 / secs,
+        }
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        match self.num_queries {
+            0 => 0.0,
+            n => self.num_errors as f64 / n as f64,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(latency_ms: u64, failed: bool) -> LoadTestSample {
+        LoadTestSample {
+            latency: Duration::from_millis(latency_ms),
+            error: failed.then(|| "connection reset".to_string()),
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn load_test_summary_works() {
+        // GIVEN
+        let samples = vec![
+            sample(10, false),
+            sample(30, false),
+            sample(20, true),
+            sample(40, false),
+        ];
+
+        // WHEN
+        let result = LoadTestSummary::new(&samples, Duration::from_secs(2));
+
+        // THEN
+        assert_eq!(result.num_queries, 4);
+        assert_eq!(result.num_errors, 1);
+        assert_eq!(result.throughput(), 2.0);
+        assert_eq!(result.error_rate(), 0.25);
+        let latency = result.latency.expect("latency should've been computed");
+        assert_eq!(latency.num_runs, 3);
+        assert_eq!(latency.median, Duration::from_millis(30));
+    }
+
+    #[test]
+    fn parsing_load_test_options_works() {
+        // GIVEN
+        // WHEN
+        let concurrency = LoadTestConcurrency::from_str("8").map(|c| c.value());
+        let duration = LoadTestDuration::from_str("2m").map(|d| d.to_string());
+        let rate = LoadTestRate::from_str("200").map(|r| r.interval());
+
+        // THEN
+        assert_eq!(concurrency, Ok(8));
+        assert_eq!(duration, Ok("2m".to_string()));
+        assert_eq!(rate, Ok(Duration::from_millis(5)));
+    }
+
+    //-------------//
+    //  FAILURES   //
+    //-------------//
+
+    #[test]
+    fn parsing_invalid_load_test_options_fails() {
+        // GIVEN
+        // WHEN
+        let concurrency = LoadTestConcurrency::from_str("0");
+        let duration = LoadTestDuration::from_str("10d");
+        let rate = LoadTestRate::from_str("fast");
+
+        // THEN
+        assert_eq!(concurrency, Err("needs to be greater than 0"));
+        assert_eq!(
+            duration,
+            Err("invalid unit provided; allowed values: [ms, s, m, h]")
+        );
+        assert_eq!(rate, Err("value is not a valid number"));
+    }
+}
@@ -1,7 +1,33 @@
+mod baseline;
 mod benchmark;
+mod language;
+mod load_test;
+mod on_error;
+mod output;
 mod pager;
+mod param_sets;
+mod params;
+mod plan;
+mod profile;
 mod result;
+mod retry;
+mod schema;
+mod sink;
+mod timeout;
 
+pub use baseline::*;
 pub use benchmark::*;
+pub use language::*;
+pub use load_test::*;
+pub use on_error::*;
+pub use output::*;
 pub use pager::*;
+pub use param_sets::*;
+pub use params::*;
+pub use plan::*;
+pub use profile::*;
 pub use result::*;
+pub use retry::*;
+pub use schema::*;
+pub use sink::*;
+pub use timeout::*;
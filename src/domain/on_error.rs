@@ -0,0 +1,30 @@
+use std::str::FromStr;
+
+/// What to do when a statement in a script of several statements fails.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum OnError {
+    #[default]
+    Stop,
+    Continue,
+}
+
+impl FromStr for OnError {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "stop" => Ok(Self::Stop),
+            "continue" => Ok(Self::Continue),
+            _ => Err("invalid value provided; allowed values: [stop, continue]"),
+        }
+    }
+}
+
+impl std::fmt::Display for OnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OnError::Stop => write!(f, "stop"),
+            OnError::Continue => write!(f, "continue"),
+        }
+    }
+}
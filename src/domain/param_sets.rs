@@ -0,0 +1,314 @@
+use super::{QueryParam, QueryParams};
+use serde_json::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// How benchmark runs go through parameter sets.
+#[derive(Debug, Clone, Copy, Default, PartialEq, clap::ValueEnum)]
+pub enum ParamSetOrder {
+    // one after the other, starting over after the last one
+    #[default]
+    Cycle,
+    // picked at random for each run
+    Sample,
+}
+
+impl std::fmt::Display for ParamSetOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamSetOrder::Cycle => write!(f, "cycle"),
+            ParamSetOrder::Sample => write!(f, "sample"),
+        }
+    }
+}
+
+#[derive(Debug, thiserror::Error, PartialEq)]
+pub enum ParamSetsError {
+    #[error("file doesn't contain any parameter sets")]
+    Empty,
+    #[error("CSV is invalid: {0}")]
+    InvalidCsv(String),
+    #[error("parameter set on line {line} is invalid: {reason}")]
+    InvalidParamSet { line: usize, reason: String },
+}
+
+/// Sets of parameters to bind to a query, one set per benchmark run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParamSets {
+    sets: Vec<QueryParams>,
+    order: ParamSetOrder,
+    from_file: bool,
+}
+
+impl ParamSets {
+    /// A single set of parameters, bound to every run.
+    pub fn single(params: QueryParams) -> Self {
+        Self {
+            sets: vec![params],
+            order: ParamSetOrder::Cycle,
+            from_file: false,
+        }
+    }
+
+    /// Parses CSV with a header row of parameter keys; values are parsed the same way as the ones
+    /// passed via `--param`.
+    pub fn from_csv(contents: &str, order: ParamSetOrder) -> Result<Self, ParamSetsError> {
+        let mut reader = csv::Reader::from_reader(contents.as_bytes());
+        let headers = reader
+            .headers()
+            .map_err(|e| ParamSetsError::InvalidCsv(e.to_string()))?
+            .clone();
+
+        let mut sets = vec![];
+        for record in reader.records() {
+            let record = record.map_err(|e| ParamSetsError::InvalidCsv(e.to_string()))?;
+            let line = record
+                .position()
+                .map(|p| p.line() as usize)
+                .unwrap_or_default();
+
+            let mut params = QueryParams::default();
+            for (key, value) in headers.iter().zip(record.iter()) {
+                let param =
+                    QueryParam::new(key, value).map_err(|e| ParamSetsError::InvalidParamSet {
+                        line,
+                        reason: e.to_string(),
+                    })?;
+                params.insert(param);
+            }
+            sets.push(params);
+        }
+
+        Self::from_sets(sets, order)
+    }
+
+    /// Parses JSON lines, each of which is an object of parameters; blank lines are skipped.
+    pub fn from_jsonl(contents: &str, order: ParamSetOrder) -> Result<Self, ParamSetsError> {
+        let sets = contents
+            .lines()
+            .enumerate()
+            .filter(|(_, l)| !l.trim().is_empty())
+            .map(|(i, l)| {
+                QueryParams::from_json(l).map_err(|e| ParamSetsError::InvalidParamSet {
+                    line: i + 1,
+                    reason: e.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::from_sets(sets, order)
+    }
+
+    fn from_sets(sets: Vec<QueryParams>, order: ParamSetOrder) -> Result<Self, ParamSetsError> {
+        if sets.is_empty() {
+            return Err(ParamSetsError::Empty);
+        }
+
+        Ok(Self {
+            sets,
+            order,
+            from_file: true,
+        })
+    }
+
+    /// Binds the base parameters to every set as well; the ones in a set take precedence.
+    pub fn with_base(mut self, base: &QueryParams) -> Self {
+        for set in &mut self.sets {
+            let mut merged = base.clone();
+            merged.extend(set);
+            *set = merged;
+        }
+
+        self
+    }
+
+    /// Whether runs are to be told apart by the parameter set they used.
+    pub fn is_from_file(&self) -> bool {
+        self.from_file
+    }
+
+    /// Returns the parameters for the next run, along with the number of the parameter set they
+    /// come from (counting from 1) if the sets came from a file.
+    pub fn pick(&self, picker: &mut ParamSetPicker) -> (&QueryParams, Option<usize>) {
+        let index = picker.next_index() % self.sets.len();
+
+        (&self.sets[index], self.from_file.then_some(index + 1))
+    }
+
+    pub fn get(&self, index: usize) -> Option<&QueryParams> {
+        self.sets.get(index)
+    }
+
+    pub fn to_json(&self) -> Vec<Value> {
+        self.sets
+            .iter()
+            .map(|s| Value::Object(s.as_map().clone()))
+            .collect()
+    }
+
+    pub fn picker(&self) -> ParamSetPicker {
+        let seed = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        ParamSetPicker::new(self.order, self.sets.len(), seed)
+    }
+}
+
+/// Picks the parameter set for each benchmark run.
+#[derive(Debug, Clone)]
+pub struct ParamSetPicker {
+    order: ParamSetOrder,
+    num_sets: usize,
+    next: usize,
+    state: u64,
+}
+
+impl ParamSetPicker {
+    fn new(order: ParamSetOrder, num_sets: usize, seed: u64) -> Self {
+        Self {
+            order,
+            num_sets,
+            next: 0,
+            // xorshift gets stuck at 0
+            state: seed | 1,
+        }
+    }
+
+    /// Returns the index of the parameter set to use for the next run.
+    pub fn next_index(&mut self) -> usize {
+        match self.order {
+            ParamSetOrder::Cycle => {
+                let index = self.next % self.num_sets;
+                self.next += 1;
+                index
+            }
+            ParamSetOrder::Sample => {
+                // xorshift64*; good enough for spreading runs over parameter sets
+                self.state ^= self.state >> 12;
+                self.state ^= self.state << 25;
+                self.state ^= self.state >> 27;
+                let random = self.state.wrapping_mul(0x2545_f491_4f6c_dd1d);
+
+                (random % self.num_sets as u64) as usize
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_yaml_snapshot;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parsing_param_sets_from_csv_works() -> anyhow::Result<()> {
+        // GIVEN
+        let contents = r#"id,name,tags
+1,Alice,"[""a"", ""b""]"
+2,"Bob, Jr.",[]
+"#;
+
+        // WHEN
+        let result = ParamSets::from_csv(contents, ParamSetOrder::Cycle)?;
+
+        // THEN
+        assert_yaml_snapshot!(result.to_json(), @r#"
+        - id: 1
+          name: Alice
+          tags:
+            - a
+            - b
+        - id: 2
+          name: "Bob, Jr."
+          tags: []
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parsing_param_sets_from_jsonl_works() -> anyhow::Result<()> {
+        // GIVEN
+        let contents = r#"{"id": 1, "name": "Alice"}
+
+{"id": 2, "name": "Bob"}
+"#;
+        let base =
+            QueryParams::from_json(r#"{"limit": 10, "id": 0}"#).map_err(|e| anyhow::anyhow!(e))?;
+
+        // WHEN
+        let result = ParamSets::from_jsonl(contents, ParamSetOrder::Cycle)?.with_base(&base);
+
+        // THEN
+        assert_yaml_snapshot!(result.to_json(), @"
+        - id: 1
+          limit: 10
+          name: Alice
+        - id: 2
+          limit: 10
+          name: Bob
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn cycling_through_param_sets_works() {
+        // GIVEN
+        let mut picker = ParamSetPicker::new(ParamSetOrder::Cycle, 3, 42);
+
+        // WHEN
+        let result: Vec<usize> = (0..7).map(|_| picker.next_index()).collect();
+
+        // THEN
+        assert_eq!(result, vec![0, 1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn sampling_param_sets_stays_within_bounds() {
+        // GIVEN
+        let mut picker = ParamSetPicker::new(ParamSetOrder::Sample, 4, 42);
+
+        // WHEN
+        let result: Vec<usize> = (0..200).map(|_| picker.next_index()).collect();
+
+        // THEN
+        assert!(result.iter().all(|i| *i < 4));
+        assert!((0..4).all(|i| result.contains(&i)));
+    }
+
+    //-------------//
+    //  FAILURES   //
+    //-------------//
+
+    #[test]
+    fn parsing_param_sets_fails_for_invalid_input() {
+        // GIVEN
+        let csv_without_rows = "id,name\n";
+        let csv_with_invalid_key = "id,first name\n1,Alice\n";
+        let jsonl_with_invalid_line = "{\"id\": 1}\n[1, 2]\n";
+
+        // WHEN
+        let result = [
+            ParamSets::from_csv(csv_without_rows, ParamSetOrder::Cycle),
+            ParamSets::from_csv(csv_with_invalid_key, ParamSetOrder::Cycle),
+            ParamSets::from_jsonl(jsonl_with_invalid_line, ParamSetOrder::Cycle),
+        ]
+        .map(|r| r.expect_err("parsing should've failed").to_string());
+
+        // THEN
+        assert_eq!(
+            result,
+            [
+                "file doesn't contain any parameter sets",
+                "parameter set on line 2 is invalid: parameter key can only contain alphanumeric characters and underscores",
+                "parameter set on line 2 is invalid: parameters need to be a JSON object",
+            ]
+        );
+    }
+}
@@ -0,0 +1,207 @@
+use serde_json::{Map, Value};
+use std::str::FromStr;
+
+/// Parameters to be bound to `$placeholders` in a query.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueryParams(Map<String, Value>);
+
+impl QueryParams {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn as_map(&self) -> &Map<String, Value> {
+        &self.0
+    }
+
+    pub fn insert(&mut self, param: QueryParam) {
+        self.0.insert(param.key, param.value);
+    }
+
+    /// Adds all the parameters of another set, replacing ones with the same key.
+    pub fn extend(&mut self, other: &QueryParams) {
+        self.0
+            .extend(other.0.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+
+    pub fn remove(&mut self, key: &str) -> bool {
+        self.0.remove(key).is_some()
+    }
+
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, &'static str> {
+        match serde_json::from_str::<Value>(contents) {
+            Ok(Value::Object(map)) => Ok(Self(map)),
+            Ok(_) => Err("parameters need to be a JSON object"),
+            Err(_) => Err("parameters are not valid JSON"),
+        }
+    }
+}
+
+impl std::fmt::Display for QueryParams {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let lines = self
+            .0
+            .iter()
+            .map(|(k, v)| format!("${k} = {v}"))
+            .collect::<Vec<_>>();
+
+        f.write_str(&lines.join("\n"))
+    }
+}
+
+/// A single query parameter, specified as `key=value`.
+///
+/// The value is parsed as JSON if possible (so that numbers, booleans, lists and maps retain their
+/// type), and is treated as a plain string otherwise.
+#[derive(Debug, Clone)]
+pub struct QueryParam {
+    key: String,
+    value: Value,
+}
+
+impl QueryParam {
+    pub fn new(key: &str, raw_value: &str) -> Result<Self, &'static str> {
+        let key = key.trim().trim_start_matches('$');
+        if key.is_empty() {
+            return Err("parameter key is empty");
+        }
+
+        if !key.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err("parameter key can only contain alphanumeric characters and underscores");
+        }
+
+        let raw_value = raw_value.trim();
+        let value = serde_json::from_str(raw_value)
+            .unwrap_or_else(|_| Value::String(raw_value.to_string()));
+
+        Ok(Self {
+            key: key.to_string(),
+            value,
+        })
+    }
+}
+
+impl std::fmt::Display for QueryParam {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.key, self.value)
+    }
+}
+
+impl FromStr for QueryParam {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, value) = s
+            .split_once('=')
+            .ok_or("parameter needs to be in the form key=value")?;
+
+        Self::new(key, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::{assert_snapshot, assert_yaml_snapshot};
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn query_param_values_are_parsed_as_json() -> anyhow::Result<()> {
+        // GIVEN
+        let inputs = [
+            "name=Alice",
+            "quoted=\"42\"",
+            "age=42",
+            "score=9.5",
+            "active=true",
+            "nothing=null",
+            "ids=[1, 2, 3]",
+            "props={\"city\": \"Berlin\"}",
+            "$prefixed=value with spaces",
+        ];
+
+        // WHEN
+        let mut params = QueryParams::default();
+        for input in inputs {
+            params.insert(QueryParam::from_str(input).map_err(|e| anyhow::anyhow!(e))?);
+        }
+
+        // THEN
+        assert_yaml_snapshot!(params.as_map(), @r#"
+        active: true
+        age: 42
+        ids:
+          - 1
+          - 2
+          - 3
+        name: Alice
+        nothing: ~
+        prefixed: value with spaces
+        props:
+          city: Berlin
+        quoted: "42"
+        score: 9.5
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn query_params_can_be_parsed_from_a_json_object() -> anyhow::Result<()> {
+        // GIVEN
+        let contents = r#"{"name": "Alice", "limit": 10}"#;
+
+        // WHEN
+        let params = QueryParams::from_json(contents).map_err(|e| anyhow::anyhow!(e))?;
+
+        // THEN
+        assert_snapshot!(params, @r#"
+        $limit = 10
+        $name = "Alice"
+        "#);
+
+        Ok(())
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn query_param_fails_to_parse_without_separator() {
+        // GIVEN
+        // WHEN
+        let result = QueryParam::from_str("name").expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"parameter needs to be in the form key=value");
+    }
+
+    #[test]
+    fn query_param_fails_to_parse_invalid_key() {
+        // GIVEN
+        // WHEN
+        let result =
+            QueryParam::from_str("na-me=Alice").expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"parameter key can only contain alphanumeric characters and underscores");
+    }
+
+    #[test]
+    fn query_params_fail_to_parse_from_a_json_array() {
+        // GIVEN
+        // WHEN
+        let result = QueryParams::from_json("[1, 2]").expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"parameters need to be a JSON object");
+    }
+}
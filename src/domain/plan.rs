@@ -0,0 +1,63 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum PlanMode {
+    Explain,
+    Profile,
+}
+
+impl PlanMode {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PlanMode::Explain => "explain",
+            PlanMode::Profile => "profile",
+        }
+    }
+}
+
+impl std::fmt::Display for PlanMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum PlanFormat {
+    Tree,
+    Json,
+}
+
+impl std::fmt::Display for PlanFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlanFormat::Tree => write!(f, "tree"),
+            PlanFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueryPlan {
+    pub mode: PlanMode,
+    pub root: PlanOperator,
+}
+
+/// A single operator in a query plan. Statistics are only present when the database reports them
+/// for the mode the plan was requested in.
+#[derive(Debug, Default, Serialize)]
+pub struct PlanOperator {
+    pub name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_rows: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub db_hits: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<PlanOperator>,
+}
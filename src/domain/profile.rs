@@ -0,0 +1,206 @@
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Contents of grafq's config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProfilesConfig {
+    #[serde(default)]
+    profiles: BTreeMap<String, ConnectionProfile>,
+}
+
+impl ProfilesConfig {
+    pub fn get(&self, name: &str) -> Option<&ConnectionProfile> {
+        self.profiles.get(name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.profiles.keys().map(|k| k.as_str()).collect()
+    }
+}
+
+/// A named set of connection settings for a database.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectionProfile {
+    pub uri: Option<String>,
+    pub user: Option<String>,
+    pub password: Option<String>,
+    pub password_env: Option<String>,
+    pub password_cmd: Option<String>,
+    pub database: Option<String>,
+    pub aws_region: Option<String>,
+    pub aws_profile: Option<String>,
+    /// Reject queries that write to the database
+    pub read_only: Option<bool>,
+}
+
+/// Where a password is to be read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecretSource {
+    Plain(String),
+    EnvVar(String),
+    Command(String),
+}
+
+impl ConnectionProfile {
+    pub fn password_source(&self) -> Result<Option<SecretSource>, &'static str> {
+        match (&self.password, &self.password_env, &self.password_cmd) {
+            (None, None, None) => Ok(None),
+            (Some(p), None, None) => Ok(Some(SecretSource::Plain(p.clone()))),
+            (None, Some(v), None) => Ok(Some(SecretSource::EnvVar(v.clone()))),
+            (None, None, Some(c)) => Ok(Some(SecretSource::Command(c.clone()))),
+            _ => Err("only one of password, password_env, and password_cmd can be set"),
+        }
+    }
+}
+
+impl std::fmt::Display for SecretSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretSource::Plain(_) => write!(f, "********"),
+            SecretSource::EnvVar(var) => write!(f, "******** (from ${var})"),
+            SecretSource::Command(_) => write!(f, "******** (from command)"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::{assert_debug_snapshot, assert_snapshot};
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn profiles_config_can_be_parsed_from_toml() -> anyhow::Result<()> {
+        // GIVEN
+        let contents = r#"
+[profiles.local]
+uri = "bolt://127.0.0.1:7687"
+user = "neo4j"
+password_env = "NEO4J_LOCAL_PASSWORD"
+database = "neo4j"
+
+[profiles.prod]
+uri = "https://abc.xyz.us-east-1.neptune.amazonaws.com:8182"
+aws_region = "us-east-1"
+aws_profile = "prod"
+read_only = true
+"#;
+
+        // WHEN
+        let config: ProfilesConfig = toml::from_str(contents)?;
+
+        // THEN
+        assert_debug_snapshot!(config, @r#"
+        ProfilesConfig {
+            profiles: {
+                "local": ConnectionProfile {
+                    uri: Some(
+                        "bolt://127.0.0.1:7687",
+                    ),
+                    user: Some(
+                        "neo4j",
+                    ),
+                    password: None,
+                    password_env: Some(
+                        "NEO4J_LOCAL_PASSWORD",
+                    ),
+                    password_cmd: None,
+                    database: Some(
+                        "neo4j",
+                    ),
+                    aws_region: None,
+                    aws_profile: None,
+                    read_only: None,
+                },
+                "prod": ConnectionProfile {
+                    uri: Some(
+                        "https://abc.xyz.us-east-1.neptune.amazonaws.com:8182",
+                    ),
+                    user: None,
+                    password: None,
+                    password_env: None,
+                    password_cmd: None,
+                    database: None,
+                    aws_region: Some(
+                        "us-east-1",
+                    ),
+                    aws_profile: Some(
+                        "prod",
+                    ),
+                    read_only: Some(
+                        true,
+                    ),
+                },
+            },
+        }
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn secret_source_is_redacted_when_displayed() {
+        // GIVEN
+        let sources = [
+            SecretSource::Plain("hunter2".to_string()),
+            SecretSource::EnvVar("NEO4J_PROD_PASSWORD".to_string()),
+            SecretSource::Command("pass show neo4j/prod".to_string()),
+        ];
+
+        // WHEN
+        let result = sources
+            .iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @r"
+        ********
+        ******** (from $NEO4J_PROD_PASSWORD)
+        ******** (from command)
+        ");
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn profiles_config_fails_to_parse_unknown_keys() {
+        // GIVEN
+        let contents = r#"
+[profiles.local]
+url = "bolt://127.0.0.1:7687"
+"#;
+
+        // WHEN
+        let result = toml::from_str::<ProfilesConfig>(contents);
+
+        // THEN
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn password_source_fails_if_several_sources_are_set() {
+        // GIVEN
+        let profile = ConnectionProfile {
+            password: Some("hunter2".to_string()),
+            password_env: Some("NEO4J_PASSWORD".to_string()),
+            ..Default::default()
+        };
+
+        // WHEN
+        let result = profile
+            .password_source()
+            .expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"only one of password, password_env, and password_cmd can be set");
+    }
+}
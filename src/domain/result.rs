@@ -1,10 +1,18 @@
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::str::FromStr;
 
-#[derive(Debug, Clone, clap::ValueEnum)]
+#[derive(Debug, Clone, PartialEq, clap::ValueEnum)]
 pub enum ResultsFormat {
     Csv,
     Json,
+    Ndjson,
+    Yaml,
+    Markdown,
+    Tsv,
+    Html,
+    Parquet,
+    Arrow,
+    Sqlite,
 }
 
 impl ResultsFormat {
@@ -12,38 +20,106 @@ impl ResultsFormat {
         match self {
             ResultsFormat::Csv => "csv",
             ResultsFormat::Json => "json",
+            ResultsFormat::Ndjson => "ndjson",
+            ResultsFormat::Yaml => "yaml",
+            ResultsFormat::Markdown => "md",
+            ResultsFormat::Tsv => "tsv",
+            ResultsFormat::Html => "html",
+            ResultsFormat::Parquet => "parquet",
+            ResultsFormat::Arrow => "arrow",
+            ResultsFormat::Sqlite => "db",
         }
     }
+
+    /// Whether results in this format can't be read as text (eg. in a pager).
+    pub fn is_binary(&self) -> bool {
+        matches!(
+            self,
+            ResultsFormat::Parquet | ResultsFormat::Arrow | ResultsFormat::Sqlite
+        )
+    }
 }
 
 impl FromStr for ResultsFormat {
-    type Err = &'static str;
+    type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let trimmed = s.trim();
-        match trimmed {
-            "csv" => Ok(Self::Csv),
-            "json" => Ok(Self::Json),
-            _ => Err("invalid format provided; allowed values: [csv, json]"),
-        }
+        <Self as clap::ValueEnum>::from_str(s.trim(), false).map_err(|_| {
+            let allowed = <Self as clap::ValueEnum>::value_variants()
+                .iter()
+                .filter_map(clap::ValueEnum::to_possible_value)
+                .map(|v| v.get_name().to_string())
+                .collect::<Vec<_>>();
+
+            format!(
+                "invalid format provided; allowed values: [{}]",
+                allowed.join(", ")
+            )
+        })
     }
 }
 
 impl std::fmt::Display for ResultsFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", self.extension())
+        match clap::ValueEnum::to_possible_value(self) {
+            Some(value) => write!(f, "{}", value.get_name()),
+            None => write!(f, "{}", self.extension()),
+        }
+    }
+}
+
+/// Name of the table results are written to in a SQLite database; it's restricted to alphanumeric
+/// characters and "_", and cannot clash with the table queries are recorded in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableName(String);
+
+impl TableName {
+    pub fn value(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::fmt::Display for TableName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for TableName {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err("name cannot be empty");
+        }
+
+        if s.starts_with(|c: char| c.is_ascii_digit())
+            || !s.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(
+                r#"name can only contain alphanumeric characters and "_" (and cannot start with a digit)"#,
+            );
+        }
+
+        let lowercase = s.to_lowercase();
+        if lowercase == "queries" || lowercase.starts_with("sqlite_") {
+            return Err(r#"name is reserved (it cannot be "queries" or start with "sqlite_")"#);
+        }
+
+        Ok(TableName(s.to_string()))
     }
 }
 
 pub struct NonEmptyResults(Vec<Value>);
 
 impl NonEmptyResults {
+    #[cfg(test)]
     pub fn list(&self) -> &[Value] {
         &self.0
     }
 
-    pub fn first(&self) -> &Value {
-        &self.0[0]
+    pub fn into_rows(self) -> Vec<Value> {
+        self.0
     }
 }
 
@@ -74,3 +150,652 @@ impl From<Vec<Value>> for QueryResults {
         QueryResults::NonEmpty(NonEmptyResults(value))
     }
 }
+
+/// Column that rows which aren't objects (eg. scalars or lists) are shown in.
+pub const VALUE_COLUMN: &str = "value";
+
+/// Headers for a set of rows: the keys of every object, in the order they first appear in, along
+/// with a "value" column if any of the rows isn't an object.
+pub fn get_headers<'a, I>(rows: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a Value>,
+{
+    let mut headers: Vec<String> = Vec::new();
+    for row in rows {
+        let keys: Vec<&str> = match row {
+            Value::Object(obj) => obj.keys().map(String::as_str).collect(),
+            _ => vec![VALUE_COLUMN],
+        };
+
+        for key in keys {
+            if !headers.iter().any(|h| h == key) {
+                headers.push(key.to_string());
+            }
+        }
+    }
+
+    headers
+}
+
+/// The value of a row under a header; rows that aren't objects only have one, under "value".
+pub fn get_cell<'a>(row: &'a Value, header: &str) -> Option<&'a Value> {
+    match row {
+        Value::Object(obj) => obj.get(header),
+        value => (header == VALUE_COLUMN).then_some(value),
+    }
+}
+
+const ENTITY_TYPE_KEY: &str = "~entityType";
+
+/// A node, relationship or path returned by a query.
+///
+/// Both engines' results are mapped to the shape Neptune uses for openCypher results, eg.
+/// `{"~id": "1", "~entityType": "node", "~labels": ["Person"], "~properties": {...}}`, which is what
+/// ends up in JSON output; tables and CSV files show entities as Cypher literals instead.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphEntity {
+    Node(Node),
+    Relationship(Relationship),
+    Path(Path),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Node {
+    pub id: String,
+    pub labels: Vec<String>,
+    pub properties: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Relationship {
+    pub id: String,
+    pub rel_type: String,
+    pub start: String,
+    pub end: String,
+    pub properties: Map<String, Value>,
+}
+
+/// `relationships[i]` connects `nodes[i]` and `nodes[i + 1]`, in either direction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Path {
+    pub nodes: Vec<Node>,
+    pub relationships: Vec<Relationship>,
+}
+
+impl GraphEntity {
+    pub fn from_value(value: &Value) -> Option<Self> {
+        let obj = value.as_object()?;
+
+        match obj.get(ENTITY_TYPE_KEY)?.as_str()? {
+            "node" => Node::from_map(obj).map(Self::Node),
+            "relationship" => Relationship::from_map(obj).map(Self::Relationship),
+            "path" => Path::from_map(obj).map(Self::Path),
+            _ => None,
+        }
+    }
+}
+
+impl Node {
+    fn from_map(obj: &Map<String, Value>) -> Option<Self> {
+        let labels = match obj.get("~labels") {
+            Some(Value::Array(labels)) => labels
+                .iter()
+                .map(|l| l.as_str().map(str::to_string))
+                .collect::<Option<Vec<_>>>()?,
+            Some(Value::String(label)) => vec![label.clone()],
+            _ => vec![],
+        };
+
+        Some(Self {
+            id: id_from_value(obj.get("~id")?)?,
+            labels,
+            properties: properties_from_map(obj),
+        })
+    }
+}
+
+impl Relationship {
+    fn from_map(obj: &Map<String, Value>) -> Option<Self> {
+        Some(Self {
+            id: id_from_value(obj.get("~id")?)?,
+            rel_type: obj.get("~type")?.as_str()?.to_string(),
+            start: id_from_value(obj.get("~start")?)?,
+            end: id_from_value(obj.get("~end")?)?,
+            properties: properties_from_map(obj),
+        })
+    }
+
+    fn connects(&self, a: &Node, b: &Node) -> bool {
+        (self.start == a.id && self.end == b.id) || (self.start == b.id && self.end == a.id)
+    }
+}
+
+impl Path {
+    fn from_map(obj: &Map<String, Value>) -> Option<Self> {
+        let nodes = obj
+            .get("~nodes")?
+            .as_array()?
+            .iter()
+            .map(|v| v.as_object().and_then(Node::from_map))
+            .collect::<Option<Vec<_>>>()?;
+        let relationships = obj
+            .get("~relationships")?
+            .as_array()?
+            .iter()
+            .map(|v| v.as_object().and_then(Relationship::from_map))
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            nodes,
+            relationships,
+        })
+    }
+
+    /// Neptune returns paths as lists alternating between nodes and the relationships connecting
+    /// them, eg. `[node, relationship, node]`.
+    pub fn from_chain(items: &[Value]) -> Option<Self> {
+        if items.len() < 3 || items.len().is_multiple_of(2) {
+            return None;
+        }
+
+        let mut nodes = vec![];
+        let mut relationships = vec![];
+        for (i, item) in items.iter().enumerate() {
+            match (i % 2, GraphEntity::from_value(item)?) {
+                (0, GraphEntity::Node(node)) => nodes.push(node),
+                (1, GraphEntity::Relationship(rel)) => relationships.push(rel),
+                _ => return None,
+            }
+        }
+
+        let connected = relationships
+            .iter()
+            .zip(nodes.windows(2))
+            .all(|(rel, pair)| rel.connects(&pair[0], &pair[1]));
+        if !connected {
+            return None;
+        }
+
+        Some(Self {
+            nodes,
+            relationships,
+        })
+    }
+}
+
+impl From<Node> for Value {
+    fn from(node: Node) -> Self {
+        let mut obj = Map::new();
+        obj.insert("~id".to_string(), Value::String(node.id));
+        obj.insert(ENTITY_TYPE_KEY.to_string(), Value::from("node"));
+        obj.insert(
+            "~labels".to_string(),
+            Value::Array(node.labels.into_iter().map(Value::String).collect()),
+        );
+        obj.insert("~properties".to_string(), Value::Object(node.properties));
+
+        Value::Object(obj)
+    }
+}
+
+impl From<Relationship> for Value {
+    fn from(rel: Relationship) -> Self {
+        let mut obj = Map::new();
+        obj.insert("~id".to_string(), Value::String(rel.id));
+        obj.insert(ENTITY_TYPE_KEY.to_string(), Value::from("relationship"));
+        obj.insert("~type".to_string(), Value::String(rel.rel_type));
+        obj.insert("~start".to_string(), Value::String(rel.start));
+        obj.insert("~end".to_string(), Value::String(rel.end));
+        obj.insert("~properties".to_string(), Value::Object(rel.properties));
+
+        Value::Object(obj)
+    }
+}
+
+impl From<Path> for Value {
+    fn from(path: Path) -> Self {
+        let mut obj = Map::new();
+        obj.insert(ENTITY_TYPE_KEY.to_string(), Value::from("path"));
+        obj.insert(
+            "~nodes".to_string(),
+            Value::Array(path.nodes.into_iter().map(Value::from).collect()),
+        );
+        obj.insert(
+            "~relationships".to_string(),
+            Value::Array(path.relationships.into_iter().map(Value::from).collect()),
+        );
+
+        Value::Object(obj)
+    }
+}
+
+impl From<GraphEntity> for Value {
+    fn from(entity: GraphEntity) -> Self {
+        match entity {
+            GraphEntity::Node(node) => node.into(),
+            GraphEntity::Relationship(rel) => rel.into(),
+            GraphEntity::Path(path) => path.into(),
+        }
+    }
+}
+
+impl std::fmt::Display for Node {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "(")?;
+        for label in &self.labels {
+            write!(f, ":{}", escape_name(label))?;
+        }
+        write_properties(f, &self.properties, !self.labels.is_empty())?;
+        write!(f, ")")
+    }
+}
+
+impl std::fmt::Display for Relationship {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[:{}", escape_name(&self.rel_type))?;
+        write_properties(f, &self.properties, true)?;
+        write!(f, "]")
+    }
+}
+
+impl std::fmt::Display for Path {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some(first) = self.nodes.first() else {
+            return Ok(());
+        };
+
+        write!(f, "{first}")?;
+        for (rel, pair) in self.relationships.iter().zip(self.nodes.windows(2)) {
+            if rel.start == pair[0].id {
+                write!(f, "-{rel}->{}", pair[1])?;
+            } else {
+                write!(f, "<-{rel}-{}", pair[1])?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for GraphEntity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphEntity::Node(node) => write!(f, "{node}"),
+            GraphEntity::Relationship(rel) => write!(f, "{rel}"),
+            GraphEntity::Path(path) => write!(f, "{path}"),
+        }
+    }
+}
+
+/// Replaces lists that form a path (see [`Path::from_chain`]) with path entities, at any depth.
+pub fn group_paths(value: Value) -> Value {
+    match value {
+        Value::Array(items) => match Path::from_chain(&items) {
+            Some(path) => path.into(),
+            None => Value::Array(items.into_iter().map(group_paths).collect()),
+        },
+        Value::Object(obj) if !obj.contains_key(ENTITY_TYPE_KEY) => {
+            Value::Object(obj.into_iter().map(|(k, v)| (k, group_paths(v))).collect())
+        }
+        other => other,
+    }
+}
+
+/// Renders a value as a Cypher literal if it is, or contains, a graph entity; eg.
+/// `[(:Person {name: "x"}), 2]`. Other values are left for the caller to render as it sees fit.
+pub fn graph_display(value: &Value) -> Option<String> {
+    if !contains_graph_entity(value) {
+        return None;
+    }
+
+    Some(CypherLiteral(value).to_string())
+}
+
+fn contains_graph_entity(value: &Value) -> bool {
+    match value {
+        Value::Array(items) => items.iter().any(contains_graph_entity),
+        Value::Object(obj) => {
+            GraphEntity::from_value(value).is_some() || obj.values().any(contains_graph_entity)
+        }
+        _ => false,
+    }
+}
+
+struct CypherLiteral<'a>(&'a Value);
+
+impl std::fmt::Display for CypherLiteral<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.0 {
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", CypherLiteral(item))?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(obj) => match GraphEntity::from_value(self.0) {
+                Some(entity) => write!(f, "{entity}"),
+                None if obj.is_empty() => write!(f, "{{}}"),
+                None => write_properties(f, obj, false),
+            },
+            other => write!(f, "{other}"),
+        }
+    }
+}
+
+fn write_properties(
+    f: &mut std::fmt::Formatter<'_>,
+    properties: &Map<String, Value>,
+    leading_space: bool,
+) -> std::fmt::Result {
+    if properties.is_empty() {
+        return Ok(());
+    }
+
+    if leading_space {
+        write!(f, " ")?;
+    }
+    write!(f, "{{")?;
+    for (i, (key, value)) in properties.iter().enumerate() {
+        if i > 0 {
+            write!(f, ", ")?;
+        }
+        write!(f, "{}: {}", escape_name(key), CypherLiteral(value))?;
+    }
+    write!(f, "}}")
+}
+
+/// Names that aren't plain identifiers need to be backticked in Cypher.
+/// Wraps names that aren't plain identifiers (eg. labels with spaces in them) in backticks.
+pub fn escape_name(name: &str) -> String {
+    let mut chars = name.chars();
+    let is_identifier = chars.next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && chars.all(|c| c.is_alphanumeric() || c == '_');
+
+    if is_identifier {
+        name.to_string()
+    } else {
+        format!("`{}`", name.replace('`', "``"))
+    }
+}
+
+fn id_from_value(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn properties_from_map(obj: &Map<String, Value>) -> Map<String, Value> {
+    match obj.get("~properties") {
+        Some(Value::Object(properties)) => properties.clone(),
+        _ => Map::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use serde_json::json;
+
+    fn node(id: &str, label: &str, name: &str) -> Value {
+        json!({
+            "~id": id,
+            "~entityType": "node",
+            "~labels": [label],
+            "~properties": {"name": name}
+        })
+    }
+
+    fn relationship(id: &str, rel_type: &str, start: &str, end: &str) -> Value {
+        json!({
+            "~id": id,
+            "~entityType": "relationship",
+            "~type": rel_type,
+            "~start": start,
+            "~end": end,
+            "~properties": {}
+        })
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parsing_results_format_works() {
+        // GIVEN
+        // WHEN
+        let result: Vec<ResultsFormat> = ["csv", "ndjson", " yaml ", "markdown", "html"]
+            .into_iter()
+            .filter_map(|f| ResultsFormat::from_str(f).ok())
+            .collect();
+
+        // THEN
+        assert_snapshot!(format!("{result:?}"), @"[Csv, Ndjson, Yaml, Markdown, Html]");
+    }
+
+    #[test]
+    fn get_headers_returns_union_of_keys_in_order_of_appearance() {
+        // GIVEN
+        let rows = [
+            json!({"language": "Rust", "year": 2010}),
+            json!({"language": "Python", "creator": "Guido van Rossum"}),
+            json!(42),
+            json!({"year": 2009, "typing": "static"}),
+            json!(["a", "list"]),
+        ];
+
+        // WHEN
+        let result = get_headers(&rows);
+
+        // THEN
+        assert_eq!(result, ["language", "year", "creator", "value", "typing"]);
+    }
+
+    #[test]
+    fn get_cell_returns_non_object_rows_under_the_value_column() {
+        // GIVEN
+        let row = json!(42);
+
+        // WHEN
+        let result = (get_cell(&row, VALUE_COLUMN), get_cell(&row, "language"));
+
+        // THEN
+        assert_eq!(result, (Some(&json!(42)), None));
+    }
+
+    #[test]
+    fn parsing_table_name_works() {
+        // GIVEN
+        // WHEN
+        let result = TableName::from_str("top_candidates_2025");
+
+        // THEN
+        assert_eq!(
+            result.as_ref().map(|n| n.value()),
+            Ok("top_candidates_2025")
+        );
+    }
+
+    #[test]
+    fn graph_entities_are_displayed_as_cypher_literals() {
+        // GIVEN
+        let values = [
+            json!({
+                "~id": "1",
+                "~entityType": "node",
+                "~labels": ["Person", "Film Buff"],
+                "~properties": {"name": "Keanu \"Neo\" Reeves", "born": 1964, "roles": ["Neo"]}
+            }),
+            json!({"~id": 2, "~entityType": "node", "~labels": [], "~properties": {}}),
+            json!({
+                "~id": "3",
+                "~entityType": "relationship",
+                "~type": "ACTED_IN",
+                "~start": "1",
+                "~end": "4",
+                "~properties": {"roles": ["Neo"]}
+            }),
+        ];
+
+        // WHEN
+        let result = values
+            .iter()
+            .map(|v| {
+                GraphEntity::from_value(v)
+                    .expect("entity should've been parsed")
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_snapshot!(result.join("\n"), @r#"
+        (:Person:`Film Buff` {born: 1964, name: "Keanu \"Neo\" Reeves", roles: ["Neo"]})
+        ()
+        [:ACTED_IN {roles: ["Neo"]}]
+        "#);
+    }
+
+    #[test]
+    fn paths_are_displayed_with_relationship_directions() {
+        // GIVEN
+        let chain = vec![
+            node("1", "Person", "Keanu"),
+            relationship("10", "ACTED_IN", "1", "2"),
+            node("2", "Movie", "The Matrix"),
+            relationship("11", "DIRECTED", "3", "2"),
+            node("3", "Person", "Lana"),
+        ];
+
+        // WHEN
+        let path = Path::from_chain(&chain).expect("path should've been built");
+
+        // THEN
+        assert_eq!(path.nodes.len(), 3);
+        assert_eq!(path.relationships.len(), 2);
+        assert_snapshot!(path, @r#"(:Person {name: "Keanu"})-[:ACTED_IN]->(:Movie {name: "The Matrix"})<-[:DIRECTED]-(:Person {name: "Lana"})"#);
+    }
+
+    #[test]
+    fn paths_survive_a_round_trip_through_json() {
+        // GIVEN
+        let chain = vec![
+            node("1", "Person", "Keanu"),
+            relationship("10", "ACTED_IN", "1", "2"),
+            node("2", "Movie", "The Matrix"),
+        ];
+        let path = Path::from_chain(&chain).expect("path should've been built");
+
+        // WHEN
+        let result = GraphEntity::from_value(&Value::from(path.clone()));
+
+        // THEN
+        assert_eq!(result, Some(GraphEntity::Path(path)));
+    }
+
+    #[test]
+    fn group_paths_replaces_nested_chains() {
+        // GIVEN
+        let row = json!({
+            "p": [
+                node("1", "Person", "Keanu"),
+                relationship("10", "ACTED_IN", "1", "2"),
+                node("2", "Movie", "The Matrix"),
+            ],
+            "names": ["Keanu", "Carrie-Anne", "Laurence"],
+        });
+
+        // WHEN
+        let result = group_paths(row);
+
+        // THEN
+        assert_eq!(result["p"]["~entityType"], "path");
+        assert_eq!(result["names"], json!(["Keanu", "Carrie-Anne", "Laurence"]));
+    }
+
+    #[test]
+    fn graph_display_renders_values_containing_entities() {
+        // GIVEN
+        let values = [
+            json!([node("1", "Person", "Keanu"), 2]),
+            json!({"who": node("1", "Person", "Keanu"), "`weird` key": null}),
+            json!([1, 2]),
+            json!({"name": "Keanu"}),
+        ];
+
+        // WHEN
+        let result = values.iter().map(graph_display).collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(
+            result,
+            vec![
+                Some(r#"[(:Person {name: "Keanu"}), 2]"#.to_string()),
+                Some(r#"{```weird`` key`: null, who: (:Person {name: "Keanu"})}"#.to_string()),
+                None,
+                None,
+            ]
+        );
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_table_name_fails_for_invalid_names() {
+        // GIVEN
+        // WHEN
+        let result: Vec<String> = ["", "1st", "my-table", "queries", "sqlite_master"]
+            .into_iter()
+            .filter_map(|n| TableName::from_str(n).err())
+            .map(|e| e.to_string())
+            .collect();
+
+        // THEN
+        assert_snapshot!(result.join("\n"), @r#"
+        name cannot be empty
+        name can only contain alphanumeric characters and "_" (and cannot start with a digit)
+        name can only contain alphanumeric characters and "_" (and cannot start with a digit)
+        name is reserved (it cannot be "queries" or start with "sqlite_")
+        name is reserved (it cannot be "queries" or start with "sqlite_")
+        "#);
+    }
+
+    #[test]
+    fn parsing_unknown_results_format_fails() {
+        // GIVEN
+        // WHEN
+        let result = ResultsFormat::from_str("xml").expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"invalid format provided; allowed values: [csv, json, ndjson, yaml, markdown, tsv, html, parquet, arrow, sqlite]");
+    }
+
+    #[test]
+    fn chains_that_are_not_connected_are_not_paths() {
+        // GIVEN
+        let disconnected = vec![
+            node("1", "Person", "Keanu"),
+            relationship("10", "ACTED_IN", "1", "5"),
+            node("2", "Movie", "The Matrix"),
+        ];
+        let out_of_order = vec![
+            node("1", "Person", "Keanu"),
+            node("2", "Movie", "The Matrix"),
+            relationship("10", "ACTED_IN", "1", "2"),
+        ];
+
+        // WHEN
+        // THEN
+        assert!(Path::from_chain(&disconnected).is_none());
+        assert!(Path::from_chain(&out_of_order).is_none());
+        assert!(Path::from_chain(&[node("1", "Person", "Keanu")]).is_none());
+    }
+}
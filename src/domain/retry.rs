@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+const BASE_DELAY: Duration = Duration::from_millis(100);
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+/// How queries that fail with transient errors are retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    max_retries: u16,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u16) -> Self {
+        Self {
+            max_retries,
+            base_delay: BASE_DELAY,
+            max_delay: MAX_DELAY,
+        }
+    }
+
+    pub fn max_retries(&self) -> u16 {
+        self.max_retries
+    }
+
+    /// Returns how long to wait before the given retry (starting at 1), or `None` if retries are
+    /// exhausted.
+    ///
+    /// The delay doubles with every retry (up to a cap). Only half of it is fixed; the other half
+    /// is scaled by `jitter` (expected to be in `[0, 1)`), so that clients that failed at the same
+    /// time don't retry in lockstep.
+    pub fn delay(&self, retry: u16, jitter: f64) -> Option<Duration> {
+        if retry == 0 || retry > self.max_retries {
+            return None;
+        }
+
+        let backoff = self
+            .base_delay
+            .saturating_mul(2_u32.saturating_pow(u32::from(retry - 1)))
+            .min(self.max_delay);
+        let half = backoff / 2;
+
+        Some(half + half.mul_f64(jitter.clamp(0.0, 1.0)))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn delay_grows_exponentially_up_to_a_cap() {
+        // GIVEN
+        let policy = RetryPolicy::new(10);
+
+        // WHEN
+        let result = (1..=8)
+            .map(|r| policy.delay(r, 0.0).map(|d| d.as_millis()))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(
+            result,
+            vec![
+                Some(50),
+                Some(100),
+                Some(200),
+                Some(400),
+                Some(800),
+                Some(1600),
+                Some(2500),
+                Some(2500)
+            ]
+        );
+    }
+
+    #[test]
+    fn delay_is_scaled_by_jitter() {
+        // GIVEN
+        let policy = RetryPolicy::new(3);
+
+        // WHEN
+        let result = [0.0, 0.5, 0.99]
+            .iter()
+            .map(|j| policy.delay(3, *j).map(|d| d.as_millis()))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(result, vec![Some(200), Some(300), Some(398)]);
+    }
+
+    #[test]
+    fn delay_is_none_once_retries_are_exhausted() {
+        // GIVEN
+        let policy = RetryPolicy::new(2);
+        let no_retries = RetryPolicy::new(0);
+
+        // WHEN
+        // THEN
+        assert!(policy.delay(2, 0.5).is_some());
+        assert!(policy.delay(3, 0.5).is_none());
+        assert!(no_retries.delay(1, 0.5).is_none());
+    }
+}
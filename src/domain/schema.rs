@@ -0,0 +1,190 @@
+use super::escape_name;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Names of the labels, relationship types, and property keys in a database, as used for
+/// completion in the console.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SchemaNames {
+    pub labels: Vec<String>,
+    pub relationship_types: Vec<String>,
+    pub property_keys: Vec<String>,
+}
+
+impl SchemaNames {
+    pub fn new(
+        labels: impl IntoIterator<Item = String>,
+        relationship_types: impl IntoIterator<Item = String>,
+        property_keys: impl IntoIterator<Item = String>,
+    ) -> Self {
+        fn sorted(names: impl IntoIterator<Item = String>) -> Vec<String> {
+            let mut names: Vec<String> = names.into_iter().collect();
+            names.sort();
+            names.dedup();
+            names
+        }
+
+        Self {
+            labels: sorted(labels),
+            relationship_types: sorted(relationship_types),
+            property_keys: sorted(property_keys),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaNames {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} labels, {} relationship types, {} property keys",
+            self.labels.len(),
+            self.relationship_types.len(),
+            self.property_keys.len()
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum SchemaFormat {
+    Table,
+    Json,
+}
+
+impl FromStr for SchemaFormat {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "table" => Ok(Self::Table),
+            "json" => Ok(Self::Json),
+            _ => Err("invalid format provided; allowed values: [table, json]"),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SchemaFormat::Table => write!(f, "table"),
+            SchemaFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// A summary of what's in a database: its labels and relationship types (and how they're
+/// connected), the properties they have, and the indexes and constraints on them.
+///
+/// Databases don't expose all of this; eg. AWS Neptune's property graph summary doesn't include
+/// the patterns relationships connect, or the types of properties.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Schema {
+    pub labels: Vec<LabelSchema>,
+    pub relationship_types: Vec<RelationshipTypeSchema>,
+    pub patterns: Vec<SchemaPattern>,
+    pub properties: Vec<PropertySchema>,
+    pub indexes: Vec<IndexSchema>,
+    pub constraints: Vec<ConstraintSchema>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LabelSchema {
+    pub label: String,
+    pub count: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RelationshipTypeSchema {
+    pub relationship_type: String,
+    pub count: Option<i64>,
+}
+
+/// Labels of the nodes a relationship type connects, eg. (:Person)-[:ACTED_IN]->(:Movie).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaPattern {
+    pub from: String,
+    pub relationship_type: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityType {
+    Node,
+    Relationship,
+}
+
+/// Nodes with all of the labels, or relationships of the type, that a property, index, or
+/// constraint applies to. No labels means any node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaElement {
+    pub entity_type: EntityType,
+    pub labels_or_types: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PropertySchema {
+    pub on: SchemaElement,
+    pub key: String,
+    /// Types of the values observed for the property (eg. "String", "Long")
+    pub types: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct IndexSchema {
+    pub name: String,
+    pub index_type: String,
+    pub on: SchemaElement,
+    pub properties: Vec<String>,
+    pub state: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConstraintSchema {
+    pub name: String,
+    pub constraint_type: String,
+    pub on: SchemaElement,
+    pub properties: Vec<String>,
+}
+
+impl SchemaElement {
+    pub fn node(labels: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            entity_type: EntityType::Node,
+            labels_or_types: labels.into_iter().collect(),
+        }
+    }
+
+    pub fn relationship(types: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            entity_type: EntityType::Relationship,
+            labels_or_types: types.into_iter().collect(),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let names: String = self
+            .labels_or_types
+            .iter()
+            .map(|n| format!(":{}", escape_name(n)))
+            .collect();
+
+        match self.entity_type {
+            EntityType::Node => write!(f, "({names})"),
+            EntityType::Relationship => write!(f, "[{names}]"),
+        }
+    }
+}
+
+impl std::fmt::Display for SchemaPattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "(:{})-[:{}]->(:{})",
+            escape_name(&self.from),
+            escape_name(&self.relationship_type),
+            escape_name(&self.to)
+        )
+    }
+}
@@ -0,0 +1,168 @@
+use serde_json::Value;
+
+/// A consumer of rows that arrive incrementally while a query is being executed.
+pub trait RowSink {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()>;
+
+    /// Called once no more rows will be written.
+    fn finish(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Whether the sink has stopped accepting rows (eg. the user quit the pager), in which case
+    /// producers can stop early.
+    fn is_closed(&self) -> bool {
+        false
+    }
+}
+
+impl RowSink for Vec<Value> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        self.push(row);
+        Ok(())
+    }
+}
+
+impl<S: RowSink + ?Sized> RowSink for &mut S {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        (**self).write_row(row)
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        (**self).finish()
+    }
+
+    fn is_closed(&self) -> bool {
+        (**self).is_closed()
+    }
+}
+
+/// An absent sink discards every row.
+impl<S: RowSink> RowSink for Option<S> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        match self {
+            Some(sink) => sink.write_row(row),
+            None => Ok(()),
+        }
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        match self {
+            Some(sink) => sink.finish(),
+            None => Ok(()),
+        }
+    }
+
+    fn is_closed(&self) -> bool {
+        match self {
+            Some(sink) => sink.is_closed(),
+            None => true,
+        }
+    }
+}
+
+/// Writes each row to both sinks; closed only once both of them are.
+impl<A: RowSink, B: RowSink> RowSink for (A, B) {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        if !self.0.is_closed() {
+            self.0.write_row(row.clone())?;
+        }
+        if !self.1.is_closed() {
+            self.1.write_row(row)?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        let first = self.0.finish();
+        let second = self.1.finish();
+
+        first.and(second)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.0.is_closed() && self.1.is_closed()
+    }
+}
+
+/// Writes rows to a sink until they run out or the sink closes, returning the number written.
+pub fn write_rows<I, S>(rows: I, sink: &mut S) -> anyhow::Result<u64>
+where
+    I: IntoIterator<Item = Value>,
+    S: RowSink + ?Sized,
+{
+    let mut num_rows = 0;
+    for row in rows {
+        sink.write_row(row)?;
+        num_rows += 1;
+
+        if sink.is_closed() {
+            break;
+        }
+    }
+
+    Ok(num_rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    struct ClosingSink {
+        rows: Vec<Value>,
+        capacity: usize,
+    }
+
+    impl RowSink for ClosingSink {
+        fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+            self.rows.push(row);
+            Ok(())
+        }
+
+        fn is_closed(&self) -> bool {
+            self.rows.len() >= self.capacity
+        }
+    }
+
+    #[test]
+    fn write_rows_writes_to_both_sinks_of_a_pair() -> anyhow::Result<()> {
+        // GIVEN
+        let mut first = vec![];
+        let mut second = Some(vec![]);
+        let rows = vec![json!({"a": 1}), json!({"a": 2})];
+
+        // WHEN
+        let num_rows = write_rows(rows, &mut (&mut first, &mut second))?;
+
+        // THEN
+        assert_eq!(num_rows, 2);
+        assert_eq!(first, vec![json!({"a": 1}), json!({"a": 2})]);
+        assert_eq!(second, Some(first));
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_rows_stops_once_all_sinks_are_closed() -> anyhow::Result<()> {
+        // GIVEN
+        let mut sink = (
+            ClosingSink {
+                rows: vec![],
+                capacity: 1,
+            },
+            None::<Vec<Value>>,
+        );
+        let rows = vec![json!(1), json!(2), json!(3)];
+
+        // WHEN
+        let num_rows = write_rows(rows, &mut sink)?;
+
+        // THEN
+        assert_eq!(num_rows, 1);
+        assert_eq!(sink.0.rows, vec![json!(1)]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,150 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+/// How long a query is allowed to run before it's cancelled.
+///
+/// Specified as a number followed by a unit ("ms", "s", "m", or "h"); a bare number is treated as
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct QueryTimeout(Duration);
+
+impl QueryTimeout {
+    pub fn value(&self) -> Duration {
+        self.0
+    }
+}
+
+impl std::fmt::Display for QueryTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", format_duration(self.0))
+    }
+}
+
+impl FromStr for QueryTimeout {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_duration(s).map(QueryTimeout)
+    }
+}
+
+/// Formats a duration in the largest whole unit it can be expressed in.
+pub(super) fn format_duration(duration: Duration) -> String {
+    match duration.as_millis() {
+        m if m % 1000 != 0 => format!("{m}ms"),
+        m if m % 3_600_000 == 0 => format!("{}h", m / 3_600_000),
+        m if m % 60_000 == 0 => format!("{}m", m / 60_000),
+        m => format!("{}s", m / 1000),
+    }
+}
+
+/// Parses a non-zero duration specified as a number followed by a unit ("ms", "s", "m", or "h");
+/// a bare number is treated as seconds.
+pub(super) fn parse_duration(s: &str) -> Result<Duration, &'static str> {
+    let s = s.trim();
+    let split_at = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (number, unit) = s.split_at(split_at);
+
+    let number: u64 = number
+        .parse()
+        .map_err(|_| "value is not a valid duration (eg. 500ms, 30s, 2m)")?;
+
+    let millis_per_unit = match unit.trim() {
+        "ms" => 1,
+        "" | "s" => 1000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => return Err("invalid unit provided; allowed values: [ms, s, m, h]"),
+    };
+
+    if number == 0 {
+        return Err("needs to be greater than 0");
+    }
+
+    let millis = number
+        .checked_mul(millis_per_unit)
+        .ok_or("value is too large")?;
+
+    Ok(Duration::from_millis(millis))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parsing_timeouts_works() {
+        // GIVEN
+        let inputs = ["500ms", "30s", "45", "2m", "1h", " 90 s "];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| QueryTimeout::from_str(i).map(|t| t.value().as_millis()))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(
+            result,
+            vec![
+                Ok(500),
+                Ok(30_000),
+                Ok(45_000),
+                Ok(120_000),
+                Ok(3_600_000),
+                Ok(90_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn timeouts_are_displayed_in_the_largest_whole_unit() {
+        // GIVEN
+        let inputs = ["1500ms", "2000ms", "90s", "120s", "60m"];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| {
+                QueryTimeout::from_str(i)
+                    .expect("timeout should've been parsed")
+                    .to_string()
+            })
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(result, vec!["1500ms", "2s", "90s", "2m", "1h"]);
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parsing_invalid_timeouts_fails() {
+        // GIVEN
+        let inputs = ["", "0s", "abc", "10d", "-5s"];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| QueryTimeout::from_str(i))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(
+            result,
+            vec![
+                Err("value is not a valid duration (eg. 500ms, 30s, 2m)"),
+                Err("needs to be greater than 0"),
+                Err("value is not a valid duration (eg. 500ms, 30s, 2m)"),
+                Err("invalid unit provided; allowed values: [ms, s, m, h]"),
+                Err("value is not a valid duration (eg. 500ms, 30s, 2m)"),
+            ]
+        );
+    }
+}
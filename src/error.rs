@@ -1,4 +1,5 @@
-use crate::cmds::{ConsoleCmdError, QueryCmdError};
+use crate::cmds::{ConsoleCmdError, QueryCmdError, SchemaCmdError};
+use crate::config::ConfigError;
 use crate::repository::DbClientError;
 use etcetera::HomeDirError;
 
@@ -8,11 +9,17 @@ pub enum AppError {
     XdgError(#[from] HomeDirError),
     #[error("{0}")]
     InvalidCLIUsage(&'static str),
+    #[error("couldn't load config")]
+    CouldntLoadConfig(#[from] ConfigError),
+    #[error("couldn't resolve connection settings")]
+    CouldntResolveConnectionSettings(#[from] DbClientError),
     #[error(transparent)]
     ConsoleCmdError(#[from] ConsoleCmdError),
     #[error(transparent)]
     QueryCmdError(#[from] QueryCmdError),
     #[error(transparent)]
+    SchemaCmdError(#[from] SchemaCmdError),
+    #[error(transparent)]
     Uncategorised(#[from] anyhow::Error),
 }
 
@@ -21,24 +28,47 @@ impl AppError {
         match self {
             AppError::XdgError(_) => None,
             AppError::InvalidCLIUsage(_) => None,
+            AppError::CouldntLoadConfig(e) => follow_up_config_error(e),
+            AppError::CouldntResolveConnectionSettings(e) => follow_up_db_client_error(e),
             AppError::ConsoleCmdError(e) => match e {
                 ConsoleCmdError::CouldntBuildDbClient(e) => follow_up_db_client_error(e),
                 ConsoleCmdError::Uncategorised(_) => None,
             },
             AppError::QueryCmdError(e) => match e {
                 QueryCmdError::CouldntBuildDbClient(e) => follow_up_db_client_error(e),
+                QueryCmdError::NoStatements => None,
+                QueryCmdError::SingleStatementRequired { .. } => None,
+                QueryCmdError::QueryInterrupted(_) => None,
+                QueryCmdError::StatementFailed { .. } => None,
+                QueryCmdError::StatementsFailed { .. } => None,
+                QueryCmdError::BenchmarkRegressed { .. } => None,
                 QueryCmdError::Uncategorised(_) => None,
             },
+            AppError::SchemaCmdError(e) => match e {
+                SchemaCmdError::CouldntBuildDbClient(e) => follow_up_db_client_error(e),
+                SchemaCmdError::Uncategorised(_) => None,
+            },
             AppError::Uncategorised(_) => None,
         }
     }
 
+    /// Regressions get an exit code of their own, so that CI can tell them apart from failures.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            AppError::QueryCmdError(QueryCmdError::BenchmarkRegressed { .. }) => 3,
+            _ => 1,
+        }
+    }
+
     pub fn is_unexpected(&self) -> bool {
         match self {
             AppError::XdgError(_) => true,
             AppError::InvalidCLIUsage(_) => false,
+            AppError::CouldntLoadConfig(_) => false,
+            AppError::CouldntResolveConnectionSettings(_) => false,
             AppError::ConsoleCmdError(_) => false,
             AppError::QueryCmdError(_) => false,
+            AppError::SchemaCmdError(_) => false,
             AppError::Uncategorised(_) => false,
         }
     }
@@ -63,6 +93,9 @@ grafq requires the environment variable DB_URI to be set.
     NEO4J_USER="neo4j"
     NEO4J_PASSWORD="your-password"
     NEO4J_DB="neo4j"
+
+Alternatively, define a connection profile in grafq's config file and select it
+via --profile.
 "#
             .trim()
             .into(),
@@ -92,6 +125,49 @@ to a neo4j database (which was determined by the bolt scheme in DB_URI).
             .trim()
             .into(),
         ),
+        DbClientError::Neo4jProfileInfoMissing { .. } => Some(
+            "
+A user, password, and database need to be set when connecting to a neo4j database, either via the
+profile or the environment variables NEO4J_USER, NEO4J_PASSWORD, and NEO4J_DB.
+"
+            .trim()
+            .into(),
+        ),
+        DbClientError::InvalidProfile(_, _) => None,
+        DbClientError::GremlinNotSupported => None,
+        DbClientError::QueryPlansNotSupported => None,
+        DbClientError::TransactionsNotSupported => None,
+        DbClientError::WriteInReadOnlyMode(_) => None,
+        DbClientError::CouldntRunPasswordCommand(_) => None,
         DbClientError::Uncategorised(_) => None,
     }
 }
+
+fn follow_up_config_error(err: &ConfigError) -> Option<String> {
+    match err {
+        ConfigError::CouldntReadFile(_, _) => None,
+        ConfigError::InvalidConfig(_, _) => Some(
+            r#"
+grafq's config file defines connection profiles as TOML tables. For example:
+
+[profiles.local]
+uri = "bolt://127.0.0.1:7687"
+user = "neo4j"
+password_env = "NEO4J_LOCAL_PASSWORD"
+database = "neo4j"
+
+[profiles.prod]
+uri = "https://abc.xyz.us-east-1.neptune.amazonaws.com:8182"
+aws_region = "us-east-1"
+aws_profile = "prod"
+"#
+            .trim()
+            .into(),
+        ),
+        ConfigError::ProfileNotFound(_, available) => Some(if available.is_empty() {
+            "No profiles are defined in the config file.".into()
+        } else {
+            format!("Available profiles: {}", available.join(", "))
+        }),
+    }
+}
@@ -2,6 +2,7 @@ mod app;
 mod cli;
 mod cmds;
 mod config;
+mod cypher;
 mod domain;
 mod error;
 mod logging;
@@ -15,6 +16,7 @@ async fn main() {
     if let Err(e) = app::run().await {
         let follow_up = e.follow_up();
         let is_unexpected = e.is_unexpected();
+        let exit_code = e.exit_code();
 
         eprintln!("Error: {:?}", anyhow::anyhow!(e));
 
@@ -35,6 +37,6 @@ Let @dhth know about this via https://github.com/dhth/grafq/issues.
             );
         }
 
-        std::process::exit(1);
+        std::process::exit(exit_code);
     }
 }
@@ -1,14 +1,100 @@
 use super::NeptuneClient;
 use super::{Neo4jClient, Neo4jConfig};
-use crate::domain::QueryResults;
+use crate::cypher::{WriteClause, find_gremlin_write_step, find_write_clause};
+use crate::domain::{
+    ConnectionProfile, PlanMode, QueryLanguage, QueryParams, QueryPlan, QueryResults, RowSink,
+    Schema, SchemaNames, SecretSource, write_rows,
+};
 use crate::utils::{EnvVarError, get_env_var};
 use anyhow::Context;
-use aws_config::BehaviorVersion;
+use aws_config::{BehaviorVersion, Region};
 use aws_sdk_neptunedata::config::ProvideCredentials;
+use std::time::Duration;
 
-pub trait QueryExecutor {
-    async fn execute_query(&self, query: &str) -> anyhow::Result<QueryResults>;
+/// Queries can be executed from several tasks at once (eg. during load tests), so the futures that
+/// execute them are `Send`.
+pub trait QueryExecutor: Sync {
+    fn execute_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> impl Future<Output = anyhow::Result<QueryResults>> + Send;
+    /// Executes a query, handing rows to the sink as they arrive. Returns the number of rows
+    /// written.
+    async fn stream_query<S: RowSink>(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        sink: &mut S,
+    ) -> anyhow::Result<u64>;
+    fn execute_gremlin_query(
+        &self,
+        query: &str,
+    ) -> impl Future<Output = anyhow::Result<QueryResults>> + Send;
+    async fn explain_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        mode: PlanMode,
+        details: bool,
+    ) -> anyhow::Result<QueryPlan>;
+    /// Starts an explicit transaction; queries run inside it until it's committed or rolled back.
+    async fn begin_transaction(&self) -> anyhow::Result<()>;
+    async fn commit_transaction(&self) -> anyhow::Result<()>;
+    async fn rollback_transaction(&self) -> anyhow::Result<()>;
+    async fn in_transaction(&self) -> bool;
+    /// Cancels a query on the server once it's been abandoned locally (because it timed out, or
+    /// the user interrupted it). Returns the number of server-side queries that were cancelled.
+    async fn cancel_query(
+        &self,
+        language: &QueryLanguage,
+        query: &str,
+        running_for: Duration,
+    ) -> anyhow::Result<u64>;
+    /// Fetches the names of the labels, relationship types, and property keys in the database.
+    async fn fetch_schema_names(&self) -> anyhow::Result<SchemaNames>;
+    /// Fetches a summary of the labels, relationship types, properties, indexes, and constraints
+    /// in the database.
+    async fn fetch_schema(&self) -> anyhow::Result<Schema>;
     fn db_uri(&self) -> String;
+
+    /// Executes a query by routing it to the executor for the language it's written in.
+    fn execute(
+        &self,
+        language: &QueryLanguage,
+        query: &str,
+        params: &QueryParams,
+    ) -> impl Future<Output = anyhow::Result<QueryResults>> + Send {
+        async move {
+            match language {
+                QueryLanguage::Cypher => self.execute_query(query, params).await,
+                QueryLanguage::Gremlin => {
+                    if !params.is_empty() {
+                        anyhow::bail!("query parameters are not supported for gremlin queries");
+                    }
+                    self.execute_gremlin_query(query).await
+                }
+            }
+        }
+    }
+
+    /// Streaming counterpart of [`QueryExecutor::execute`]. Gremlin results are fetched in one go,
+    /// and then handed to the sink.
+    async fn stream<S: RowSink>(
+        &self,
+        language: &QueryLanguage,
+        query: &str,
+        params: &QueryParams,
+        sink: &mut S,
+    ) -> anyhow::Result<u64> {
+        match language {
+            QueryLanguage::Cypher => self.stream_query(query, params, sink).await,
+            QueryLanguage::Gremlin => match self.execute(language, query, params).await? {
+                QueryResults::Empty => Ok(0),
+                QueryResults::NonEmpty(results) => write_rows(results.into_rows(), sink),
+            },
+        }
+    }
 }
 
 pub enum DbClient {
@@ -17,10 +103,115 @@ pub enum DbClient {
 }
 
 impl QueryExecutor for DbClient {
-    async fn execute_query(&self, query: &str) -> anyhow::Result<QueryResults> {
+    async fn execute_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> anyhow::Result<QueryResults> {
+        self.ensure_query_is_read_only(query)?;
+
+        match self {
+            DbClient::Neptune(c) => c.execute_query(query, params).await,
+            DbClient::Neo4j(c) => c.execute_query(query, params).await,
+        }
+    }
+
+    async fn stream_query<S: RowSink>(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        sink: &mut S,
+    ) -> anyhow::Result<u64> {
+        self.ensure_query_is_read_only(query)?;
+
+        match self {
+            DbClient::Neptune(c) => c.stream_query(query, params, sink).await,
+            DbClient::Neo4j(c) => c.stream_query(query, params, sink).await,
+        }
+    }
+
+    async fn execute_gremlin_query(&self, query: &str) -> anyhow::Result<QueryResults> {
+        if self.read_only()
+            && let Some(step) = find_gremlin_write_step(query)
+        {
+            return Err(DbClientError::WriteInReadOnlyMode(step).into());
+        }
+
+        match self {
+            DbClient::Neptune(c) => c.execute_gremlin_query(query).await,
+            DbClient::Neo4j(_) => Err(DbClientError::GremlinNotSupported.into()),
+        }
+    }
+
+    async fn explain_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        mode: PlanMode,
+        details: bool,
+    ) -> anyhow::Result<QueryPlan> {
+        // profiling (and Neptune's detailed explain mode) executes the query
+        if mode == PlanMode::Profile || details {
+            self.ensure_query_is_read_only(query)?;
+        }
+
+        match self {
+            DbClient::Neptune(c) => c.explain_query(query, params, mode, details).await,
+            DbClient::Neo4j(_) => Err(DbClientError::QueryPlansNotSupported.into()),
+        }
+    }
+
+    async fn begin_transaction(&self) -> anyhow::Result<()> {
         match self {
-            DbClient::Neptune(c) => c.execute_query(query).await,
-            DbClient::Neo4j(c) => c.execute_query(query).await,
+            DbClient::Neptune(_) => Err(DbClientError::TransactionsNotSupported.into()),
+            DbClient::Neo4j(c) => c.begin_transaction().await,
+        }
+    }
+
+    async fn commit_transaction(&self) -> anyhow::Result<()> {
+        match self {
+            DbClient::Neptune(_) => Err(DbClientError::TransactionsNotSupported.into()),
+            DbClient::Neo4j(c) => c.commit_transaction().await,
+        }
+    }
+
+    async fn rollback_transaction(&self) -> anyhow::Result<()> {
+        match self {
+            DbClient::Neptune(_) => Err(DbClientError::TransactionsNotSupported.into()),
+            DbClient::Neo4j(c) => c.rollback_transaction().await,
+        }
+    }
+
+    async fn in_transaction(&self) -> bool {
+        match self {
+            DbClient::Neptune(_) => false,
+            DbClient::Neo4j(c) => c.in_transaction().await,
+        }
+    }
+
+    async fn cancel_query(
+        &self,
+        language: &QueryLanguage,
+        query: &str,
+        running_for: Duration,
+    ) -> anyhow::Result<u64> {
+        match self {
+            DbClient::Neptune(c) => c.cancel_query(language, query, running_for).await,
+            DbClient::Neo4j(c) => c.cancel_query(query).await,
+        }
+    }
+
+    async fn fetch_schema_names(&self) -> anyhow::Result<SchemaNames> {
+        match self {
+            DbClient::Neptune(c) => c.fetch_schema_names().await,
+            DbClient::Neo4j(c) => c.fetch_schema_names().await,
+        }
+    }
+
+    async fn fetch_schema(&self) -> anyhow::Result<Schema> {
+        match self {
+            DbClient::Neptune(c) => c.fetch_schema().await,
+            DbClient::Neo4j(c) => c.fetch_schema().await,
         }
     }
 
@@ -33,8 +224,33 @@ impl QueryExecutor for DbClient {
 }
 
 impl DbClient {
+    pub fn backend(&self) -> &'static str {
+        match self {
+            DbClient::Neptune(_) => "neptune",
+            DbClient::Neo4j(_) => "neo4j",
+        }
+    }
+
+    pub fn read_only(&self) -> bool {
+        match self {
+            DbClient::Neptune(c) => c.read_only(),
+            DbClient::Neo4j(c) => c.read_only(),
+        }
+    }
+
+    fn ensure_query_is_read_only(&self, query: &str) -> Result<(), DbClientError> {
+        if !self.read_only() {
+            return Ok(());
+        }
+
+        match find_write_clause(query) {
+            Some(clause) => Err(DbClientError::WriteInReadOnlyMode(clause)),
+            None => Ok(()),
+        }
+    }
+
     pub async fn verify_connectivity(&self) -> anyhow::Result<()> {
-        self.execute_query("RETURN 1")
+        self.execute_query("RETURN 1", &QueryParams::default())
             .await
             .with_context(|| format!("couldn't verify connection to {}", self.db_uri()))?;
 
@@ -54,20 +270,211 @@ pub enum DbClientError {
     DBUriIsInvalid(String),
     #[error(r#"environment variable "{0}" is missing"#)]
     Neo4jConnectionInfoMissing(String),
+    #[error(r#"profile "{profile}" doesn't set "{key}", and environment variable "{env_var}" is missing"#)]
+    Neo4jProfileInfoMissing {
+        profile: String,
+        key: &'static str,
+        env_var: &'static str,
+    },
+    #[error(r#"profile "{0}" is invalid: {1}"#)]
+    InvalidProfile(String, &'static str),
+    #[error("gremlin queries are only supported for AWS Neptune, not for neo4j")]
+    GremlinNotSupported,
+    #[error(
+        "query plans aren't available for neo4j yet, as the neo4j driver grafq uses doesn't expose result summaries"
+    )]
+    QueryPlansNotSupported,
+    #[error(
+        "explicit transactions aren't supported for AWS Neptune, as its data API runs each request in a transaction of its own"
+    )]
+    TransactionsNotSupported,
+    #[error("query contains a write {0}, which isn't allowed in read-only mode")]
+    WriteInReadOnlyMode(WriteClause),
+    #[error("couldn't get password via command")]
+    CouldntRunPasswordCommand(#[source] anyhow::Error),
     #[error(transparent)]
     Uncategorised(#[from] anyhow::Error),
 }
 
-pub async fn get_db_client() -> Result<DbClient, DbClientError> {
-    let db_uri = get_env_var("DB_URI")?.ok_or(DbClientError::DBUriNotSet)?;
+/// Connection settings resolved from an (optional) profile and environment variables, with the
+/// latter taking precedence.
+#[derive(Default)]
+pub struct ConnectionSettings {
+    profile_name: Option<String>,
+    db_uri: Option<String>,
+    user: Option<String>,
+    password: Option<SecretSource>,
+    database: Option<String>,
+    aws_region: Option<String>,
+    aws_profile: Option<String>,
+    read_only: bool,
+    max_connections: Option<usize>,
+}
+
+impl ConnectionSettings {
+    /// Read-only mode is on if either the flag is passed, or the profile turns it on.
+    pub fn resolve(
+        profile: Option<(&str, &ConnectionProfile)>,
+        read_only: bool,
+    ) -> Result<Self, DbClientError> {
+        let (profile_name, profile) = match profile {
+            Some((name, p)) => (Some(name.to_string()), p.clone()),
+            None => (None, ConnectionProfile::default()),
+        };
+
+        let profile_password = match profile.password_source() {
+            Ok(p) => p,
+            Err(e) => {
+                return Err(DbClientError::InvalidProfile(
+                    profile_name.unwrap_or_default(),
+                    e,
+                ));
+            }
+        };
+
+        let password = match get_env_var("NEO4J_PASSWORD")? {
+            Some(_) => Some(SecretSource::EnvVar("NEO4J_PASSWORD".to_string())),
+            None => profile_password,
+        };
+
+        Ok(Self {
+            profile_name,
+            db_uri: get_env_var("DB_URI")?.or(profile.uri),
+            user: get_env_var("NEO4J_USER")?.or(profile.user),
+            password,
+            database: get_env_var("NEO4J_DB")?.or(profile.database),
+            aws_region: get_env_var("AWS_REGION")?.or(profile.aws_region),
+            aws_profile: get_env_var("AWS_PROFILE")?.or(profile.aws_profile),
+            read_only: read_only || profile.read_only.unwrap_or_default(),
+            max_connections: None,
+        })
+    }
+
+    pub fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    /// Sizes the connection pool (Neo4j only), so that queries running concurrently don't have to
+    /// wait for a connection.
+    pub fn with_max_connections(mut self, max_connections: usize) -> Self {
+        self.max_connections = Some(max_connections);
+        self
+    }
+
+    fn neo4j_value(
+        &self,
+        value: Option<String>,
+        key: &'static str,
+        env_var: &'static str,
+    ) -> Result<String, DbClientError> {
+        value.ok_or_else(|| match &self.profile_name {
+            Some(profile) => DbClientError::Neo4jProfileInfoMissing {
+                profile: profile.clone(),
+                key,
+                env_var,
+            },
+            None => DbClientError::Neo4jConnectionInfoMissing(env_var.to_string()),
+        })
+    }
+}
+
+/// The password is redacted, so that settings can be logged safely.
+impl std::fmt::Debug for ConnectionSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ConnectionSettings")
+            .field("profile_name", &self.profile_name)
+            .field("db_uri", &self.db_uri)
+            .field("user", &self.user)
+            .field("password", &self.password.as_ref().map(|p| p.to_string()))
+            .field("database", &self.database)
+            .field("aws_region", &self.aws_region)
+            .field("aws_profile", &self.aws_profile)
+            .field("read_only", &self.read_only)
+            .field("max_connections", &self.max_connections)
+            .finish()
+    }
+}
+
+impl std::fmt::Display for ConnectionSettings {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn or_unset<T: ToString>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_else(|| "(not set)".to_string())
+        }
+
+        write!(
+            f,
+            "
+profile:                    {}
+db uri:                     {}
+user:                       {}
+password:                   {}
+database:                   {}
+aws region:                 {}
+aws profile:                {}
+read only:                  {}
+",
+            or_unset(&self.profile_name),
+            or_unset(&self.db_uri),
+            or_unset(&self.user),
+            or_unset(&self.password),
+            or_unset(&self.database),
+            or_unset(&self.aws_region),
+            or_unset(&self.aws_profile),
+            self.read_only,
+        )
+    }
+}
+
+fn read_secret(source: &SecretSource) -> Result<String, DbClientError> {
+    match source {
+        SecretSource::Plain(s) => Ok(s.clone()),
+        SecretSource::EnvVar(var) => {
+            get_env_var(var)?.ok_or_else(|| DbClientError::Neo4jConnectionInfoMissing(var.clone()))
+        }
+        SecretSource::Command(cmd) => {
+            let parts = shlex::split(cmd).filter(|p| !p.is_empty()).ok_or_else(|| {
+                DbClientError::CouldntRunPasswordCommand(anyhow::anyhow!("couldn't parse command"))
+            })?;
+
+            let output = std::process::Command::new(&parts[0])
+                .args(&parts[1..])
+                .output()
+                .with_context(|| format!(r#"couldn't run "{}""#, parts[0]))
+                .map_err(DbClientError::CouldntRunPasswordCommand)?;
 
-    fn get_neo4j_env_var(key: &str) -> Result<String, DbClientError> {
-        get_env_var(key)?.ok_or_else(|| DbClientError::Neo4jConnectionInfoMissing(key.to_string()))
+            if !output.status.success() {
+                return Err(DbClientError::CouldntRunPasswordCommand(anyhow::anyhow!(
+                    "command exited with status {}",
+                    output.status
+                )));
+            }
+
+            let password = String::from_utf8(output.stdout)
+                .context("command output is not valid utf-8")
+                .map_err(DbClientError::CouldntRunPasswordCommand)?;
+
+            Ok(password.trim_end_matches(['\n', '\r']).to_string())
+        }
     }
+}
+
+pub async fn get_db_client(settings: ConnectionSettings) -> Result<DbClient, DbClientError> {
+    let db_uri = settings.db_uri.clone().ok_or(DbClientError::DBUriNotSet)?;
 
     let db_client = match db_uri.split_once("://") {
         Some(("https", _)) => {
-            let sdk_config = aws_config::load_defaults(BehaviorVersion::latest()).await;
+            let mut loader = aws_config::defaults(BehaviorVersion::latest());
+            if let Some(region) = &settings.aws_region {
+                loader = loader.region(Region::new(region.clone()));
+            }
+            if let Some(aws_profile) = &settings.aws_profile {
+                loader = loader.profile_name(aws_profile);
+            }
+            let sdk_config = loader.load().await;
+
             if let Some(provider) = sdk_config.credentials_provider() {
                 provider
                     .provide_credentials()
@@ -75,19 +482,27 @@ pub async fn get_db_client() -> Result<DbClient, DbClientError> {
                     .context("couldn't fetch AWS credentials")?;
             }
 
-            let neptune_client = NeptuneClient::new(&sdk_config, &db_uri);
+            let neptune_client = NeptuneClient::new(&sdk_config, &db_uri, settings.read_only);
             Ok(DbClient::Neptune(neptune_client))
         }
         Some(("bolt", _)) => {
-            let user = get_neo4j_env_var("NEO4J_USER")?;
-            let password = get_neo4j_env_var("NEO4J_PASSWORD")?;
-            let database_name = get_neo4j_env_var("NEO4J_DB")?;
+            let user = settings.neo4j_value(settings.user.clone(), "user", "NEO4J_USER")?;
+            let password_source = settings.password.clone();
+            let password = settings.neo4j_value(
+                password_source.as_ref().map(read_secret).transpose()?,
+                "password",
+                "NEO4J_PASSWORD",
+            )?;
+            let database_name =
+                settings.neo4j_value(settings.database.clone(), "database", "NEO4J_DB")?;
 
             let config = Neo4jConfig {
                 db_uri,
                 user,
                 password,
                 database_name,
+                read_only: settings.read_only,
+                max_connections: settings.max_connections,
             };
 
             let neo4j_client = Neo4jClient::new(&config).await?;
@@ -99,3 +514,78 @@ pub async fn get_db_client() -> Result<DbClient, DbClientError> {
 
     Ok(db_client)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn debug_output_of_connection_settings_redacts_the_password() {
+        // GIVEN
+        let settings = ConnectionSettings {
+            db_uri: Some("bolt://127.0.0.1:7687".to_string()),
+            user: Some("neo4j".to_string()),
+            password: Some(SecretSource::Plain("hunter2".to_string())),
+            ..ConnectionSettings::default()
+        };
+
+        // WHEN
+        let result = format!("{settings:?}");
+
+        // THEN
+        assert_snapshot!(result, @r#"ConnectionSettings { profile_name: None, db_uri: Some("bolt://127.0.0.1:7687"), user: Some("neo4j"), password: Some("********"), database: None, aws_region: None, aws_profile: None, read_only: false, max_connections: None }"#);
+    }
+
+    #[tokio::test]
+    async fn transactions_are_refused_for_neptune() {
+        // GIVEN
+        let sdk_config = aws_config::SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .build();
+        let db_client = DbClient::Neptune(NeptuneClient::new(
+            &sdk_config,
+            "https://neptune.example.com:8182",
+            false,
+        ));
+
+        // WHEN
+        let result = db_client
+            .begin_transaction()
+            .await
+            .expect_err("result should've been an error");
+
+        // THEN
+        assert!(!db_client.in_transaction().await);
+        assert_snapshot!(result, @"explicit transactions aren't supported for AWS Neptune, as its data API runs each request in a transaction of its own");
+    }
+
+    #[tokio::test]
+    async fn write_queries_are_rejected_in_read_only_mode() {
+        // GIVEN
+        let sdk_config = aws_config::SdkConfig::builder()
+            .behavior_version(BehaviorVersion::latest())
+            .region(Region::new("us-east-1"))
+            .build();
+        let db_client = DbClient::Neptune(NeptuneClient::new(
+            &sdk_config,
+            "https://neptune.example.com:8182",
+            true,
+        ));
+
+        // WHEN
+        let mut rows: Vec<serde_json::Value> = vec![];
+        let result = db_client
+            .stream_query(
+                "MATCH (n:Person)\nDETACH DELETE n",
+                &QueryParams::default(),
+                &mut rows,
+            )
+            .await
+            .expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @r#"query contains a write "DETACH DELETE" (line 2, column 1), which isn't allowed in read-only mode"#);
+    }
+}
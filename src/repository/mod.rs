@@ -1,7 +1,11 @@
 mod client;
 mod neo4j;
 mod neptune;
+mod neptune_plan;
+mod query_error;
+mod schema;
 
 pub use client::*;
 use neo4j::{Neo4jClient, Neo4jConfig};
 use neptune::NeptuneClient;
+pub use query_error::*;
@@ -1,12 +1,52 @@
 use anyhow::Context;
-use neo4rs::{ConfigBuilder, Graph, query as neo4j_query};
-use serde_json::Value;
+use neo4rs::{
+    BoltMap, BoltNode, BoltPath, BoltType, ConfigBuilder, Graph, Neo4jClientErrorKind,
+    Neo4jErrorKind, Query, Row, Txn, query as neo4j_query,
+};
+use serde::Deserialize;
+use serde::de::IntoDeserializer;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
 
-use crate::domain::QueryResults;
+use super::QueryError;
+use super::schema::{
+    count_from_rows, label_count_query, relationship_type_count_query, strings_in_column,
+};
+use crate::domain::{
+    ConstraintSchema, GraphEntity, IndexSchema, LabelSchema, Node, Path, PropertySchema,
+    QueryParams, QueryResults, Relationship, RelationshipTypeSchema, RowSink, Schema,
+    SchemaElement, SchemaNames, SchemaPattern,
+};
+
+const SCHEMA_VISUALIZATION_QUERY: &str =
+    "CALL db.schema.visualization() YIELD nodes, relationships RETURN nodes, relationships";
+const NODE_PROPERTIES_QUERY: &str = "CALL db.schema.nodeTypeProperties()
+YIELD nodeLabels, propertyName, propertyTypes
+RETURN nodeLabels, propertyName, propertyTypes";
+const RELATIONSHIP_PROPERTIES_QUERY: &str = "CALL db.schema.relTypeProperties()
+YIELD relType, propertyName, propertyTypes
+RETURN relType, propertyName, propertyTypes";
+const INDEXES_QUERY: &str = "SHOW INDEXES
+YIELD name, type, entityType, labelsOrTypes, properties, state
+RETURN name, type, entityType, labelsOrTypes, properties, state";
+const CONSTRAINTS_QUERY: &str = "SHOW CONSTRAINTS
+YIELD name, type, entityType, labelsOrTypes, properties
+RETURN name, type, entityType, labelsOrTypes, properties";
+
+const RUNNING_TRANSACTIONS_QUERY: &str = "SHOW TRANSACTIONS YIELD transactionId, currentQuery
+WHERE currentQuery = $query
+RETURN transactionId";
 
 pub struct Neo4jClient {
     inner: Graph,
     db_uri: String,
+    read_only: bool,
+    // appended to every query this client sends, so that its transactions can be told apart from
+    // other clients' ones running the same query
+    query_tag: String,
+    // queries run inside this transaction while it's open
+    txn: Mutex<Option<Box<Txn>>>,
 }
 
 pub struct Neo4jConfig {
@@ -14,22 +54,30 @@ pub struct Neo4jConfig {
     pub user: String,
     pub password: String,
     pub database_name: String,
+    pub read_only: bool,
+    pub max_connections: Option<usize>,
 }
 
 impl Neo4jClient {
     pub async fn new(config: &Neo4jConfig) -> anyhow::Result<Self> {
-        let cfg = ConfigBuilder::default()
+        let mut cfg = ConfigBuilder::default()
             .uri(config.db_uri.as_str())
             .user(config.user.as_str())
             .password(config.password.as_str())
-            .db(config.database_name.as_str())
-            .build()?;
+            .db(config.database_name.as_str());
+        if let Some(max_connections) = config.max_connections {
+            cfg = cfg.max_connections(max_connections);
+        }
+        let cfg = cfg.build()?;
 
         let graph = Graph::connect(cfg).await?;
 
         Ok(Self {
             inner: graph,
             db_uri: config.db_uri.to_string(),
+            read_only: config.read_only,
+            query_tag: new_query_tag(),
+            txn: Mutex::new(None),
         })
     }
 
@@ -37,24 +85,803 @@ impl Neo4jClient {
         self.db_uri.clone()
     }
 
-    pub(super) async fn execute_query(&self, query: &str) -> anyhow::Result<QueryResults> {
+    pub(super) fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub(super) async fn execute_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> anyhow::Result<QueryResults> {
+        let mut results = Vec::new();
+        self.stream_query(query, params, &mut results).await?;
+
+        Ok(results.into())
+    }
+
+    /// Rows are pulled from the server in batches, and handed to the sink as soon as they arrive.
+    pub(super) async fn stream_query<S: RowSink>(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        sink: &mut S,
+    ) -> anyhow::Result<u64> {
+        let query = build_query(&self.tagged(query), params)?;
+
+        // the lock is only held while an explicit transaction is open, so that queries from
+        // several tasks (eg. during load tests) can run on the pool's connections at once
+        let mut open_txn = self.txn.lock().await;
+        if let Some(txn) = open_txn.as_mut() {
+            return stream_in_txn(txn, query, sink).await;
+        }
+        drop(open_txn);
+
+        if self.read_only {
+            // neo4rs 0.8 can't open sessions in READ access mode (0.9 adds `Graph::execute_read`,
+            // which should replace this once it's released), so read-only queries run in a
+            // transaction that's always rolled back; the server then discards any writes that slip
+            // past the write clause check, though procedures that commit on their own still can't
+            // be stopped this way
+            let mut txn = self
+                .inner
+                .start_txn()
+                .await
+                .map_err(classify_neo4j_error)
+                .context("couldn't start read-only transaction")?;
+            let streamed = stream_in_txn(&mut txn, query, sink).await;
+            let rolled_back = txn.rollback().await;
+
+            let num_rows = streamed?;
+            rolled_back.context("couldn't roll back read-only transaction")?;
+
+            return Ok(num_rows);
+        }
+
+        let mut num_rows = 0;
         let mut result = self
             .inner
-            .execute(neo4j_query(query))
+            .execute(query)
             .await
+            .map_err(classify_neo4j_error)
             .context("couldn't execute query")?;
 
-        let mut results = Vec::new();
-
         while let Some(row) = result
             .next()
             .await
+            .map_err(classify_neo4j_error)
             .context("couldn't get row from results")?
         {
-            let row_value = row.to::<Value>().context("couldn't parse row as value")?;
-            results.push(row_value);
+            let row_value = row_to_value(&row)?;
+            sink.write_row(row_value)?;
+            num_rows += 1;
+
+            if sink.is_closed() {
+                break;
+            }
         }
 
-        Ok(results.into())
+        Ok(num_rows)
+    }
+
+    pub(super) async fn begin_transaction(&self) -> anyhow::Result<()> {
+        if self.read_only {
+            anyhow::bail!("explicit transactions aren't available in read-only mode");
+        }
+
+        let mut txn = self.txn.lock().await;
+        if txn.is_some() {
+            anyhow::bail!("a transaction is already open");
+        }
+
+        *txn = Some(Box::new(
+            self.inner
+                .start_txn()
+                .await
+                .context("couldn't start transaction")?,
+        ));
+
+        Ok(())
+    }
+
+    pub(super) async fn commit_transaction(&self) -> anyhow::Result<()> {
+        let Some(txn) = self.txn.lock().await.take() else {
+            anyhow::bail!("no transaction is open");
+        };
+
+        txn.commit().await.context("couldn't commit transaction")?;
+
+        Ok(())
+    }
+
+    pub(super) async fn rollback_transaction(&self) -> anyhow::Result<()> {
+        let Some(txn) = self.txn.lock().await.take() else {
+            anyhow::bail!("no transaction is open");
+        };
+
+        (*txn)
+            .rollback()
+            .await
+            .context("couldn't roll back transaction")?;
+
+        Ok(())
+    }
+
+    pub(super) async fn in_transaction(&self) -> bool {
+        self.txn.lock().await.is_some()
+    }
+
+    fn tagged(&self, query: &str) -> String {
+        format!("{query}\n/* {} */", self.query_tag)
+    }
+
+    /// Terminates the transactions running the query on the server. neo4rs doesn't expose the ids
+    /// of the transactions it starts, so they're looked up by the query's text, which carries this
+    /// client's tag.
+    ///
+    /// An open explicit transaction is rolled back as well: the query was cut short while its rows
+    /// were being pulled, so the transaction can't be used any further.
+    pub(super) async fn cancel_query(&self, query: &str) -> anyhow::Result<u64> {
+        let open_txn = self.txn.lock().await.take();
+        let terminated = self.terminate_transactions(query).await;
+
+        if let Some(txn) = open_txn {
+            // the server may have ended the transaction already while terminating it, in which
+            // case rolling it back fails; either way, its connection is no longer mid-transaction
+            // when it's handed back to the pool
+            let _ = txn.rollback().await;
+        }
+
+        terminated
+    }
+
+    async fn terminate_transactions(&self, query: &str) -> anyhow::Result<u64> {
+        let mut result = self
+            .inner
+            .execute(neo4j_query(RUNNING_TRANSACTIONS_QUERY).param("query", self.tagged(query)))
+            .await
+            .context("couldn't list running transactions")?;
+
+        let mut transaction_ids = vec![];
+        while let Some(row) = result
+            .next()
+            .await
+            .context("couldn't get row from running transactions")?
+        {
+            transaction_ids.push(
+                row.get::<String>("transactionId")
+                    .context("couldn't get transaction id")?,
+            );
+        }
+
+        if transaction_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let num_transactions = transaction_ids.len() as u64;
+        self.inner
+            .run(neo4j_query("TERMINATE TRANSACTIONS $ids").param("ids", transaction_ids))
+            .await
+            .context("couldn't terminate transactions")?;
+
+        Ok(num_transactions)
+    }
+
+    pub(super) async fn fetch_schema_names(&self) -> anyhow::Result<SchemaNames> {
+        let labels = self
+            .fetch_rows("CALL db.labels() YIELD label RETURN label")
+            .await
+            .context("couldn't fetch labels")?;
+        let relationship_types = self
+            .fetch_rows(
+                "CALL db.relationshipTypes() YIELD relationshipType RETURN relationshipType",
+            )
+            .await
+            .context("couldn't fetch relationship types")?;
+        let property_keys = self
+            .fetch_rows("CALL db.propertyKeys() YIELD propertyKey RETURN propertyKey")
+            .await
+            .context("couldn't fetch property keys")?;
+
+        Ok(SchemaNames::new(
+            strings_in_column(&labels, "label"),
+            strings_in_column(&relationship_types, "relationshipType"),
+            strings_in_column(&property_keys, "propertyKey"),
+        ))
+    }
+
+    /// Labels and relationship types are counted via the count store, which makes counting them
+    /// cheap; the patterns relationships connect come from `db.schema.visualization()`.
+    pub(super) async fn fetch_schema(&self) -> anyhow::Result<Schema> {
+        let names = self.fetch_schema_names().await?;
+
+        let mut labels = vec![];
+        for label in names.labels {
+            let rows = self
+                .fetch_rows(&label_count_query(&label))
+                .await
+                .with_context(|| format!(r#"couldn't count nodes with label "{label}""#))?;
+            labels.push(LabelSchema {
+                count: count_from_rows(&rows),
+                label,
+            });
+        }
+
+        let mut relationship_types = vec![];
+        for relationship_type in names.relationship_types {
+            let rows = self
+                .fetch_rows(&relationship_type_count_query(&relationship_type))
+                .await
+                .with_context(|| {
+                    format!(r#"couldn't count relationships of type "{relationship_type}""#)
+                })?;
+            relationship_types.push(RelationshipTypeSchema {
+                count: count_from_rows(&rows),
+                relationship_type,
+            });
+        }
+
+        let patterns = self
+            .fetch_rows(SCHEMA_VISUALIZATION_QUERY)
+            .await
+            .context("couldn't fetch schema visualization")?
+            .iter()
+            .flat_map(patterns_from_visualization)
+            .collect();
+
+        let mut properties: Vec<PropertySchema> = self
+            .fetch_rows(NODE_PROPERTIES_QUERY)
+            .await
+            .context("couldn't fetch node properties")?
+            .iter()
+            .filter_map(node_property_from_row)
+            .collect();
+        properties.extend(
+            self.fetch_rows(RELATIONSHIP_PROPERTIES_QUERY)
+                .await
+                .context("couldn't fetch relationship properties")?
+                .iter()
+                .filter_map(relationship_property_from_row),
+        );
+
+        let indexes = self
+            .fetch_rows(INDEXES_QUERY)
+            .await
+            .context("couldn't fetch indexes")?
+            .iter()
+            .map(index_from_row)
+            .collect();
+
+        let constraints = self
+            .fetch_rows(CONSTRAINTS_QUERY)
+            .await
+            .context("couldn't fetch constraints")?
+            .iter()
+            .map(constraint_from_row)
+            .collect();
+
+        Ok(Schema {
+            labels,
+            relationship_types,
+            patterns,
+            properties,
+            indexes,
+            constraints,
+        })
+    }
+
+    /// Runs a query outside of any open transaction; used for looking up the database's schema.
+    async fn fetch_rows(&self, query: &str) -> anyhow::Result<Vec<Value>> {
+        let mut result = self
+            .inner
+            .execute(neo4j_query(query))
+            .await
+            .map_err(classify_neo4j_error)?;
+
+        let mut rows = vec![];
+        while let Some(row) = result.next().await.map_err(classify_neo4j_error)? {
+            rows.push(row_to_value(&row)?);
+        }
+
+        Ok(rows)
+    }
+}
+
+/// Returns the patterns in a row returned by `db.schema.visualization()`, whose nodes stand in for
+/// labels (and are named after them).
+fn patterns_from_visualization(row: &Value) -> Vec<SchemaPattern> {
+    let entities = |column: &str| -> Vec<GraphEntity> {
+        row.get(column)
+            .and_then(Value::as_array)
+            .map(|values| values.iter().filter_map(GraphEntity::from_value).collect())
+            .unwrap_or_default()
+    };
+
+    let labels: HashMap<String, String> = entities("nodes")
+        .into_iter()
+        .filter_map(|entity| match entity {
+            GraphEntity::Node(node) => {
+                let name = node
+                    .properties
+                    .get("name")
+                    .and_then(Value::as_str)
+                    .map(String::from)
+                    .or_else(|| node.labels.first().cloned())?;
+                Some((node.id, name))
+            }
+            _ => None,
+        })
+        .collect();
+
+    entities("relationships")
+        .into_iter()
+        .filter_map(|entity| match entity {
+            GraphEntity::Relationship(rel) => Some(SchemaPattern {
+                from: labels.get(&rel.start)?.clone(),
+                relationship_type: rel.rel_type,
+                to: labels.get(&rel.end)?.clone(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Rows for labels without properties are skipped.
+fn node_property_from_row(row: &Value) -> Option<PropertySchema> {
+    let key = row.get("propertyName").and_then(Value::as_str)?;
+    let labels = strings_in(row.get("nodeLabels"));
+
+    Some(PropertySchema {
+        on: SchemaElement::node(labels),
+        key: key.to_string(),
+        types: strings_in(row.get("propertyTypes")),
+    })
+}
+
+/// Relationship types are returned as they'd appear in a pattern, eg. ":`ACTED_IN`".
+fn relationship_property_from_row(row: &Value) -> Option<PropertySchema> {
+    let key = row.get("propertyName").and_then(Value::as_str)?;
+    let relationship_type = row
+        .get("relType")
+        .and_then(Value::as_str)?
+        .trim_start_matches(':');
+    let relationship_type = match relationship_type.strip_prefix('`') {
+        Some(quoted) => quoted.trim_end_matches('`').replace("``", "`"),
+        None => relationship_type.to_string(),
+    };
+
+    Some(PropertySchema {
+        on: SchemaElement::relationship([relationship_type]),
+        key: key.to_string(),
+        types: strings_in(row.get("propertyTypes")),
+    })
+}
+
+fn index_from_row(row: &Value) -> IndexSchema {
+    IndexSchema {
+        name: string_in(row.get("name")),
+        index_type: string_in(row.get("type")),
+        on: element_from_row(row),
+        properties: strings_in(row.get("properties")),
+        state: string_in(row.get("state")),
+    }
+}
+
+fn constraint_from_row(row: &Value) -> ConstraintSchema {
+    ConstraintSchema {
+        name: string_in(row.get("name")),
+        constraint_type: string_in(row.get("type")),
+        on: element_from_row(row),
+        properties: strings_in(row.get("properties")),
+    }
+}
+
+fn element_from_row(row: &Value) -> SchemaElement {
+    let labels_or_types = strings_in(row.get("labelsOrTypes"));
+    match row.get("entityType").and_then(Value::as_str) {
+        Some("RELATIONSHIP") => SchemaElement::relationship(labels_or_types),
+        _ => SchemaElement::node(labels_or_types),
+    }
+}
+
+fn string_in(value: Option<&Value>) -> String {
+    value
+        .and_then(Value::as_str)
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Lookup indexes have no labels or properties, which are returned as nulls.
+fn strings_in(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+async fn stream_in_txn<S: RowSink>(
+    txn: &mut Txn,
+    query: Query,
+    sink: &mut S,
+) -> anyhow::Result<u64> {
+    let mut result = txn
+        .execute(query)
+        .await
+        .map_err(classify_neo4j_error)
+        .context("couldn't execute query in transaction")?;
+
+    let mut num_rows = 0;
+
+    while let Some(row) = result
+        .next(txn.handle())
+        .await
+        .map_err(classify_neo4j_error)
+        .context("couldn't get row from results")?
+    {
+        let row_value = row_to_value(&row)?;
+        sink.write_row(row_value)?;
+        num_rows += 1;
+
+        if sink.is_closed() {
+            break;
+        }
+    }
+
+    Ok(num_rows)
+}
+
+/// Transient errors (eg. deadlocks, or a cluster member no longer being the leader) and dropped
+/// connections may go away if the query is retried.
+fn classify_neo4j_error(err: neo4rs::Error) -> QueryError {
+    let is_transient = match &err {
+        neo4rs::Error::Neo4j(e) => matches!(
+            e.kind(),
+            Neo4jErrorKind::Transient
+                | Neo4jErrorKind::Client(Neo4jClientErrorKind::SessionExpired)
+        ),
+        neo4rs::Error::ConnectionError | neo4rs::Error::IOError { .. } => true,
+        _ => false,
+    };
+
+    if is_transient {
+        QueryError::Transient(err.into())
+    } else {
+        QueryError::Permanent(err.into())
+    }
+}
+
+/// Unique enough to tell apart the clients that may be running the same query at once.
+fn new_query_tag() -> String {
+    let started_at = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_nanos();
+
+    format!("grafq:{}-{started_at}", std::process::id())
+}
+
+fn build_query(query: &str, params: &QueryParams) -> anyhow::Result<Query> {
+    let mut neo4j_query = neo4j_query(query);
+    for (key, value) in params.as_map() {
+        let bolt_value = BoltType::try_from(value.clone())
+            .with_context(|| format!(r#"couldn't convert parameter "{key}" to a bolt value"#))?;
+        neo4j_query = neo4j_query.param(key, bolt_value);
+    }
+
+    Ok(neo4j_query)
+}
+
+/// Nodes, relationships and paths are mapped to graph entities; other values are left to neo4rs'
+/// own deserialization.
+fn row_to_value(row: &Row) -> anyhow::Result<Value> {
+    let attributes = row.to::<BoltMap>().context("couldn't parse row as value")?;
+
+    bolt_map_to_value(&attributes).context("couldn't parse row as value")
+}
+
+fn bolt_to_value(value: &BoltType) -> anyhow::Result<Value> {
+    let value = match value {
+        BoltType::Node(node) => node_from_bolt(node)?.into(),
+        BoltType::Relation(rel) => Relationship {
+            id: rel.id.value.to_string(),
+            rel_type: rel.typ.value.clone(),
+            start: rel.start_node_id.value.to_string(),
+            end: rel.end_node_id.value.to_string(),
+            properties: bolt_map_to_map(&rel.properties)?,
+        }
+        .into(),
+        BoltType::Path(path) => path_from_bolt(path)?.into(),
+        BoltType::List(list) => Value::Array(
+            list.value
+                .iter()
+                .map(bolt_to_value)
+                .collect::<anyhow::Result<_>>()?,
+        ),
+        BoltType::Map(map) => bolt_map_to_value(map)?,
+        other => Value::deserialize(other.into_deserializer())?,
+    };
+
+    Ok(value)
+}
+
+fn bolt_map_to_value(map: &BoltMap) -> anyhow::Result<Value> {
+    Ok(Value::Object(bolt_map_to_map(map)?))
+}
+
+fn bolt_map_to_map(map: &BoltMap) -> anyhow::Result<Map<String, Value>> {
+    map.value
+        .iter()
+        .map(|(k, v)| Ok((k.value.clone(), bolt_to_value(v)?)))
+        .collect()
+}
+
+fn node_from_bolt(node: &BoltNode) -> anyhow::Result<Node> {
+    let labels = node
+        .labels
+        .value
+        .iter()
+        .map(|label| match label {
+            BoltType::String(s) => Ok(s.value.clone()),
+            other => anyhow::bail!("unexpected node label: {other:?}"),
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(Node {
+        id: node.id.value.to_string(),
+        labels,
+        properties: bolt_map_to_map(&node.properties)?,
+    })
+}
+
+/// Bolt sends a path's distinct nodes and relationships, and a list of indices alternating between
+/// the next relationship (1-based, negative if it's traversed backwards) and the next node.
+fn path_from_bolt(path: &BoltPath) -> anyhow::Result<Path> {
+    let all_nodes = path
+        .nodes()
+        .iter()
+        .map(node_from_bolt)
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let all_rels = path.rels();
+    let indices = path.indices();
+
+    let Some(first) = all_nodes.first() else {
+        anyhow::bail!("path has no nodes");
+    };
+
+    let mut nodes = vec![first.clone()];
+    let mut relationships = vec![];
+    for pair in indices.chunks(2) {
+        let [rel_index, node_index] = pair else {
+            anyhow::bail!("path has an odd number of indices");
+        };
+
+        let previous = &nodes[nodes.len() - 1];
+        let node = usize::try_from(node_index.value)
+            .ok()
+            .and_then(|i| all_nodes.get(i))
+            .with_context(|| format!("path has an invalid node index: {}", node_index.value))?;
+        // relationship indices are 1-based, and signed by the direction they're traversed in
+        let rel = rel_index
+            .value
+            .unsigned_abs()
+            .checked_sub(1)
+            .and_then(|i| usize::try_from(i).ok())
+            .and_then(|i| all_rels.get(i))
+            .with_context(|| {
+                format!(
+                    "path has an invalid relationship index: {}",
+                    rel_index.value
+                )
+            })?;
+
+        let (start, end) = if rel_index.value > 0 {
+            (previous.id.clone(), node.id.clone())
+        } else {
+            (node.id.clone(), previous.id.clone())
+        };
+
+        relationships.push(Relationship {
+            id: rel.id.value.to_string(),
+            rel_type: rel.typ.value.clone(),
+            start,
+            end,
+            properties: bolt_map_to_map(&rel.properties)?,
+        });
+        nodes.push(node.clone());
+    }
+
+    Ok(Path {
+        nodes,
+        relationships,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+    use neo4rs::{BoltList, BoltUnboundedRelation};
+    use serde_json::json;
+
+    fn person(id: i64, name: &str) -> BoltNode {
+        BoltNode::new(
+            id.into(),
+            vec!["Person".into()].into(),
+            vec![("name".into(), name.into())].into_iter().collect(),
+        )
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn row_to_value_maps_nodes_relationships_and_paths() -> anyhow::Result<()> {
+        // GIVEN
+        let knows = BoltUnboundedRelation::new(7.into(), "KNOWS".into(), BoltMap::new());
+        let follows = BoltUnboundedRelation::new(8.into(), "FOLLOWS".into(), BoltMap::new());
+        // alice -[:KNOWS]-> bob <-[:FOLLOWS]- carol
+        let path = BoltPath {
+            nodes: vec![
+                person(1, "alice").into(),
+                person(2, "bob").into(),
+                person(3, "carol").into(),
+            ]
+            .into(),
+            rels: vec![knows.into(), follows.into()].into(),
+            indices: vec![1.into(), 1.into(), (-2).into(), 2.into()].into(),
+        };
+        let row = Row::new(
+            BoltList::from(vec!["p".into(), "names".into()]),
+            BoltList::from(vec![
+                BoltType::Path(path),
+                BoltType::List(vec!["alice".into(), "bob".into()].into()),
+            ]),
+        );
+
+        // WHEN
+        let result = row_to_value(&row)?;
+
+        // THEN
+        let Some(GraphEntity::Path(path)) = GraphEntity::from_value(&result["p"]) else {
+            anyhow::bail!("expected a path, got: {}", result["p"]);
+        };
+        assert_snapshot!(path, @r#"(:Person {name: "alice"})-[:KNOWS]->(:Person {name: "bob"})<-[:FOLLOWS]-(:Person {name: "carol"})"#);
+        assert_eq!(path.relationships[1].start, "3");
+        assert_eq!(result["names"], serde_json::json!(["alice", "bob"]));
+
+        Ok(())
+    }
+
+    #[test]
+    fn patterns_from_visualization_names_nodes_after_their_labels() {
+        // GIVEN
+        let node = |id: i64, label: &str| {
+            json!({
+                "~id": id,
+                "~entityType": "node",
+                "~labels": [label],
+                "~properties": {"name": label, "indexes": [], "constraints": []},
+            })
+        };
+        let relationship = |id: i64, rel_type: &str, start: i64, end: i64| {
+            json!({
+                "~id": id,
+                "~entityType": "relationship",
+                "~type": rel_type,
+                "~start": start,
+                "~end": end,
+                "~properties": {},
+            })
+        };
+        let row = json!({
+            "nodes": [node(-1, "Person"), node(-2, "Movie")],
+            "relationships": [
+                relationship(-3, "ACTED_IN", -1, -2),
+                relationship(-4, "FOLLOWS", -1, -1),
+            ],
+        });
+
+        // WHEN
+        let result = patterns_from_visualization(&row)
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @"
+        (:Person)-[:ACTED_IN]->(:Movie)
+        (:Person)-[:FOLLOWS]->(:Person)
+        ");
+    }
+
+    #[test]
+    fn schema_rows_are_parsed() {
+        // GIVEN
+        let node_properties = [
+            json!({"nodeLabels": ["Person", "Actor"], "propertyName": "name", "propertyTypes": ["String"]}),
+            json!({"nodeLabels": ["Tag"], "propertyName": null, "propertyTypes": null}),
+        ];
+        let relationship_properties = [
+            json!({"relType": ":`ACTED IN`", "propertyName": "roles", "propertyTypes": ["StringArray"]}),
+            json!({"relType": ":FOLLOWS", "propertyName": null, "propertyTypes": null}),
+        ];
+        let indexes = [
+            json!({"name": "index_343aff4e", "type": "LOOKUP", "entityType": "NODE", "labelsOrTypes": null, "properties": null, "state": "ONLINE"}),
+            json!({"name": "since", "type": "RANGE", "entityType": "RELATIONSHIP", "labelsOrTypes": ["FOLLOWS"], "properties": ["since"], "state": "POPULATING"}),
+        ];
+        let constraints = [
+            json!({"name": "person_name", "type": "UNIQUENESS", "entityType": "NODE", "labelsOrTypes": ["Person"], "properties": ["name"]}),
+        ];
+
+        // WHEN
+        let mut result = vec![];
+        for property in node_properties
+            .iter()
+            .filter_map(node_property_from_row)
+            .chain(
+                relationship_properties
+                    .iter()
+                    .filter_map(relationship_property_from_row),
+            )
+        {
+            result.push(format!(
+                "{}.{} {:?}",
+                property.on, property.key, property.types
+            ));
+        }
+        for index in indexes.iter().map(index_from_row) {
+            result.push(format!(
+                "{} {} on {} {:?} ({})",
+                index.index_type, index.name, index.on, index.properties, index.state
+            ));
+        }
+        for constraint in constraints.iter().map(constraint_from_row) {
+            result.push(format!(
+                "{} {} on {} {:?}",
+                constraint.constraint_type, constraint.name, constraint.on, constraint.properties
+            ));
+        }
+
+        // THEN
+        assert_snapshot!(result.join("\n"), @r#"
+        (:Person:Actor).name ["String"]
+        [:`ACTED IN`].roles ["StringArray"]
+        LOOKUP index_343aff4e on () [] (ONLINE)
+        RANGE since on [:FOLLOWS] ["since"] (POPULATING)
+        UNIQUENESS person_name on (:Person) ["name"]
+        "#);
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn row_to_value_fails_for_a_path_with_a_zero_relationship_index() {
+        // GIVEN
+        let knows = BoltUnboundedRelation::new(7.into(), "KNOWS".into(), BoltMap::new());
+        let path = BoltPath {
+            nodes: vec![person(1, "alice").into(), person(2, "bob").into()].into(),
+            rels: vec![knows.into()].into(),
+            indices: vec![0.into(), 1.into()].into(),
+        };
+        let row = Row::new(
+            BoltList::from(vec!["p".into()]),
+            BoltList::from(vec![BoltType::Path(path)]),
+        );
+
+        // WHEN
+        let result = row_to_value(&row).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(format!("{result:#}"), @"couldn't parse row as value: path has an invalid relationship index: 0");
     }
 }
@@ -1,18 +1,47 @@
 use anyhow::Context;
 use aws_config::SdkConfig;
 use aws_sdk_neptunedata::Client as NeptuneDataClient;
+use aws_sdk_neptunedata::config::retry::RetryConfig;
+use aws_sdk_neptunedata::error::{ProvideErrorMetadata, SdkError};
+use aws_sdk_neptunedata::types::{
+    GraphSummaryType, GremlinQueryStatus, OpenCypherExplainMode, PropertygraphSummary,
+};
 use aws_smithy_types::{Document, Number};
 use serde_json::{Map, Value};
+use std::time::Duration;
 
-use crate::domain::QueryResults;
+use super::QueryError;
+use super::neptune_plan::parse_explain_output;
+use crate::domain::{
+    GraphEntity, LabelSchema, PlanMode, PropertySchema, QueryLanguage, QueryParams, QueryPlan,
+    QueryResults, RelationshipTypeSchema, RowSink, Schema, SchemaElement, SchemaNames,
+    SchemaPattern, group_paths, write_rows,
+};
+
+/// Queries that have been running on the server for longer than the local query (plus this margin)
+/// were sent by someone else, and are left alone when cancelling.
+const CANCEL_MATCH_MARGIN: Duration = Duration::from_secs(2);
+
+/// HTTP statuses Neptune (or a load balancer in front of it) responds with when it's overloaded, or
+/// briefly unavailable.
+const TRANSIENT_STATUS_CODES: [u16; 4] = [429, 502, 503, 504];
+
+/// Error codes Neptune responds with when a request may succeed if it's retried.
+const TRANSIENT_ERROR_CODES: [&str; 4] = [
+    "ConcurrentModificationException",
+    "ServerShutdownException",
+    "ThrottlingException",
+    "TooManyRequestsException",
+];
 
 pub struct NeptuneClient {
     inner: NeptuneDataClient,
     db_uri: String,
+    read_only: bool,
 }
 
 impl NeptuneClient {
-    pub fn new(sdk_config: &SdkConfig, db_uri: &str) -> Self {
+    pub fn new(sdk_config: &SdkConfig, db_uri: &str, read_only: bool) -> Self {
         let neptune_config = aws_sdk_neptunedata::config::Builder::from(sdk_config)
             .endpoint_url(db_uri)
             .build();
@@ -21,6 +50,7 @@ impl NeptuneClient {
         Self {
             inner: neptune_client,
             db_uri: db_uri.to_string(),
+            read_only,
         }
     }
 
@@ -28,13 +58,49 @@ impl NeptuneClient {
         self.db_uri.clone()
     }
 
-    pub(super) async fn execute_query(&self, query: &str) -> anyhow::Result<QueryResults> {
-        let output = self
+    pub(super) fn read_only(&self) -> bool {
+        self.read_only
+    }
+
+    pub(super) async fn execute_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+    ) -> anyhow::Result<QueryResults> {
+        Ok(self.fetch_rows(query, params).await?.into())
+    }
+
+    /// Neptune returns all results in a single response, which are then handed to the sink one
+    /// row at a time.
+    pub(super) async fn stream_query<S: RowSink>(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        sink: &mut S,
+    ) -> anyhow::Result<u64> {
+        let rows = self.fetch_rows(query, params).await?;
+
+        write_rows(rows, sink)
+    }
+
+    async fn fetch_rows(&self, query: &str, params: &QueryParams) -> anyhow::Result<Vec<Value>> {
+        let mut request = self
             .inner
             .execute_open_cypher_query()
-            .open_cypher_query(query)
+            .open_cypher_query(query);
+
+        if !params.is_empty() {
+            let params_json = serde_json::to_string(params.as_map())
+                .context("couldn't serialize query parameters")?;
+            request = request.parameters(params_json);
+        }
+
+        let output = request
+            .customize()
+            .config_override(without_sdk_retries())
             .send()
             .await
+            .map_err(classify_sdk_error)
             .context("couldn't execute query")?;
 
         let document = output.results();
@@ -46,7 +112,455 @@ impl NeptuneClient {
             _ => anyhow::bail!("unexpected response received, was expecting an array"),
         };
 
-        Ok(results.into())
+        Ok(results)
+    }
+
+    pub(super) async fn explain_query(
+        &self,
+        query: &str,
+        params: &QueryParams,
+        mode: PlanMode,
+        details: bool,
+    ) -> anyhow::Result<QueryPlan> {
+        let explain_mode = match (mode, details) {
+            (_, true) => OpenCypherExplainMode::Details,
+            (PlanMode::Explain, false) => OpenCypherExplainMode::Static,
+            (PlanMode::Profile, false) => OpenCypherExplainMode::Dynamic,
+        };
+
+        let mut request = self
+            .inner
+            .execute_open_cypher_explain_query()
+            .open_cypher_query(query)
+            .explain_mode(explain_mode);
+
+        if !params.is_empty() {
+            let params_json = serde_json::to_string(params.as_map())
+                .context("couldn't serialize query parameters")?;
+            request = request.parameters(params_json);
+        }
+
+        let output = request
+            .send()
+            .await
+            .context("couldn't execute explain query")?;
+
+        let explain_output = String::from_utf8_lossy(output.results().as_ref());
+        let root = parse_explain_output(&explain_output)?;
+
+        Ok(QueryPlan { mode, root })
+    }
+
+    pub(super) async fn execute_gremlin_query(&self, query: &str) -> anyhow::Result<QueryResults> {
+        let output = self
+            .inner
+            .execute_gremlin_query()
+            .gremlin_query(query)
+            .customize()
+            .config_override(without_sdk_retries())
+            .send()
+            .await
+            .map_err(classify_sdk_error)
+            .context("couldn't execute query")?;
+
+        let Some(document) = output.result() else {
+            return Ok(QueryResults::Empty);
+        };
+
+        let result_value = document_to_value(document);
+
+        Ok(gremlin_result_to_rows(result_value)?.into())
+    }
+
+    /// Names are taken from Neptune's property graph summary, which is only available if the
+    /// cluster computes DFE statistics.
+    pub(super) async fn fetch_schema_names(&self) -> anyhow::Result<SchemaNames> {
+        let Some(summary) = self.fetch_graph_summary().await? else {
+            return Ok(SchemaNames::default());
+        };
+
+        let property_keys = summary
+            .node_properties()
+            .iter()
+            .chain(summary.edge_properties())
+            .flat_map(|properties| properties.keys().cloned());
+
+        Ok(SchemaNames::new(
+            summary.node_labels().to_vec(),
+            summary.edge_labels().to_vec(),
+            property_keys,
+        ))
+    }
+
+    /// Everything comes from Neptune's property graph summary (see [`schema_from_summary`]), as
+    /// counting labels and relationship types via queries would scan the whole graph.
+    pub(super) async fn fetch_schema(&self) -> anyhow::Result<Schema> {
+        let Some(summary) = self.fetch_graph_summary().await? else {
+            return Ok(Schema::default());
+        };
+
+        Ok(schema_from_summary(&summary))
+    }
+
+    async fn fetch_graph_summary(&self) -> anyhow::Result<Option<PropertygraphSummary>> {
+        let output = self
+            .inner
+            .get_propertygraph_summary()
+            .mode(GraphSummaryType::Detailed)
+            .send()
+            .await
+            .map_err(classify_sdk_error)
+            .context("couldn't fetch property graph summary")?;
+
+        Ok(output.payload().and_then(|p| p.graph_summary()).cloned())
+    }
+
+    /// Neptune doesn't let clients tag the queries they send, so the query is looked up among the
+    /// server's open queries by its text, and every match is cancelled.
+    pub(super) async fn cancel_query(
+        &self,
+        language: &QueryLanguage,
+        query: &str,
+        running_for: Duration,
+    ) -> anyhow::Result<u64> {
+        let open_queries = match language {
+            QueryLanguage::Cypher => self
+                .inner
+                .list_open_cypher_queries()
+                .include_waiting(true)
+                .send()
+                .await
+                .context("couldn't list open queries")?
+                .queries()
+                .to_vec(),
+            QueryLanguage::Gremlin => self
+                .inner
+                .list_gremlin_queries()
+                .include_waiting(true)
+                .send()
+                .await
+                .context("couldn't list open queries")?
+                .queries()
+                .to_vec(),
+        };
+
+        let mut num_cancelled = 0;
+        for query_id in get_matching_query_ids(&open_queries, query, running_for) {
+            match language {
+                QueryLanguage::Cypher => {
+                    self.inner
+                        .cancel_open_cypher_query()
+                        .query_id(&query_id)
+                        .send()
+                        .await
+                        .with_context(|| format!(r#"couldn't cancel query "{query_id}""#))?;
+                }
+                QueryLanguage::Gremlin => {
+                    self.inner
+                        .cancel_gremlin_query()
+                        .query_id(&query_id)
+                        .send()
+                        .await
+                        .with_context(|| format!(r#"couldn't cancel query "{query_id}""#))?;
+                }
+            }
+            num_cancelled += 1;
+        }
+
+        Ok(num_cancelled)
+    }
+}
+
+/// The detailed summary describes the structures of nodes (their properties, and the labels of the
+/// edges going out of them) and edges (their properties), along with how many there are of each,
+/// but not which labels they have. Properties are only tied to a label, and counts and patterns are
+/// only included, when there's a single node label (or relationship type) for all of them to
+/// belong to. Neptune manages indexes itself, and doesn't support constraints.
+fn schema_from_summary(summary: &PropertygraphSummary) -> Schema {
+    let only = |labels: &[String]| match labels {
+        [label] => Some(label.clone()),
+        _ => None,
+    };
+    let node_label = only(summary.node_labels());
+    let edge_label = only(summary.edge_labels());
+
+    let labels = summary
+        .node_labels()
+        .iter()
+        .map(|label| LabelSchema {
+            label: label.clone(),
+            count: node_label.as_ref().and_then(|_| summary.num_nodes()),
+        })
+        .collect();
+    let relationship_types = summary
+        .edge_labels()
+        .iter()
+        .map(|relationship_type| RelationshipTypeSchema {
+            relationship_type: relationship_type.clone(),
+            count: edge_label.as_ref().and_then(|_| summary.num_edges()),
+        })
+        .collect();
+
+    let patterns = match &node_label {
+        Some(label) => sorted_names(
+            summary
+                .node_structures()
+                .iter()
+                .flat_map(|s| s.distinct_outgoing_edge_labels()),
+        )
+        .into_iter()
+        .map(|relationship_type| SchemaPattern {
+            from: label.clone(),
+            relationship_type,
+            to: label.clone(),
+        })
+        .collect(),
+        None => vec![],
+    };
+
+    let properties = |keys: Vec<String>, element: SchemaElement| {
+        keys.into_iter()
+            .map(|key| PropertySchema {
+                on: element.clone(),
+                key,
+                types: vec![],
+            })
+            .collect::<Vec<_>>()
+    };
+    let mut schema = Schema {
+        labels,
+        relationship_types,
+        patterns,
+        properties: properties(
+            sorted_names(
+                summary
+                    .node_structures()
+                    .iter()
+                    .flat_map(|s| s.node_properties()),
+            ),
+            SchemaElement::node(node_label),
+        ),
+        ..Default::default()
+    };
+    schema.properties.extend(properties(
+        sorted_names(
+            summary
+                .edge_structures()
+                .iter()
+                .flat_map(|s| s.edge_properties()),
+        ),
+        SchemaElement::relationship(edge_label),
+    ));
+
+    schema
+}
+
+fn sorted_names<'a>(names: impl Iterator<Item = &'a String>) -> Vec<String> {
+    let mut names: Vec<String> = names.cloned().collect();
+    names.sort();
+    names.dedup();
+    names
+}
+
+fn classify_sdk_error<E>(err: SdkError<E>) -> QueryError
+where
+    E: ProvideErrorMetadata + std::error::Error + Send + Sync + 'static,
+{
+    let is_transient = match &err {
+        SdkError::ServiceError(e) => {
+            e.err()
+                .code()
+                .is_some_and(|code| TRANSIENT_ERROR_CODES.contains(&code))
+                || TRANSIENT_STATUS_CODES.contains(&e.raw().status().as_u16())
+        }
+        SdkError::DispatchFailure(e) => e.is_io() || e.is_timeout(),
+        // the response couldn't be parsed, so only its status tells whether it's worth retrying
+        SdkError::ResponseError(e) => TRANSIENT_STATUS_CODES.contains(&e.raw().status().as_u16()),
+        SdkError::TimeoutError(_) => true,
+        _ => false,
+    };
+
+    if is_transient {
+        QueryError::Transient(err.into())
+    } else {
+        QueryError::Permanent(err.into())
+    }
+}
+
+/// grafq retries failed queries itself (and reports doing so), so the SDK's own retries are turned
+/// off for them; other requests keep the SDK's retry behaviour.
+fn without_sdk_retries() -> aws_sdk_neptunedata::config::Builder {
+    aws_sdk_neptunedata::config::Builder::default().retry_config(RetryConfig::disabled())
+}
+
+/// Returns the ids of open queries with the same text as the query, skipping ones that are already
+/// being cancelled, or that were started well before it.
+fn get_matching_query_ids(
+    open_queries: &[GremlinQueryStatus],
+    query: &str,
+    running_for: Duration,
+) -> Vec<String> {
+    let max_elapsed = (running_for + CANCEL_MATCH_MARGIN).as_millis();
+
+    open_queries
+        .iter()
+        .filter(|q| q.query_string().map(str::trim) == Some(query.trim()))
+        .filter(|q| {
+            q.query_eval_stats().is_none_or(|stats| {
+                !stats.cancelled().unwrap_or_default()
+                    && stats.elapsed().is_none_or(|elapsed| {
+                        u128::try_from(elapsed).unwrap_or_default() <= max_elapsed
+                    })
+            })
+        })
+        .filter_map(|q| q.query_id().map(str::to_string))
+        .collect()
+}
+
+/// Gremlin responses look like `{"data": <GraphSON>, "meta": {...}}`. The data is unwrapped into
+/// plain JSON, and each element that is not an object is put under a "value" key so that all rows
+/// share the same shape.
+fn gremlin_result_to_rows(result: Value) -> anyhow::Result<Vec<Value>> {
+    let data = match result {
+        Value::Object(mut map) if map.contains_key("data") => {
+            map.remove("data").unwrap_or_default()
+        }
+        other => other,
+    };
+
+    let rows = match graphson_to_value(data) {
+        Value::Array(arr) => arr,
+        Value::Null => vec![],
+        _ => anyhow::bail!("unexpected response received, was expecting a list of results"),
+    };
+
+    Ok(rows
+        .into_iter()
+        .map(|row| match row {
+            Value::Object(_) => row,
+            other => {
+                let mut obj = Map::new();
+                obj.insert("value".to_string(), other);
+                Value::Object(obj)
+            }
+        })
+        .collect())
+}
+
+/// Strips GraphSON type wrappers (`{"@type": ..., "@value": ...}`) from a value. Vertices and edges
+/// are mapped to the same shape that Neptune uses for nodes and relationships in openCypher results.
+fn graphson_to_value(value: Value) -> Value {
+    match value {
+        Value::Object(mut map) => {
+            let (Some(Value::String(typ)), Some(inner)) =
+                (map.remove("@type"), map.remove("@value"))
+            else {
+                return Value::Object(
+                    map.into_iter()
+                        .map(|(k, v)| (k, graphson_to_value(v)))
+                        .collect(),
+                );
+            };
+
+            match (typ.as_str(), inner) {
+                ("g:List" | "g:Set", Value::Array(items)) => {
+                    Value::Array(items.into_iter().map(graphson_to_value).collect())
+                }
+                ("g:Map", Value::Array(items)) => {
+                    let mut obj = Map::new();
+                    let mut iter = items.into_iter();
+                    while let (Some(k), Some(v)) = (iter.next(), iter.next()) {
+                        let key = match graphson_to_value(k) {
+                            Value::String(s) => s,
+                            other => other.to_string(),
+                        };
+                        obj.insert(key, graphson_to_value(v));
+                    }
+                    Value::Object(obj)
+                }
+                ("g:Vertex", Value::Object(vertex)) => graphson_vertex_to_value(vertex),
+                ("g:Edge", Value::Object(edge)) => graphson_edge_to_value(edge),
+                ("g:VertexProperty" | "g:Property", Value::Object(mut prop)) => {
+                    graphson_to_value(prop.remove("value").unwrap_or_default())
+                }
+                ("g:Path", Value::Object(mut path)) => group_paths(graphson_to_value(
+                    path.remove("objects").unwrap_or_default(),
+                )),
+                (_, inner) => graphson_to_value(inner),
+            }
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(graphson_to_value).collect()),
+        other => other,
+    }
+}
+
+fn graphson_vertex_to_value(mut vertex: Map<String, Value>) -> Value {
+    let mut properties = Map::new();
+    if let Value::Object(props) = graphson_to_value(vertex.remove("properties").unwrap_or_default())
+    {
+        for (key, values) in props {
+            let value = match values {
+                Value::Array(mut items) if items.len() == 1 => items.remove(0),
+                other => other,
+            };
+            properties.insert(key, value);
+        }
+    }
+
+    let mut obj = Map::new();
+    obj.insert(
+        "~id".to_string(),
+        graphson_to_value(vertex.remove("id").unwrap_or_default()),
+    );
+    obj.insert("~entityType".to_string(), Value::String("node".to_string()));
+    obj.insert(
+        "~labels".to_string(),
+        Value::Array(vertex.remove("label").into_iter().collect()),
+    );
+    obj.insert("~properties".to_string(), Value::Object(properties));
+
+    into_entity(Value::Object(obj))
+}
+
+fn graphson_edge_to_value(mut edge: Map<String, Value>) -> Value {
+    let mut obj = Map::new();
+    obj.insert(
+        "~id".to_string(),
+        graphson_to_value(edge.remove("id").unwrap_or_default()),
+    );
+    obj.insert(
+        "~entityType".to_string(),
+        Value::String("relationship".to_string()),
+    );
+    obj.insert(
+        "~start".to_string(),
+        graphson_to_value(edge.remove("outV").unwrap_or_default()),
+    );
+    obj.insert(
+        "~end".to_string(),
+        graphson_to_value(edge.remove("inV").unwrap_or_default()),
+    );
+    obj.insert(
+        "~type".to_string(),
+        edge.remove("label").unwrap_or_default(),
+    );
+    obj.insert(
+        "~properties".to_string(),
+        graphson_to_value(
+            edge.remove("properties")
+                .unwrap_or_else(|| Value::Object(Map::new())),
+        ),
+    );
+
+    into_entity(Value::Object(obj))
+}
+
+/// Goes through the same types Neo4j's entities are mapped to, so that ids (which GraphSON may
+/// return as numbers) end up as strings for both databases.
+fn into_entity(value: Value) -> Value {
+    match GraphEntity::from_value(&value) {
+        Some(entity) => entity.into(),
+        None => value,
     }
 }
 
@@ -83,6 +597,11 @@ fn number_to_value(num: Number) -> Value {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use aws_sdk_neptunedata::config::http::HttpResponse;
+    use aws_sdk_neptunedata::error::ErrorMetadata;
+    use aws_sdk_neptunedata::operation::execute_open_cypher_query::ExecuteOpenCypherQueryError;
+    use aws_sdk_neptunedata::types::{EdgeStructure, NodeStructure, QueryEvalStats};
+    use aws_smithy_types::body::SdkBody;
     use insta::assert_yaml_snapshot;
     use std::collections::HashMap;
 
@@ -266,6 +785,344 @@ mod tests {
         ");
     }
 
+    #[test]
+    fn gremlin_result_to_rows_unwraps_graphson() -> anyhow::Result<()> {
+        // GIVEN
+        let result = serde_json::json!({
+            "data": {
+                "@type": "g:List",
+                "@value": [
+                    {
+                        "@type": "g:Map",
+                        "@value": [
+                            "name", {"@type": "g:List", "@value": ["Alice"]},
+                            {"@type": "g:T", "@value": "id"}, {"@type": "g:Int64", "@value": 1},
+                        ]
+                    },
+                    {"@type": "g:Int64", "@value": 42},
+                ]
+            },
+            "meta": {"@type": "g:Map", "@value": []}
+        });
+
+        // WHEN
+        let rows = gremlin_result_to_rows(result)?;
+
+        // THEN
+        assert_yaml_snapshot!(rows, @r"
+        - id: 1
+          name:
+            - Alice
+        - value: 42
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn gremlin_result_to_rows_maps_vertices_and_edges_with_string_ids() -> anyhow::Result<()> {
+        // GIVEN
+        let result = serde_json::json!({
+            "data": {
+                "@type": "g:List",
+                "@value": [
+                    {
+                        "@type": "g:Vertex",
+                        "@value": {
+                            "id": {"@type": "g:Int64", "@value": 1},
+                            "label": "Person",
+                            "properties": {
+                                "name": [{
+                                    "@type": "g:VertexProperty",
+                                    "@value": {"id": "p1", "value": "Alice", "label": "name"}
+                                }]
+                            }
+                        }
+                    },
+                    {
+                        "@type": "g:Edge",
+                        "@value": {
+                            "id": "e1",
+                            "label": "KNOWS",
+                            "inVLabel": "Person",
+                            "outVLabel": "Person",
+                            "inV": {"@type": "g:Int64", "@value": 2},
+                            "outV": {"@type": "g:Int64", "@value": 1},
+                            "properties": {
+                                "since": {
+                                    "@type": "g:Property",
+                                    "@value": {"key": "since", "value": {"@type": "g:Int32", "@value": 2020}}
+                                }
+                            }
+                        }
+                    }
+                ]
+            }
+        });
+
+        // WHEN
+        let rows = gremlin_result_to_rows(result)?;
+
+        // THEN
+        assert_yaml_snapshot!(rows, @r#"
+        - ~entityType: node
+          ~id: "1"
+          ~labels:
+            - Person
+          ~properties:
+            name: Alice
+        - ~end: "2"
+          ~entityType: relationship
+          ~id: e1
+          ~properties:
+            since: 2020
+          ~start: "1"
+          ~type: KNOWS
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn gremlin_result_to_rows_maps_paths() -> anyhow::Result<()> {
+        // GIVEN
+        let vertex = |id: &str, name: &str| {
+            serde_json::json!({
+                "@type": "g:Vertex",
+                "@value": {
+                    "id": id,
+                    "label": "Person",
+                    "properties": {
+                        "name": [{
+                            "@type": "g:VertexProperty",
+                            "@value": {"id": format!("p-{id}"), "value": name, "label": "name"}
+                        }]
+                    }
+                }
+            })
+        };
+        let result = serde_json::json!({
+            "data": {
+                "@type": "g:List",
+                "@value": [{
+                    "@type": "g:Path",
+                    "@value": {
+                        "labels": {"@type": "g:List", "@value": []},
+                        "objects": {
+                            "@type": "g:List",
+                            "@value": [
+                                vertex("v1", "Alice"),
+                                {
+                                    "@type": "g:Edge",
+                                    "@value": {
+                                        "id": "e1",
+                                        "label": "KNOWS",
+                                        "inV": "v2",
+                                        "outV": "v1"
+                                    }
+                                },
+                                vertex("v2", "Bob")
+                            ]
+                        }
+                    }
+                }]
+            }
+        });
+
+        // WHEN
+        let rows = gremlin_result_to_rows(result)?;
+
+        // THEN
+        let entities = rows
+            .iter()
+            .map(|row| GraphEntity::from_value(row).map(|e| e.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            entities,
+            vec![Some(
+                r#"(:Person {name: "Alice"})-[:KNOWS]->(:Person {name: "Bob"})"#.to_string()
+            )]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn schema_from_summary_takes_properties_from_node_and_edge_structures() {
+        // GIVEN
+        let summary = PropertygraphSummary::builder()
+            .num_nodes(171)
+            .num_edges(172)
+            .node_labels("Person")
+            .node_labels("Movie")
+            .edge_labels("ACTED_IN")
+            .node_structures(
+                NodeStructure::builder()
+                    .count(133)
+                    .node_properties("name")
+                    .node_properties("born")
+                    .distinct_outgoing_edge_labels("ACTED_IN")
+                    .build(),
+            )
+            .node_structures(
+                NodeStructure::builder()
+                    .count(38)
+                    .node_properties("title")
+                    .build(),
+            )
+            .edge_structures(
+                EdgeStructure::builder()
+                    .count(172)
+                    .edge_properties("roles")
+                    .build(),
+            )
+            .build();
+
+        // WHEN
+        let result = schema_from_summary(&summary);
+
+        // THEN
+        let labels = result
+            .labels
+            .iter()
+            .map(|l| (l.label.as_str(), l.count))
+            .collect::<Vec<_>>();
+        assert_eq!(labels, [("Person", None), ("Movie", None)]);
+        assert_eq!(result.relationship_types[0].relationship_type, "ACTED_IN");
+        assert_eq!(result.relationship_types[0].count, Some(172));
+        let properties = result
+            .properties
+            .iter()
+            .map(|p| format!("{}.{}", p.on, p.key))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            properties,
+            ["().born", "().name", "().title", "[:ACTED_IN].roles"]
+        );
+        assert!(result.patterns.is_empty());
+        assert!(result.indexes.is_empty());
+    }
+
+    #[test]
+    fn schema_from_summary_ties_everything_to_a_single_label() {
+        // GIVEN
+        let summary = PropertygraphSummary::builder()
+            .num_nodes(3748)
+            .num_edges(57645)
+            .node_labels("airport")
+            .edge_labels("route")
+            .edge_labels("contains")
+            .node_structures(
+                NodeStructure::builder()
+                    .count(3504)
+                    .node_properties("code")
+                    .distinct_outgoing_edge_labels("route")
+                    .build(),
+            )
+            .node_structures(
+                NodeStructure::builder()
+                    .count(244)
+                    .node_properties("code")
+                    .node_properties("desc")
+                    .distinct_outgoing_edge_labels("route")
+                    .distinct_outgoing_edge_labels("contains")
+                    .build(),
+            )
+            .build();
+
+        // WHEN
+        let result = schema_from_summary(&summary);
+
+        // THEN
+        assert_eq!(result.labels[0].count, Some(3748));
+        assert_eq!(result.relationship_types[1].count, None);
+        let patterns = result
+            .patterns
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            patterns,
+            [
+                "(:airport)-[:contains]->(:airport)",
+                "(:airport)-[:route]->(:airport)"
+            ]
+        );
+        let properties = result
+            .properties
+            .iter()
+            .map(|p| format!("{}.{}", p.on, p.key))
+            .collect::<Vec<_>>();
+        assert_eq!(properties, ["(:airport).code", "(:airport).desc"]);
+    }
+
+    #[test]
+    fn classify_sdk_error_goes_by_error_code_and_http_status() {
+        // GIVEN
+        let response = |status: u16| {
+            HttpResponse::new(
+                status.try_into().expect("status should've been valid"),
+                SdkBody::empty(),
+            )
+        };
+        let service_error = |code: &str, status: u16| {
+            SdkError::service_error(
+                ExecuteOpenCypherQueryError::generic(ErrorMetadata::builder().code(code).build()),
+                response(status),
+            )
+        };
+        let errors = vec![
+            service_error("ThrottlingException", 400),
+            service_error("InternalFailureException", 503),
+            service_error("MalformedQueryException", 400),
+            SdkError::response_error("couldn't parse response", response(502)),
+            SdkError::response_error("couldn't parse response", response(400)),
+        ];
+
+        // WHEN
+        let result = errors
+            .into_iter()
+            .map(|e| matches!(classify_sdk_error(e), QueryError::Transient(_)))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert_eq!(result, vec![true, true, false, true, false]);
+    }
+
+    #[test]
+    fn get_matching_query_ids_only_matches_the_query_sent_by_grafq() {
+        // GIVEN
+        let status = |id: &str, query: &str, elapsed: i32, cancelled: bool| {
+            GremlinQueryStatus::builder()
+                .query_id(id)
+                .query_string(query)
+                .query_eval_stats(
+                    QueryEvalStats::builder()
+                        .elapsed(elapsed)
+                        .cancelled(cancelled)
+                        .build(),
+                )
+                .build()
+        };
+        let open_queries = vec![
+            status("ours", "MATCH (n) RETURN n", 9_500, false),
+            status("different-query", "MATCH (m) RETURN m", 9_500, false),
+            status("started-earlier", "MATCH (n) RETURN n", 60_000, false),
+            status("already-cancelled", "MATCH (n) RETURN n", 9_500, true),
+            GremlinQueryStatus::builder()
+                .query_id("no-stats")
+                .query_string(" MATCH (n) RETURN n\n")
+                .build(),
+        ];
+
+        // WHEN
+        let result =
+            get_matching_query_ids(&open_queries, "MATCH (n) RETURN n", Duration::from_secs(10));
+
+        // THEN
+        assert_eq!(result, vec!["ours", "no-stats"]);
+    }
+
     fn get_document_with_numbers(values: Vec<(&str, Number)>) -> Document {
         let mut map = HashMap::new();
         for (key, value) in values {
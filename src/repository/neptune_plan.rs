@@ -0,0 +1,319 @@
+use crate::domain::PlanOperator;
+use std::collections::{HashMap, HashSet};
+
+const MAIN_TABLE: &str = "main";
+
+/// Parses the text output of Neptune's openCypher explain endpoint into an operator tree.
+///
+/// The output consists of a table for the main plan, followed by a table for each subquery
+/// (preceded by the subquery's name). Each row is an operator; the "Out #1"/"Out #2" columns hold
+/// the IDs of the operators it feeds into. Operators whose arguments reference a subquery get the
+/// subquery's plan as a child.
+///
+/// ```text
+/// ╔════╤════════╤════════╤═══════════════════╤════════════════════╤══════╗
+/// ║ ID │ Out #1 │ Out #2 │ Name              │ Arguments          │ Mode ║
+/// ╠════╪════════╪════════╪═══════════════════╪════════════════════╪══════╣
+/// ║ 0  │ 1      │ -      │ SolutionInjection │ solutions=[{}]     │ -    ║
+/// ╟────┼────────┼────────┼───────────────────┼────────────────────┼──────╢
+/// ║ 1  │ -      │ -      │ DFESubquery       │ subQuery=subQuery1 │ -    ║
+/// ╚════╧════════╧════════╧═══════════════════╧════════════════════╧══════╝
+///
+/// subQuery1
+/// ╔════╤════════╤════════╤═══════════════════╤════════════════════╤══════╗
+/// ...
+/// ```
+pub(super) fn parse_explain_output(output: &str) -> anyhow::Result<PlanOperator> {
+    let tables = parse_tables(output);
+
+    let Some(main) = tables.get(MAIN_TABLE) else {
+        anyhow::bail!("couldn't find a plan in the explain output");
+    };
+
+    let Some(first_id) = main.first().map(|r| r.id) else {
+        anyhow::bail!("plan in the explain output is empty");
+    };
+
+    let mut visited = HashSet::new();
+    build_operator(&tables, MAIN_TABLE, first_id, &mut visited)
+        .ok_or_else(|| anyhow::anyhow!("couldn't build plan from the explain output"))
+}
+
+#[derive(Debug, Default)]
+struct Row {
+    id: usize,
+    outs: Vec<usize>,
+    cells: HashMap<String, String>,
+}
+
+fn parse_tables(output: &str) -> HashMap<String, Vec<Row>> {
+    let mut tables = HashMap::new();
+    let mut last_text_line: Option<&str> = None;
+    let mut current: Option<(String, Vec<String>, Vec<Row>)> = None;
+
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with('╔') {
+            let name = if tables.is_empty() && current.is_none() {
+                MAIN_TABLE.to_string()
+            } else {
+                last_text_line.unwrap_or_default().to_string()
+            };
+            current = Some((name, vec![], vec![]));
+        } else if line.starts_with('╚') {
+            if let Some((name, _, rows)) = current.take() {
+                tables.insert(name, rows);
+            }
+        } else if let Some(contents) = line.strip_prefix('║') {
+            let Some((_, headers, rows)) = current.as_mut() else {
+                continue;
+            };
+
+            let cells: Vec<String> = contents
+                .trim_end_matches('║')
+                .split('│')
+                .map(|c| c.trim().to_string())
+                .collect();
+
+            if headers.is_empty() {
+                *headers = cells;
+                continue;
+            }
+
+            let id_cell = cells.first().map(|c| c.as_str()).unwrap_or_default();
+            if id_cell.is_empty() {
+                // long cells wrap over to the next line, with the remaining cells left empty
+                if let Some(previous) = rows.last_mut() {
+                    for (header, cell) in headers.iter().zip(cells) {
+                        if cell.is_empty() {
+                            continue;
+                        }
+                        let entry = previous.cells.entry(header.clone()).or_default();
+                        if !entry.is_empty() {
+                            entry.push(' ');
+                        }
+                        entry.push_str(&cell);
+                    }
+                }
+                continue;
+            }
+
+            let Ok(id) = id_cell.parse() else {
+                continue;
+            };
+
+            let cells: HashMap<String, String> = headers.iter().cloned().zip(cells).collect();
+            let outs = ["Out #1", "Out #2"]
+                .iter()
+                .filter_map(|h| cells.get(*h).and_then(|v| v.parse().ok()))
+                .collect();
+
+            rows.push(Row { id, outs, cells });
+        } else if !line.is_empty() && !line.starts_with(['╠', '╟']) {
+            last_text_line = Some(line);
+        }
+    }
+
+    tables
+}
+
+fn build_operator(
+    tables: &HashMap<String, Vec<Row>>,
+    table_name: &str,
+    id: usize,
+    visited: &mut HashSet<(String, usize)>,
+) -> Option<PlanOperator> {
+    if !visited.insert((table_name.to_string(), id)) {
+        return None;
+    }
+
+    let row = tables.get(table_name)?.iter().find(|r| r.id == id)?;
+    let cell = |header: &str| {
+        row.cells
+            .get(header)
+            .map(|v| v.as_str())
+            .filter(|v| !v.is_empty() && *v != "-")
+    };
+
+    let mut details = vec![];
+    if let Some(args) = cell("Arguments") {
+        details.push(args.to_string());
+    }
+    if let Some(mode) = cell("Mode") {
+        details.push(format!("mode={mode}"));
+    }
+
+    let mut children = vec![];
+    if let Some(subquery) = cell("Arguments").and_then(|args| {
+        args.split_once("subQuery=")
+            .map(|(_, rest)| rest.split([',', ' ']).next().unwrap_or(rest))
+    }) && let Some(first_id) = tables
+        .get(subquery)
+        .and_then(|rows| rows.first())
+        .map(|r| r.id)
+        && let Some(child) = build_operator(tables, subquery, first_id, visited)
+    {
+        children.push(child);
+    }
+
+    for out in &row.outs {
+        if let Some(child) = build_operator(tables, table_name, *out, visited) {
+            children.push(child);
+        }
+    }
+
+    Some(PlanOperator {
+        name: cell("Name").unwrap_or("Unknown").to_string(),
+        details,
+        estimated_rows: None,
+        rows: cell("Units Out").and_then(|v| v.parse().ok()),
+        db_hits: None,
+        time_ms: cell("Time (ms)").and_then(|v| v.parse().ok()),
+        children,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::{assert_debug_snapshot, assert_snapshot};
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn parse_explain_output_handles_static_plans() -> anyhow::Result<()> {
+        // GIVEN
+        let output = r#"
+Query:
+MATCH (n:Person) RETURN n.name LIMIT 1
+
+╔════╤════════╤════════╤═══════════════════╤════════════════════╤═════════════════════╗
+║ ID │ Out #1 │ Out #2 │ Name              │ Arguments          │ Mode                ║
+╠════╪════════╪════════╪═══════════════════╪════════════════════╪═════════════════════╣
+║ 0  │ 1      │ -      │ SolutionInjection │ solutions=[{}]     │ -                   ║
+╟────┼────────┼────────┼───────────────────┼────────────────────┼─────────────────────╢
+║ 1  │ 2      │ -      │ DFESubquery       │ subQuery=subQuery1 │ -                   ║
+╟────┼────────┼────────┼───────────────────┼────────────────────┼─────────────────────╢
+║ 2  │ -      │ -      │ TermResolution    │ vars=[?n.name]     │ id2value_opencypher ║
+╚════╧════════╧════════╧═══════════════════╧════════════════════╧═════════════════════╝
+
+subQuery1
+╔════╤════════╤════════╤═══════════════════════╤══════════════════════════════════╤══════╗
+║ ID │ Out #1 │ Out #2 │ Name                  │ Arguments                        │ Mode ║
+╠════╪════════╪════════╪═══════════════════════╪══════════════════════════════════╪══════╣
+║ 0  │ 1      │ -      │ DFEPipelineScan       │ pattern=Node(?n) with property   │ -    ║
+║    │        │        │                       │ 'name'                           │      ║
+╟────┼────────┼────────┼───────────────────────┼──────────────────────────────────┼──────╢
+║ 1  │ -      │ -      │ DFEDrain              │ -                                │ -    ║
+╚════╧════════╧════════╧═══════════════════════╧══════════════════════════════════╧══════╝
+"#;
+
+        // WHEN
+        let result = parse_explain_output(output)?;
+
+        // THEN
+        assert_debug_snapshot!(result, @r#"
+        PlanOperator {
+            name: "SolutionInjection",
+            details: [
+                "solutions=[{}]",
+            ],
+            estimated_rows: None,
+            rows: None,
+            db_hits: None,
+            time_ms: None,
+            children: [
+                PlanOperator {
+                    name: "DFESubquery",
+                    details: [
+                        "subQuery=subQuery1",
+                    ],
+                    estimated_rows: None,
+                    rows: None,
+                    db_hits: None,
+                    time_ms: None,
+                    children: [
+                        PlanOperator {
+                            name: "DFEPipelineScan",
+                            details: [
+                                "pattern=Node(?n) with property 'name'",
+                            ],
+                            estimated_rows: None,
+                            rows: None,
+                            db_hits: None,
+                            time_ms: None,
+                            children: [
+                                PlanOperator {
+                                    name: "DFEDrain",
+                                    details: [],
+                                    estimated_rows: None,
+                                    rows: None,
+                                    db_hits: None,
+                                    time_ms: None,
+                                    children: [],
+                                },
+                            ],
+                        },
+                        PlanOperator {
+                            name: "TermResolution",
+                            details: [
+                                "vars=[?n.name]",
+                                "mode=id2value_opencypher",
+                            ],
+                            estimated_rows: None,
+                            rows: None,
+                            db_hits: None,
+                            time_ms: None,
+                            children: [],
+                        },
+                    ],
+                },
+            ],
+        }
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_explain_output_reads_runtime_statistics() -> anyhow::Result<()> {
+        // GIVEN
+        let output = r#"
+╔════╤════════╤════════╤═══════════════════╤════════════════╤══════╤══════════╤═══════════╤═══════╤═══════════╗
+║ ID │ Out #1 │ Out #2 │ Name              │ Arguments      │ Mode │ Units In │ Units Out │ Ratio │ Time (ms) ║
+╠════╪════════╪════════╪═══════════════════╪════════════════╪══════╪══════════╪═══════════╪═══════╪═══════════╣
+║ 0  │ 1      │ -      │ SolutionInjection │ solutions=[{}] │ -    │ 0        │ 1         │ 0.00  │ 0         ║
+╟────┼────────┼────────┼───────────────────┼────────────────┼──────┼──────────┼───────────┼───────┼───────────╢
+║ 1  │ -      │ -      │ DFEDrain          │ -              │ -    │ 1        │ 5         │ 5.00  │ 4.25      ║
+╚════╧════════╧════════╧═══════════════════╧════════════════╧══════╧══════════╧═══════════╧═══════╧═══════════╝
+"#;
+
+        // WHEN
+        let result = parse_explain_output(output)?;
+
+        // THEN
+        assert_eq!(result.rows, Some(1));
+        assert_eq!(result.children[0].rows, Some(5));
+        assert_eq!(result.children[0].time_ms, Some(4.25));
+
+        Ok(())
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn parse_explain_output_fails_if_no_table_is_present() {
+        // GIVEN
+        let output = "Query:\nMATCH (n) RETURN n";
+
+        // WHEN
+        let result = parse_explain_output(output).expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"couldn't find a plan in the explain output");
+    }
+}
@@ -0,0 +1,23 @@
+/// An error returned while executing a query, classified by whether retrying the query could
+/// succeed (eg. when the database throttles requests, or a cluster is switching leaders).
+#[derive(Debug, thiserror::Error)]
+pub enum QueryError {
+    #[error("transient error")]
+    Transient(#[source] anyhow::Error),
+    #[error(transparent)]
+    Permanent(anyhow::Error),
+}
+
+impl QueryError {
+    pub fn is_transient(&self) -> bool {
+        matches!(self, QueryError::Transient(_))
+    }
+}
+
+/// Whether an error (with any context added to it) was caused by a transient query error.
+pub fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain().any(|e| {
+        e.downcast_ref::<QueryError>()
+            .is_some_and(QueryError::is_transient)
+    })
+}
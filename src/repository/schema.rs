@@ -0,0 +1,28 @@
+use crate::domain::escape_name;
+use serde_json::Value;
+
+pub(super) fn label_count_query(label: &str) -> String {
+    format!("MATCH (n:{}) RETURN count(n) AS count", escape_name(label))
+}
+
+pub(super) fn relationship_type_count_query(relationship_type: &str) -> String {
+    format!(
+        "MATCH ()-[r:{}]->() RETURN count(r) AS count",
+        escape_name(relationship_type)
+    )
+}
+
+/// Returns the count in the results of a count query.
+pub(super) fn count_from_rows(rows: &[Value]) -> Option<i64> {
+    rows.first()
+        .and_then(|row| row.get("count"))
+        .and_then(Value::as_i64)
+}
+
+/// Returns the strings in a column of the results, skipping other values.
+pub(super) fn strings_in_column(rows: &[Value], column: &str) -> Vec<String> {
+    rows.iter()
+        .filter_map(|row| row.get(column).and_then(Value::as_str))
+        .map(String::from)
+        .collect()
+}
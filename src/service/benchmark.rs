@@ -0,0 +1,492 @@
+use super::{
+    InterruptedQuery, QueryOutcome, Retried, Retry, execute_with_retries, run_interruptible,
+};
+use crate::domain::{
+    BaselineName, BenchmarkNumRuns, BenchmarkReport, BenchmarkReportFormat, BenchmarkRun,
+    ParamSets, QueryLanguage, QueryParams, QueryResults, QueryTimeout, RetryPolicy,
+};
+use crate::repository::QueryExecutor;
+use anyhow::Context;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// How the runs of a benchmark are executed.
+pub struct BenchmarkSettings {
+    pub num_runs: BenchmarkNumRuns,
+    pub num_warmup_runs: u16,
+    pub timeout: Option<QueryTimeout>,
+    pub retry_policy: RetryPolicy,
+}
+
+/// Progress of a benchmark, reported as it happens.
+pub enum BenchmarkEvent<'a> {
+    WarmUpStarted(u16),
+    WarmUpRunFinished(&'a BenchmarkRun),
+    WarmUpFinished,
+    BenchmarkStarted(u16),
+    RunFinished(&'a BenchmarkRun),
+    Retrying(&'a Retry<'a>),
+}
+
+/// The runs of a benchmark; if it was interrupted, only the ones that finished before that.
+pub struct BenchmarkRuns {
+    pub started_at: DateTime<Utc>,
+    pub runs: Vec<BenchmarkRun>,
+    pub interruption: Option<InterruptedQuery>,
+}
+
+/// Warms up, and then runs a query a number of times, binding a set of parameters to every run.
+///
+/// Interrupting any run (via Ctrl+C, or because it timed out) stops the benchmark; the runs that
+/// finished before that are returned along with the interruption.
+pub async fn run_benchmark<D: QueryExecutor>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    params: &ParamSets,
+    settings: &BenchmarkSettings,
+    mut on_event: impl FnMut(BenchmarkEvent),
+) -> anyhow::Result<BenchmarkRuns> {
+    let started_at = Utc::now();
+
+    if let Some(interruption) =
+        warm_up(db_client, language, query, params, settings, &mut on_event).await?
+    {
+        return Ok(BenchmarkRuns {
+            started_at,
+            runs: vec![],
+            interruption: Some(interruption),
+        });
+    }
+
+    on_event(BenchmarkEvent::BenchmarkStarted(settings.num_runs.value()));
+
+    // retried runs are timed by their successful attempt, with retries counted separately
+    let mut runs = vec![];
+    let mut picker = params.picker();
+    for i in 0..settings.num_runs.value() {
+        let (run_params, param_set) = params.pick(&mut picker);
+        let run = match run_benchmark_query(
+            db_client,
+            language,
+            query,
+            run_params,
+            settings,
+            &mut on_event,
+        )
+        .await
+        {
+            QueryOutcome::Finished(result) => result
+                .with_context(|| format!("couldn't execute query for benchmark run #{}", i + 1))?,
+            QueryOutcome::Interrupted(interruption) => {
+                return Ok(BenchmarkRuns {
+                    started_at,
+                    runs,
+                    interruption: Some(interruption),
+                });
+            }
+        };
+
+        let run = BenchmarkRun {
+            run: i + 1,
+            latency: run.last_attempt_took,
+            retries: run.retries,
+            param_set,
+        };
+        on_event(BenchmarkEvent::RunFinished(&run));
+        runs.push(run);
+    }
+
+    Ok(BenchmarkRuns {
+        started_at,
+        runs,
+        interruption: None,
+    })
+}
+
+/// Runs a query a number of times before it's benchmarked, so that caches are populated. Returns
+/// the interruption that stopped the warmup, if any.
+pub async fn warm_up<D: QueryExecutor>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    params: &ParamSets,
+    settings: &BenchmarkSettings,
+    mut on_event: impl FnMut(BenchmarkEvent),
+) -> anyhow::Result<Option<InterruptedQuery>> {
+    if settings.num_warmup_runs == 0 {
+        return Ok(None);
+    }
+
+    on_event(BenchmarkEvent::WarmUpStarted(settings.num_warmup_runs));
+    let mut picker = params.picker();
+    for i in 0..settings.num_warmup_runs {
+        let (run_params, param_set) = params.pick(&mut picker);
+        let run = match run_benchmark_query(
+            db_client,
+            language,
+            query,
+            run_params,
+            settings,
+            &mut on_event,
+        )
+        .await
+        {
+            QueryOutcome::Finished(result) => {
+                result.with_context(|| format!("couldn't get results for warmup run #{}", i + 1))?
+            }
+            QueryOutcome::Interrupted(interruption) => return Ok(Some(interruption)),
+        };
+
+        on_event(BenchmarkEvent::WarmUpRunFinished(&BenchmarkRun {
+            run: i + 1,
+            latency: run.last_attempt_took,
+            retries: run.retries,
+            param_set,
+        }));
+    }
+    on_event(BenchmarkEvent::WarmUpFinished);
+
+    Ok(None)
+}
+
+async fn run_benchmark_query<D: QueryExecutor>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    params: &QueryParams,
+    settings: &BenchmarkSettings,
+    on_event: &mut impl FnMut(BenchmarkEvent),
+) -> QueryOutcome<anyhow::Result<Retried<QueryResults>>> {
+    run_interruptible(
+        db_client,
+        language,
+        query,
+        settings.timeout,
+        execute_with_retries(
+            db_client,
+            language,
+            query,
+            params,
+            settings.retry_policy,
+            |r| on_event(BenchmarkEvent::Retrying(r)),
+        ),
+    )
+    .await
+}
+
+/// A CSV record for a single benchmark run; the report's details are repeated in every record so
+/// that reports from several benchmarks can be concatenated and analysed together.
+#[derive(Serialize)]
+struct BenchmarkCsvRecord<'a> {
+    query: &'a str,
+    language: &'a str,
+    backend: &'a str,
+    db_uri: &'a str,
+    started_at: &'a str,
+    finished_at: &'a str,
+    run: u16,
+    latency_us: u128,
+    retries: u16,
+    param_set: Option<usize>,
+}
+
+/// Writes a benchmark report to a file in the results directory, and returns its path.
+pub fn write_benchmark_report<P>(
+    report: &BenchmarkReport,
+    results_directory: P,
+    format: &BenchmarkReportFormat,
+    reference_time: DateTime<Utc>,
+) -> anyhow::Result<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    let results_directory = results_directory.as_ref();
+    std::fs::create_dir_all(results_directory).with_context(|| {
+        format!(
+            "couldn't create results directory: {}",
+            results_directory.to_string_lossy()
+        )
+    })?;
+
+    let report_path = results_directory.join(format!(
+        "{}-benchmark.{}",
+        reference_time.format("%Y-%m-%d-%H-%M-%S"),
+        format.extension()
+    ));
+
+    let file = File::create(&report_path).with_context(|| {
+        format!(
+            "couldn't create benchmark report: {}",
+            report_path.to_string_lossy()
+        )
+    })?;
+
+    write_report(report, format, BufWriter::new(file))?;
+
+    Ok(report_path)
+}
+
+/// Saves a benchmark report as a baseline, replacing any earlier baseline with the same name.
+pub fn save_baseline<P>(
+    report: &BenchmarkReport,
+    baselines_directory: P,
+    name: &BaselineName,
+) -> anyhow::Result<PathBuf>
+where
+    P: AsRef<Path>,
+{
+    let baselines_directory = baselines_directory.as_ref();
+    std::fs::create_dir_all(baselines_directory).with_context(|| {
+        format!(
+            "couldn't create baselines directory: {}",
+            baselines_directory.to_string_lossy()
+        )
+    })?;
+
+    let baseline_path = baseline_path(baselines_directory, name);
+    let file = File::create(&baseline_path).with_context(|| {
+        format!(
+            "couldn't create baseline file: {}",
+            baseline_path.to_string_lossy()
+        )
+    })?;
+
+    write_report(report, &BenchmarkReportFormat::Json, BufWriter::new(file))?;
+
+    Ok(baseline_path)
+}
+
+pub fn read_baseline<P>(
+    baselines_directory: P,
+    name: &BaselineName,
+) -> anyhow::Result<BenchmarkReport>
+where
+    P: AsRef<Path>,
+{
+    let baseline_path = baseline_path(baselines_directory.as_ref(), name);
+    let contents = match std::fs::read_to_string(&baseline_path) {
+        Ok(c) => c,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!(
+                r#"baseline "{name}" doesn't exist (save it first via --save-baseline {name})"#
+            );
+        }
+        Err(e) => {
+            return Err(e).with_context(|| {
+                format!(
+                    "couldn't read baseline file: {}",
+                    baseline_path.to_string_lossy()
+                )
+            });
+        }
+    };
+
+    serde_json::from_str(&contents).with_context(|| {
+        format!(
+            "baseline file is invalid: {}",
+            baseline_path.to_string_lossy()
+        )
+    })
+}
+
+fn baseline_path(baselines_directory: &Path, name: &BaselineName) -> PathBuf {
+    baselines_directory.join(format!("{}.json", name.value()))
+}
+
+fn write_report<W: Write>(
+    report: &BenchmarkReport,
+    format: &BenchmarkReportFormat,
+    mut writer: W,
+) -> anyhow::Result<()> {
+    match format {
+        BenchmarkReportFormat::Json => {
+            serde_json::to_writer_pretty(&mut writer, report)
+                .context("couldn't serialize benchmark report to JSON")?;
+            writeln!(writer)?;
+            writer.flush()?;
+        }
+        BenchmarkReportFormat::Csv => {
+            let mut csv_writer = csv::Writer::from_writer(writer);
+            for run in &report.runs {
+                csv_writer
+                    .serialize(BenchmarkCsvRecord {
+                        query: &report.query,
+                        language: &report.language,
+                        backend: &report.backend,
+                        db_uri: &report.db_uri,
+                        started_at: &report.started_at,
+                        finished_at: &report.finished_at,
+                        run: run.run,
+                        latency_us: run.latency.as_micros(),
+                        retries: run.retries,
+                        param_set: run.param_set,
+                    })
+                    .context("couldn't write benchmark run to CSV")?;
+            }
+            csv_writer.flush()?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{BenchmarkRun, BenchmarkStats};
+    use insta::assert_snapshot;
+    use std::str::FromStr;
+    use std::time::Duration;
+
+    fn get_report() -> BenchmarkReport {
+        let runs: Vec<BenchmarkRun> = [1500, 1250, 2003]
+            .into_iter()
+            .enumerate()
+            .map(|(i, micros)| BenchmarkRun {
+                run: i as u16 + 1,
+                latency: Duration::from_micros(micros),
+                retries: if i == 2 { 1 } else { 0 },
+                param_set: None,
+            })
+            .collect();
+        let times: Vec<Duration> = runs.iter().map(|r| r.latency).collect();
+
+        BenchmarkReport {
+            query: "MATCH (n) RETURN count(n)".to_string(),
+            language: "cypher".to_string(),
+            backend: "neo4j".to_string(),
+            db_uri: "bolt://127.0.0.1:7687".to_string(),
+            started_at: "2025-01-16T12:00:00+00:00".to_string(),
+            finished_at: "2025-01-16T12:00:01+00:00".to_string(),
+            num_warmup_runs: 3,
+            statistics: BenchmarkStats::new(&times).expect("stats should've been computed"),
+            runs,
+            param_sets: vec![],
+        }
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn writing_a_benchmark_report_as_csv_works() -> anyhow::Result<()> {
+        // GIVEN
+        let report = get_report();
+        let mut buf = vec![];
+
+        // WHEN
+        write_report(&report, &BenchmarkReportFormat::Csv, &mut buf)?;
+
+        // THEN
+        assert_snapshot!(String::from_utf8(buf)?, @"
+        query,language,backend,db_uri,started_at,finished_at,run,latency_us,retries,param_set
+        MATCH (n) RETURN count(n),cypher,neo4j,bolt://127.0.0.1:7687,2025-01-16T12:00:00+00:00,2025-01-16T12:00:01+00:00,1,1500,0,
+        MATCH (n) RETURN count(n),cypher,neo4j,bolt://127.0.0.1:7687,2025-01-16T12:00:00+00:00,2025-01-16T12:00:01+00:00,2,1250,0,
+        MATCH (n) RETURN count(n),cypher,neo4j,bolt://127.0.0.1:7687,2025-01-16T12:00:00+00:00,2025-01-16T12:00:01+00:00,3,2003,1,
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn saved_baselines_can_be_read_back() -> anyhow::Result<()> {
+        // GIVEN
+        let report = get_report();
+        let directory = tempfile::tempdir()?;
+        let name = BaselineName::from_str("main").map_err(|e| anyhow::anyhow!(e))?;
+
+        // WHEN
+        save_baseline(&report, directory.path(), &name)?;
+        let result = read_baseline(directory.path(), &name)?;
+
+        // THEN
+        assert_eq!(result.query, report.query);
+        assert_eq!(result.runs, report.runs);
+        // baselines store durations as whole microseconds
+        assert_eq!(
+            result.statistics.p99.as_micros(),
+            report.statistics.p99.as_micros()
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn writing_a_benchmark_report_as_json_works() -> anyhow::Result<()> {
+        // GIVEN
+        let report = get_report();
+        let mut buf = vec![];
+
+        // WHEN
+        write_report(&report, &BenchmarkReportFormat::Json, &mut buf)?;
+
+        // THEN
+        assert_snapshot!(String::from_utf8(buf)?, @r#"
+        {
+          "query": "MATCH (n) RETURN count(n)",
+          "language": "cypher",
+          "backend": "neo4j",
+          "db_uri": "bolt://127.0.0.1:7687",
+          "started_at": "2025-01-16T12:00:00+00:00",
+          "finished_at": "2025-01-16T12:00:01+00:00",
+          "num_warmup_runs": 3,
+          "statistics": {
+            "num_runs": 3,
+            "min_us": 1250,
+            "max_us": 2003,
+            "mean_us": 1584,
+            "median_us": 1500,
+            "p90_us": 1902,
+            "p95_us": 1952,
+            "p99_us": 1992,
+            "std_dev_us": 383,
+            "cv": 0.24206923699287305
+          },
+          "runs": [
+            {
+              "run": 1,
+              "latency_us": 1500,
+              "retries": 0
+            },
+            {
+              "run": 2,
+              "latency_us": 1250,
+              "retries": 0
+            },
+            {
+              "run": 3,
+              "latency_us": 2003,
+              "retries": 1
+            }
+          ]
+        }
+        "#);
+
+        Ok(())
+    }
+
+    //-------------//
+    //  FAILURES   //
+    //-------------//
+
+    #[test]
+    fn reading_a_missing_baseline_fails() -> anyhow::Result<()> {
+        // GIVEN
+        let directory = tempfile::tempdir()?;
+        let name = BaselineName::from_str("main").map_err(|e| anyhow::anyhow!(e))?;
+
+        // WHEN
+        let result = read_baseline(directory.path(), &name);
+
+        // THEN
+        let err = result.expect_err("reading baseline should've failed");
+        assert_snapshot!(err, @r#"baseline "main" doesn't exist (save it first via --save-baseline main)"#);
+
+        Ok(())
+    }
+}
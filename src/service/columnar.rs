@@ -0,0 +1,414 @@
+use crate::domain::{QueryResults, RowSink};
+use anyhow::Context;
+use arrow_array::RecordBatch;
+use arrow_schema::{DataType, Field, Fields, Schema};
+use parquet::arrow::ArrowWriter;
+use serde_json::{Map, Value};
+use std::io::Write;
+use std::sync::Arc;
+
+const BATCH_SIZE: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColumnarFormat {
+    Parquet,
+    Arrow,
+}
+
+/// Writes results in a columnar format. As the schema has to be inferred from every row before
+/// anything can be written, rows are buffered until the writer is finished.
+pub struct ColumnarWriter<W: Write> {
+    pub(super) inner: W,
+    format: ColumnarFormat,
+    rows: Vec<Value>,
+}
+
+impl<W: Write> ColumnarWriter<W> {
+    pub fn new(format: ColumnarFormat, writer: W) -> Self {
+        Self {
+            inner: writer,
+            format,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl<W: Write> RowSink for ColumnarWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        if !row.is_object() {
+            anyhow::bail!("expected each result to be an object");
+        }
+
+        self.rows.push(row);
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        let QueryResults::NonEmpty(results) = QueryResults::from(std::mem::take(&mut self.rows))
+        else {
+            return Ok(());
+        };
+
+        let rows = results.into_rows();
+        let columns = infer_columns(&rows);
+        let schema = Arc::new(Schema::new(
+            columns
+                .iter()
+                .map(|(name, column_type)| Field::new(name, column_type.data_type(), true))
+                .collect::<Vec<_>>(),
+        ));
+        let rows: Vec<Value> = rows
+            .into_iter()
+            .map(|row| conform_row(row, &columns))
+            .collect();
+
+        match self.format {
+            ColumnarFormat::Parquet => {
+                // the parquet writer needs its output to be Send, which isn't required of W
+                let mut writer = ArrowWriter::try_new(Vec::new(), Arc::clone(&schema), None)
+                    .context("couldn't start writing Parquet")?;
+                for batch in record_batches(&rows, &schema) {
+                    writer
+                        .write(&batch?)
+                        .context("couldn't write results to Parquet")?;
+                }
+                let bytes = writer
+                    .into_inner()
+                    .context("couldn't finish writing Parquet")?;
+                self.inner
+                    .write_all(&bytes)
+                    .context("couldn't write bytes to file")?;
+            }
+            ColumnarFormat::Arrow => {
+                let mut writer = arrow_ipc::writer::FileWriter::try_new(&mut self.inner, &schema)
+                    .context("couldn't start writing Arrow")?;
+                for batch in record_batches(&rows, &schema) {
+                    writer
+                        .write(&batch?)
+                        .context("couldn't write results to Arrow")?;
+                }
+                writer.finish().context("couldn't finish writing Arrow")?;
+            }
+        }
+
+        self.inner.flush().context("couldn't flush results")?;
+
+        Ok(())
+    }
+}
+
+fn record_batches<'a>(
+    rows: &'a [Value],
+    schema: &Arc<Schema>,
+) -> impl Iterator<Item = anyhow::Result<RecordBatch>> + 'a {
+    let schema = Arc::clone(schema);
+    rows.chunks(BATCH_SIZE).map(move |chunk| {
+        let mut decoder = arrow_json::ReaderBuilder::new(Arc::clone(&schema))
+            .with_batch_size(BATCH_SIZE)
+            .build_decoder()
+            .context("couldn't set up conversion of results")?;
+        decoder
+            .serialize(chunk)
+            .context("couldn't convert results to columns")?;
+
+        decoder
+            .flush()
+            .context("couldn't convert results to columns")?
+            .context("no results were converted to columns")
+    })
+}
+
+/// The type of a column, inferred from all the values in it.
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnType {
+    Null,
+    Bool,
+    Int,
+    Float,
+    String,
+    List(Box<ColumnType>),
+    Struct(Vec<(String, ColumnType)>),
+    // values that don't share a type are stored as JSON strings
+    Json,
+}
+
+impl ColumnType {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Bool,
+            Value::Number(n) if n.is_i64() => Self::Int,
+            Value::Number(_) => Self::Float,
+            Value::String(_) => Self::String,
+            Value::Array(items) => Self::List(Box::new(
+                items
+                    .iter()
+                    .map(Self::of)
+                    .fold(Self::Null, |acc, t| acc.merge(t)),
+            )),
+            Value::Object(obj) => Self::Struct(
+                obj.iter()
+                    .map(|(key, value)| (key.clone(), Self::of(value)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Null, t) | (t, Self::Null) => t,
+            (Self::Int, Self::Float) | (Self::Float, Self::Int) => Self::Float,
+            (Self::List(a), Self::List(b)) => Self::List(Box::new(a.merge(*b))),
+            (Self::Struct(a), Self::Struct(b)) => Self::Struct(merge_fields(a, b)),
+            (a, b) if a == b => a,
+            _ => Self::Json,
+        }
+    }
+
+    /// Structs without fields can't be stored in Parquet, so they're kept as JSON instead.
+    fn normalize(self) -> Self {
+        match self {
+            Self::List(t) => Self::List(Box::new(t.normalize())),
+            Self::Struct(fields) if fields.is_empty() => Self::Json,
+            Self::Struct(fields) => Self::Struct(
+                fields
+                    .into_iter()
+                    .map(|(name, t)| (name, t.normalize()))
+                    .collect(),
+            ),
+            t => t,
+        }
+    }
+
+    fn data_type(&self) -> DataType {
+        match self {
+            Self::Bool => DataType::Boolean,
+            Self::Int => DataType::Int64,
+            Self::Float => DataType::Float64,
+            Self::Null | Self::String | Self::Json => DataType::Utf8,
+            Self::List(t) => DataType::List(Arc::new(Field::new_list_field(t.data_type(), true))),
+            Self::Struct(fields) => DataType::Struct(Fields::from(
+                fields
+                    .iter()
+                    .map(|(name, t)| Field::new(name, t.data_type(), true))
+                    .collect::<Vec<_>>(),
+            )),
+        }
+    }
+
+    /// Turns values of columns that fell back to JSON into JSON strings (strings are kept as they
+    /// are, like in CSV files).
+    fn conform(&self, value: Value) -> Value {
+        match (self, value) {
+            (_, Value::Null) => Value::Null,
+            (Self::Json, Value::String(s)) => Value::String(s),
+            (Self::Json, value) => Value::String(value.to_string()),
+            (Self::List(t), Value::Array(items)) => {
+                Value::Array(items.into_iter().map(|item| t.conform(item)).collect())
+            }
+            (Self::Struct(fields), Value::Object(obj)) => {
+                Value::Object(conform_object(obj, fields))
+            }
+            (_, value) => value,
+        }
+    }
+}
+
+fn merge_fields(
+    mut fields: Vec<(String, ColumnType)>,
+    other: Vec<(String, ColumnType)>,
+) -> Vec<(String, ColumnType)> {
+    for (name, t) in other {
+        match fields.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => {
+                *existing = std::mem::replace(existing, ColumnType::Null).merge(t)
+            }
+            None => fields.push((name, t)),
+        }
+    }
+
+    fields
+}
+
+/// Infers the columns of the results, in the order their keys first appear in.
+fn infer_columns(rows: &[Value]) -> Vec<(String, ColumnType)> {
+    let columns = rows
+        .iter()
+        .filter_map(Value::as_object)
+        .fold(Vec::new(), |columns, obj| {
+            let row_columns = obj
+                .iter()
+                .map(|(key, value)| (key.clone(), ColumnType::of(value)))
+                .collect();
+            merge_fields(columns, row_columns)
+        });
+
+    columns
+        .into_iter()
+        .map(|(name, t)| (name, t.normalize()))
+        .collect()
+}
+
+fn conform_row(row: Value, columns: &[(String, ColumnType)]) -> Value {
+    match row {
+        Value::Object(obj) => Value::Object(conform_object(obj, columns)),
+        row => row,
+    }
+}
+
+fn conform_object(
+    mut obj: Map<String, Value>,
+    fields: &[(String, ColumnType)],
+) -> Map<String, Value> {
+    fields
+        .iter()
+        .filter_map(|(name, t)| obj.remove(name).map(|v| (name.clone(), t.conform(v))))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::write_rows;
+    use insta::assert_snapshot;
+    use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use std::io::Cursor;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn infer_columns_works() {
+        // GIVEN
+        let rows = sample_rows();
+
+        // WHEN
+        let columns = infer_columns(&rows);
+
+        // THEN
+        let result = columns
+            .iter()
+            .map(|(name, t)| format!("{name}: {}", t.data_type()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert_snapshot!(result, @r#"
+        compiled: Boolean
+        creators: List(Field { name: "item", data_type: Utf8, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} })
+        features: Struct([Field { name: "garbage_collection", data_type: Boolean, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} }, Field { name: "typing", data_type: Utf8, nullable: true, dict_id: 0, dict_is_ordered: false, metadata: {} }])
+        id: Utf8
+        language: Utf8
+        rating: Float64
+        year: Int64
+        "#);
+    }
+
+    #[test]
+    fn parquet_writer_keeps_types_of_values() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_file = tempfile::tempfile()?;
+        let mut writer = ColumnarWriter::new(ColumnarFormat::Parquet, &temp_file);
+
+        // WHEN
+        write_rows(sample_rows(), &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let reader = ParquetRecordBatchReaderBuilder::try_new(temp_file)?.build()?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        assert_snapshot!(batches_to_json(&batches)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn arrow_writer_keeps_types_of_values() -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = ColumnarWriter::new(ColumnarFormat::Arrow, &mut buffer);
+
+        // WHEN
+        write_rows(sample_rows(), &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let reader = arrow_ipc::reader::FileReader::try_new(Cursor::new(buffer), None)?;
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        assert_snapshot!(batches_to_json(&batches)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn columnar_writer_writes_nothing_when_there_are_no_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = ColumnarWriter::new(ColumnarFormat::Parquet, &mut buffer);
+
+        // WHEN
+        writer.finish()?;
+
+        // THEN
+        assert!(buffer.is_empty());
+
+        Ok(())
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[test]
+    fn columnar_writer_fails_if_rows_are_not_objects() {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = ColumnarWriter::new(ColumnarFormat::Arrow, &mut buffer);
+
+        // WHEN
+        let result = writer
+            .write_row(serde_json::json!(42))
+            .expect_err("result should've been an error");
+
+        // THEN
+        assert_snapshot!(result, @"expected each result to be an object");
+    }
+
+    fn batches_to_json(batches: &[RecordBatch]) -> anyhow::Result<String> {
+        let mut writer = arrow_json::LineDelimitedWriter::new(Vec::new());
+        writer.write_batches(&batches.iter().collect::<Vec<_>>())?;
+        writer.finish()?;
+
+        Ok(String::from_utf8(writer.into_inner())?)
+    }
+
+    fn sample_rows() -> Vec<Value> {
+        vec![
+            serde_json::json!({
+                "language": "Rust",
+                "year": 2010,
+                "compiled": true,
+                "creators": ["Graydon Hoare"],
+                "features": {"garbage_collection": false, "typing": "static"},
+                "rating": 4.8,
+                "id": 1,
+            }),
+            serde_json::json!({
+                "language": "Python",
+                "year": 1991,
+                "compiled": false,
+                "creators": [],
+                "features": {"typing": "dynamic"},
+                "rating": 4,
+                "id": "py",
+            }),
+            serde_json::json!({
+                "language": "Gleam",
+                "year": 2016,
+                "creators": null,
+                "features": null,
+                "id": {"slug": "gleam"},
+            }),
+        ]
+    }
+}
@@ -0,0 +1,103 @@
+use crate::domain::{QueryLanguage, QueryTimeout};
+use crate::repository::QueryExecutor;
+use std::time::{Duration, Instant};
+
+/// Cancelling a query on the server shouldn't block grafq for long if the server is unresponsive.
+const SERVER_CANCELLATION_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interruption {
+    TimedOut(QueryTimeout),
+    CtrlC,
+}
+
+impl std::fmt::Display for Interruption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interruption::TimedOut(timeout) => write!(f, "query timed out after {timeout}"),
+            Interruption::CtrlC => write!(f, "query cancelled"),
+        }
+    }
+}
+
+/// A query that was abandoned locally, along with the outcome of cancelling it on the server.
+#[derive(Debug)]
+pub struct InterruptedQuery {
+    pub reason: Interruption,
+    pub server_cancellation: Result<u64, String>,
+}
+
+impl InterruptedQuery {
+    pub fn server_cancellation_summary(&self) -> String {
+        match &self.server_cancellation {
+            Ok(0) => "no matching query was found running on the server".to_string(),
+            Ok(1) => "the query was cancelled on the server as well".to_string(),
+            Ok(n) => format!("{n} matching queries were cancelled on the server"),
+            Err(e) => format!("couldn't cancel the query on the server: {e}"),
+        }
+    }
+}
+
+pub enum QueryOutcome<T> {
+    Finished(T),
+    Interrupted(InterruptedQuery),
+}
+
+/// Drives a future that runs a query until it finishes, the timeout elapses, or the user presses
+/// Ctrl+C. Dropping the future only stops grafq from waiting on the query, so in the latter two
+/// cases the query is cancelled on the server as well.
+pub async fn run_interruptible<D, F>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    timeout: Option<QueryTimeout>,
+    future: F,
+) -> QueryOutcome<F::Output>
+where
+    D: QueryExecutor,
+    F: Future,
+{
+    let start = Instant::now();
+    let deadline = async move {
+        match timeout {
+            Some(t) => {
+                tokio::time::sleep(t.value()).await;
+                Interruption::TimedOut(t)
+            }
+            None => std::future::pending().await,
+        }
+    };
+
+    let reason = tokio::select! {
+        output = future => return QueryOutcome::Finished(output),
+        reason = deadline => reason,
+        Ok(()) = tokio::signal::ctrl_c() => Interruption::CtrlC,
+    };
+
+    let server_cancellation = cancel_on_server(db_client, language, query, start.elapsed()).await;
+
+    QueryOutcome::Interrupted(InterruptedQuery {
+        reason,
+        server_cancellation,
+    })
+}
+
+/// Cancels a query abandoned locally on the server, without waiting on an unresponsive server for
+/// long.
+pub async fn cancel_on_server<D: QueryExecutor>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    running_for: Duration,
+) -> Result<u64, String> {
+    match tokio::time::timeout(
+        SERVER_CANCELLATION_TIMEOUT,
+        db_client.cancel_query(language, query, running_for),
+    )
+    .await
+    {
+        Ok(Ok(num_cancelled)) => Ok(num_cancelled),
+        Ok(Err(e)) => Err(format!("{e:#}")),
+        Err(_) => Err("the server didn't respond in time".to_string()),
+    }
+}
@@ -1,5 +1,15 @@
+mod benchmark;
+mod columnar;
+mod interrupt;
 mod page;
+mod retry;
+mod sqlite;
 mod write;
 
+pub use benchmark::*;
+pub use columnar::*;
+pub use interrupt::*;
 pub use page::*;
+pub use retry::*;
+pub use sqlite::*;
 pub use write::*;
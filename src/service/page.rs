@@ -1,18 +1,133 @@
-use crate::domain::Pager;
+use super::ResultsWriter;
+use crate::domain::{Pager, ResultsFormat, RowSink};
 use anyhow::Context;
-use std::path::Path;
+use serde_json::Value;
+use std::io::Write;
+use std::process::{Child, ChildStdin, Stdio};
 
-pub fn page_results<P>(results_file: P, pager: &Pager) -> anyhow::Result<()>
-where
-    P: AsRef<Path>,
-{
-    let mut cmd = pager.get_command();
+/// Pipes results to the pager as they arrive. The pager is only started once the first row is
+/// received; once all rows are written, [`PagedResults::wait`] waits for the user to quit it.
+pub struct PagedResults<'a> {
+    pager: &'a Pager,
+    format: ResultsFormat,
+    child: Option<Child>,
+    writer: Option<ResultsWriter<PagerInput>>,
+}
+
+impl<'a> PagedResults<'a> {
+    pub fn new(pager: &'a Pager, format: &ResultsFormat) -> Self {
+        Self {
+            pager,
+            // binary formats would be unreadable in a pager, so results are shown as JSON instead
+            format: if format.is_binary() {
+                ResultsFormat::Json
+            } else {
+                format.clone()
+            },
+            child: None,
+            writer: None,
+        }
+    }
+
+    /// Waits for the pager to exit, without blocking the runtime's threads in the meantime.
+    pub async fn wait(&mut self) -> anyhow::Result<()> {
+        let Some(mut child) = self.child.take() else {
+            return Ok(());
+        };
+
+        tokio::task::spawn_blocking(move || child.wait())
+            .await
+            .context("couldn't wait for pager command")?
+            .context("pager command failed")?;
+
+        Ok(())
+    }
+}
+
+impl RowSink for PagedResults<'_> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        let writer = match &mut self.writer {
+            Some(writer) => writer,
+            None => {
+                let mut child = self
+                    .pager
+                    .get_command()
+                    .stdin(Stdio::piped())
+                    .spawn()
+                    .context("couldn't execute pager command")?;
+
+                let stdin = child
+                    .stdin
+                    .take()
+                    .context("couldn't get a handle to the pager's input")?;
+
+                self.child = Some(child);
+                self.writer.insert(ResultsWriter::new(
+                    &self.format,
+                    PagerInput {
+                        stdin,
+                        closed: false,
+                    },
+                ))
+            }
+        };
+
+        writer
+            .write_row(row)
+            .context("couldn't write results to pager")
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        let Some(mut writer) = self.writer.take() else {
+            return Ok(());
+        };
+
+        writer.finish().context("couldn't write results to pager")?;
+        // closes the pager's input, letting it know that there are no more results
+        drop(writer);
+
+        Ok(())
+    }
+
+    fn is_closed(&self) -> bool {
+        self.writer
+            .as_ref()
+            .is_some_and(|writer| writer.get_ref().closed)
+    }
+}
+
+/// The pager's input; once the user quits the pager, any further writes are discarded.
+struct PagerInput {
+    stdin: ChildStdin,
+    closed: bool,
+}
+
+impl Write for PagerInput {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.closed {
+            return Ok(buf.len());
+        }
+
+        match self.stdin.write(buf) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(buf.len())
+            }
+            result => result,
+        }
+    }
 
-    cmd.arg(results_file.as_ref())
-        .spawn()
-        .context("couldn't execute pager command")?
-        .wait()
-        .context("pager command failed")?;
+    fn flush(&mut self) -> std::io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
 
-    Ok(())
+        match self.stdin.flush() {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {
+                self.closed = true;
+                Ok(())
+            }
+            result => result,
+        }
+    }
 }
@@ -0,0 +1,424 @@
+use crate::cypher::{find_gremlin_write_step, find_write_clause};
+use crate::domain::{QueryLanguage, QueryParams, QueryResults, RetryPolicy, RowSink};
+use crate::repository::{QueryExecutor, is_transient};
+use serde_json::Value;
+use std::hash::{BuildHasher, Hasher, RandomState};
+use std::time::{Duration, Instant};
+
+/// A retry that's about to happen, after the previous attempt failed with a transient error.
+pub struct Retry<'a> {
+    pub retry: u16,
+    pub max_retries: u16,
+    pub delay: Duration,
+    pub error: &'a anyhow::Error,
+}
+
+impl std::fmt::Display for Retry<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:#}; retrying in {} ms ({}/{})",
+            self.error,
+            self.delay.as_millis(),
+            self.retry,
+            self.max_retries
+        )
+    }
+}
+
+/// The result of a query that may have been retried.
+pub struct Retried<T> {
+    pub value: T,
+    pub retries: u16,
+    /// How long the successful attempt took; failed attempts and the waits between them aren't
+    /// included.
+    pub last_attempt_took: Duration,
+}
+
+/// Streams a query's results, retrying it if it fails with a transient error before any rows have
+/// been handed to the sink.
+pub async fn stream_with_retries<D, S>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    params: &QueryParams,
+    sink: &mut S,
+    policy: RetryPolicy,
+    mut on_retry: impl FnMut(&Retry),
+) -> anyhow::Result<Retried<u64>>
+where
+    D: QueryExecutor,
+    S: RowSink,
+{
+    let retryable = is_retryable(db_client, language, query).await;
+    let mut retries = 0;
+
+    loop {
+        let start = Instant::now();
+        let mut counter = RowCounter {
+            sink: &mut *sink,
+            num_rows: 0,
+        };
+
+        let err = match db_client
+            .stream(language, query, params, &mut counter)
+            .await
+        {
+            Ok(num_rows) => {
+                return Ok(Retried {
+                    value: num_rows,
+                    retries,
+                    last_attempt_took: start.elapsed(),
+                });
+            }
+            Err(e) => e,
+        };
+
+        let retryable = retryable && counter.num_rows == 0;
+        retries += 1;
+        match next_delay(policy, retries, retryable, &err) {
+            Some(delay) => wait_to_retry(policy, retries, delay, &err, &mut on_retry).await,
+            None => return Err(err),
+        }
+    }
+}
+
+/// Executes a query, retrying it if it fails with a transient error.
+pub async fn execute_with_retries<D: QueryExecutor>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+    params: &QueryParams,
+    policy: RetryPolicy,
+    mut on_retry: impl FnMut(&Retry),
+) -> anyhow::Result<Retried<QueryResults>> {
+    let retryable = is_retryable(db_client, language, query).await;
+    let mut retries = 0;
+
+    loop {
+        let start = Instant::now();
+        let err = match db_client.execute(language, query, params).await {
+            Ok(results) => {
+                return Ok(Retried {
+                    value: results,
+                    retries,
+                    last_attempt_took: start.elapsed(),
+                });
+            }
+            Err(e) => e,
+        };
+
+        retries += 1;
+        match next_delay(policy, retries, retryable, &err) {
+            Some(delay) => wait_to_retry(policy, retries, delay, &err, &mut on_retry).await,
+            None => return Err(err),
+        }
+    }
+}
+
+/// Only read queries are retried, as a write that failed midway could end up being applied twice.
+/// Queries in an explicit transaction aren't retried either, as the transaction doesn't outlive
+/// the error.
+async fn is_retryable<D: QueryExecutor>(
+    db_client: &D,
+    language: &QueryLanguage,
+    query: &str,
+) -> bool {
+    let is_read_query = match language {
+        QueryLanguage::Cypher => find_write_clause(query).is_none(),
+        QueryLanguage::Gremlin => find_gremlin_write_step(query).is_none(),
+    };
+
+    is_read_query && !db_client.in_transaction().await
+}
+
+fn next_delay(
+    policy: RetryPolicy,
+    retry: u16,
+    retryable: bool,
+    err: &anyhow::Error,
+) -> Option<Duration> {
+    if !retryable || !is_transient(err) {
+        return None;
+    }
+
+    policy.delay(retry, random_jitter())
+}
+
+async fn wait_to_retry(
+    policy: RetryPolicy,
+    retry: u16,
+    delay: Duration,
+    err: &anyhow::Error,
+    on_retry: &mut impl FnMut(&Retry),
+) {
+    on_retry(&Retry {
+        retry,
+        max_retries: policy.max_retries(),
+        delay,
+        error: err,
+    });
+    tokio::time::sleep(delay).await;
+}
+
+/// A number in `[0, 1)`; every `RandomState` is seeded with fresh random keys, which is random
+/// enough for spreading out retries.
+fn random_jitter() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+
+    (random >> 11) as f64 / (1_u64 << 53) as f64
+}
+
+struct RowCounter<'a, S: RowSink> {
+    sink: &'a mut S,
+    num_rows: u64,
+}
+
+impl<S: RowSink> RowSink for RowCounter<'_, S> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        self.num_rows += 1;
+        self.sink.write_row(row)
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sink.is_closed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{PlanMode, QueryPlan, Schema, SchemaNames};
+    use crate::repository::QueryError;
+    use insta::assert_snapshot;
+    use std::sync::atomic::{AtomicU16, Ordering};
+
+    /// Fails the first `failures` queries with the given kind of error.
+    struct FlakyExecutor {
+        failures: u16,
+        transient: bool,
+        calls: AtomicU16,
+    }
+
+    impl FlakyExecutor {
+        fn new(failures: u16, transient: bool) -> Self {
+            Self {
+                failures,
+                transient,
+                calls: AtomicU16::new(0),
+            }
+        }
+    }
+
+    impl QueryExecutor for FlakyExecutor {
+        async fn execute_query(
+            &self,
+            query: &str,
+            params: &QueryParams,
+        ) -> anyhow::Result<QueryResults> {
+            let mut rows = vec![];
+            self.stream_query(query, params, &mut rows).await?;
+            Ok(rows.into())
+        }
+
+        async fn stream_query<S: RowSink>(
+            &self,
+            _query: &str,
+            _params: &QueryParams,
+            sink: &mut S,
+        ) -> anyhow::Result<u64> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if call <= self.failures {
+                let err = anyhow::anyhow!("ThrottlingException: rate exceeded (call {call})");
+                let err = if self.transient {
+                    QueryError::Transient(err)
+                } else {
+                    QueryError::Permanent(err)
+                };
+                return Err(anyhow::Error::from(err).context("couldn't execute query"));
+            }
+
+            sink.write_row(serde_json::json!({"n": 1}))?;
+            Ok(1)
+        }
+
+        async fn execute_gremlin_query(&self, _query: &str) -> anyhow::Result<QueryResults> {
+            unimplemented!()
+        }
+
+        async fn explain_query(
+            &self,
+            _query: &str,
+            _params: &QueryParams,
+            _mode: PlanMode,
+            _details: bool,
+        ) -> anyhow::Result<QueryPlan> {
+            unimplemented!()
+        }
+
+        async fn begin_transaction(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn commit_transaction(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn rollback_transaction(&self) -> anyhow::Result<()> {
+            unimplemented!()
+        }
+
+        async fn in_transaction(&self) -> bool {
+            false
+        }
+
+        async fn cancel_query(
+            &self,
+            _language: &QueryLanguage,
+            _query: &str,
+            _running_for: Duration,
+        ) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+
+        async fn fetch_schema_names(&self) -> anyhow::Result<SchemaNames> {
+            unimplemented!()
+        }
+
+        async fn fetch_schema(&self) -> anyhow::Result<Schema> {
+            unimplemented!()
+        }
+
+        fn db_uri(&self) -> String {
+            "bolt://127.0.0.1:7687".to_string()
+        }
+    }
+
+    const READ_QUERY: &str = "MATCH (n) RETURN n LIMIT 1";
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[tokio::test]
+    async fn read_queries_are_retried_after_transient_errors() -> anyhow::Result<()> {
+        // GIVEN
+        let db_client = FlakyExecutor::new(2, true);
+        let mut rows: Vec<Value> = vec![];
+        let mut retries = vec![];
+
+        // WHEN
+        let result = stream_with_retries(
+            &db_client,
+            &QueryLanguage::Cypher,
+            READ_QUERY,
+            &QueryParams::default(),
+            &mut rows,
+            RetryPolicy::new(3),
+            |r| retries.push(format!("{:#} ({}/{})", r.error, r.retry, r.max_retries)),
+        )
+        .await?;
+
+        // THEN
+        assert_eq!(result.value, 1);
+        assert_eq!(result.retries, 2);
+        assert_eq!(rows.len(), 1);
+        assert_snapshot!(retries.join("\n"), @"
+        couldn't execute query: transient error: ThrottlingException: rate exceeded (call 1) (1/3)
+        couldn't execute query: transient error: ThrottlingException: rate exceeded (call 2) (2/3)
+        ");
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn execute_with_retries_reports_retries() -> anyhow::Result<()> {
+        // GIVEN
+        let db_client = FlakyExecutor::new(1, true);
+        let mut num_retries = 0;
+
+        // WHEN
+        let result = execute_with_retries(
+            &db_client,
+            &QueryLanguage::Cypher,
+            READ_QUERY,
+            &QueryParams::default(),
+            RetryPolicy::new(3),
+            |_| num_retries += 1,
+        )
+        .await?;
+
+        // THEN
+        assert_eq!(result.retries, 1);
+        assert_eq!(num_retries, 1);
+
+        Ok(())
+    }
+
+    //------------//
+    //  FAILURES  //
+    //------------//
+
+    #[tokio::test]
+    async fn retries_stop_once_they_are_exhausted() {
+        // GIVEN
+        let db_client = FlakyExecutor::new(5, true);
+
+        // WHEN
+        let result = execute_with_retries(
+            &db_client,
+            &QueryLanguage::Cypher,
+            READ_QUERY,
+            &QueryParams::default(),
+            RetryPolicy::new(2),
+            |_| {},
+        )
+        .await;
+
+        // THEN
+        assert!(result.is_err());
+        assert_eq!(db_client.calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn permanent_errors_are_not_retried() {
+        // GIVEN
+        let db_client = FlakyExecutor::new(1, false);
+
+        // WHEN
+        let result = execute_with_retries(
+            &db_client,
+            &QueryLanguage::Cypher,
+            READ_QUERY,
+            &QueryParams::default(),
+            RetryPolicy::new(3),
+            |_| {},
+        )
+        .await;
+
+        // THEN
+        assert!(result.is_err());
+        assert_eq!(db_client.calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn write_queries_are_not_retried() {
+        // GIVEN
+        let db_client = FlakyExecutor::new(1, true);
+
+        // WHEN
+        let result = execute_with_retries(
+            &db_client,
+            &QueryLanguage::Cypher,
+            "MERGE (n:Person {id: 1}) RETURN n",
+            &QueryParams::default(),
+            RetryPolicy::new(3),
+            |_| {},
+        )
+        .await;
+
+        // THEN
+        assert!(result.is_err());
+        assert_eq!(db_client.calls.load(Ordering::SeqCst), 1);
+    }
+}
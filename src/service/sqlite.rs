@@ -0,0 +1,501 @@
+use crate::domain::{QueryResults, RowSink, get_cell, get_headers};
+use anyhow::Context;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OptionalExtension, params, params_from_iter};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+const QUERIES_TABLE: &str = "queries";
+
+/// What's recorded about a query in the database's "queries" table.
+pub struct QueryRecord {
+    pub query: String,
+    pub db_uri: String,
+    pub started_at: DateTime<Utc>,
+    pub timer: Instant,
+}
+
+/// Writes results to a table in a SQLite database, and records the query they're for. As the
+/// columns' types are inferred from every row, rows are buffered until the writer is finished.
+/// Rows that aren't objects go in a "value" column, as they do in the other formats.
+pub struct SqliteWriter {
+    path: PathBuf,
+    table: Option<String>,
+    record: QueryRecord,
+    rows: Vec<Value>,
+}
+
+impl SqliteWriter {
+    /// Results go to a table with the given name, or to "results_<id>" (where id is that of the
+    /// query in the "queries" table) if no name is given. If the table already exists, results are
+    /// appended to it, with columns it doesn't have yet added to it.
+    pub fn new<P>(path: P, table: Option<String>, record: QueryRecord) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            path: path.as_ref().to_path_buf(),
+            table,
+            record,
+            rows: Vec::new(),
+        }
+    }
+}
+
+impl RowSink for SqliteWriter {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        self.rows.push(row);
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        let QueryResults::NonEmpty(results) = QueryResults::from(std::mem::take(&mut self.rows))
+        else {
+            return Ok(());
+        };
+        let rows = results.into_rows();
+        let columns = infer_columns(&rows);
+        if columns.is_empty() {
+            anyhow::bail!("results don't have any columns");
+        }
+
+        let mut connection = Connection::open(&self.path).with_context(|| {
+            format!(
+                "couldn't open results database: {}",
+                self.path.to_string_lossy()
+            )
+        })?;
+        let tx = connection
+            .transaction()
+            .context("couldn't start a transaction")?;
+
+        tx.execute(
+            &format!(
+                "CREATE TABLE IF NOT EXISTS {QUERIES_TABLE} (
+    id INTEGER PRIMARY KEY,
+    table_name TEXT NOT NULL,
+    query TEXT NOT NULL,
+    db_uri TEXT NOT NULL,
+    started_at TEXT NOT NULL,
+    duration_ms INTEGER NOT NULL,
+    num_rows INTEGER NOT NULL
+)"
+            ),
+            [],
+        )
+        .context("couldn't create the queries table")?;
+
+        let last_id: Option<i64> = tx
+            .query_row(&format!("SELECT MAX(id) FROM {QUERIES_TABLE}"), [], |r| {
+                r.get(0)
+            })
+            .optional()
+            .context("couldn't read the queries table")?
+            .flatten();
+        let id = last_id.unwrap_or_default() + 1;
+        let table = self
+            .table
+            .clone()
+            .unwrap_or_else(|| format!("results_{id}"));
+
+        let existing_columns = {
+            let mut statement = tx
+                .prepare(&format!("PRAGMA table_info({})", quote_identifier(&table)))
+                .with_context(|| format!(r#"couldn't read the columns of table "{table}""#))?;
+            statement
+                .query_map([], |r| r.get::<_, String>("name"))
+                .and_then(|names| names.collect::<Result<Vec<_>, _>>())
+                .with_context(|| format!(r#"couldn't read the columns of table "{table}""#))?
+        };
+
+        if existing_columns.is_empty() {
+            let column_definitions = columns
+                .iter()
+                .map(|(name, affinity)| column_definition(name, *affinity))
+                .collect::<Vec<_>>();
+            tx.execute(
+                &format!(
+                    "CREATE TABLE {} ({})",
+                    quote_identifier(&table),
+                    column_definitions.join(", ")
+                ),
+                [],
+            )
+            .with_context(|| format!(r#"couldn't create table "{table}""#))?;
+        } else {
+            for (name, affinity) in columns.iter().filter(|(name, _)| {
+                !existing_columns
+                    .iter()
+                    .any(|c| c.eq_ignore_ascii_case(name))
+            }) {
+                tx.execute(
+                    &format!(
+                        "ALTER TABLE {} ADD COLUMN {}",
+                        quote_identifier(&table),
+                        column_definition(name, *affinity)
+                    ),
+                    [],
+                )
+                .with_context(|| format!(r#"couldn't add column "{name}" to table "{table}""#))?;
+            }
+        }
+
+        {
+            let mut insert = tx
+                .prepare(&format!(
+                    "INSERT INTO {} ({}) VALUES ({})",
+                    quote_identifier(&table),
+                    columns
+                        .iter()
+                        .map(|(name, _)| quote_identifier(name))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    vec!["?"; columns.len()].join(", ")
+                ))
+                .context("couldn't prepare inserting results")?;
+
+            for row in &rows {
+                let values = columns.iter().map(|(name, _)| {
+                    get_cell(row, name)
+                        .map(to_sql_value)
+                        .unwrap_or(SqlValue::Null)
+                });
+                insert
+                    .execute(params_from_iter(values))
+                    .with_context(|| format!(r#"couldn't insert results into table "{table}""#))?;
+            }
+        }
+
+        tx.execute(
+            &format!(
+                "INSERT INTO {QUERIES_TABLE} (id, table_name, query, db_uri, started_at, duration_ms, num_rows) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"
+            ),
+            params![
+                id,
+                table,
+                self.record.query,
+                self.record.db_uri,
+                self.record
+                    .started_at
+                    .to_rfc3339_opts(SecondsFormat::Millis, true),
+                i64::try_from(self.record.timer.elapsed().as_millis()).unwrap_or(i64::MAX),
+                i64::try_from(rows.len()).unwrap_or(i64::MAX),
+            ],
+        )
+        .context("couldn't record the query")?;
+
+        tx.commit().context("couldn't commit results")?;
+
+        Ok(())
+    }
+}
+
+/// The type affinity of a column, going by the values in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColumnAffinity {
+    Unknown,
+    Integer,
+    Real,
+    Text,
+}
+
+impl ColumnAffinity {
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Unknown,
+            Value::Bool(_) => Self::Integer,
+            Value::Number(n) if n.is_i64() => Self::Integer,
+            Value::Number(_) => Self::Real,
+            // lists and maps are stored as JSON
+            Value::String(_) | Value::Array(_) | Value::Object(_) => Self::Text,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        match (self, other) {
+            (Self::Unknown, a) | (a, Self::Unknown) => a,
+            (Self::Integer, Self::Real) | (Self::Real, Self::Integer) => Self::Real,
+            (a, b) if a == b => a,
+            _ => Self::Text,
+        }
+    }
+
+    fn type_name(&self) -> Option<&'static str> {
+        match self {
+            Self::Unknown => None,
+            Self::Integer => Some("INTEGER"),
+            Self::Real => Some("REAL"),
+            Self::Text => Some("TEXT"),
+        }
+    }
+}
+
+/// Infers the columns of the results, in the order their keys first appear in.
+fn infer_columns(rows: &[Value]) -> Vec<(String, ColumnAffinity)> {
+    get_headers(rows)
+        .into_iter()
+        .map(|header| {
+            let affinity = rows
+                .iter()
+                .filter_map(|row| get_cell(row, &header))
+                .map(ColumnAffinity::of)
+                .fold(ColumnAffinity::Unknown, ColumnAffinity::merge);
+            (header, affinity)
+        })
+        .collect()
+}
+
+fn column_definition(name: &str, affinity: ColumnAffinity) -> String {
+    match affinity.type_name() {
+        Some(t) => format!("{} {t}", quote_identifier(name)),
+        None => quote_identifier(name),
+    }
+}
+
+fn to_sql_value(value: &Value) -> SqlValue {
+    match value {
+        Value::Null => SqlValue::Null,
+        Value::Bool(b) => SqlValue::Integer(i64::from(*b)),
+        Value::Number(n) => match (n.as_i64(), n.as_f64()) {
+            (Some(i), _) => SqlValue::Integer(i),
+            (None, Some(f)) => SqlValue::Real(f),
+            (None, None) => SqlValue::Text(n.to_string()),
+        },
+        Value::String(s) => SqlValue::Text(s.clone()),
+        Value::Array(_) | Value::Object(_) => SqlValue::Text(value.to_string()),
+    }
+}
+
+fn quote_identifier(name: &str) -> String {
+    format!("\"{}\"", name.replace('"', "\"\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::write_rows;
+    use insta::assert_snapshot;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn sqlite_writer_writes_typed_columns() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("results.db");
+        let mut writer = SqliteWriter::new(&path, None, query_record("MATCH (n) RETURN n"));
+
+        // WHEN
+        write_rows(sample_rows(), &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let connection = Connection::open(&path)?;
+        let schema: String = connection.query_row(
+            "SELECT sql FROM sqlite_master WHERE name = 'results_1'",
+            [],
+            |r| r.get(0),
+        )?;
+        let rows = dump_table(&connection, "results_1")?;
+        assert_snapshot!(format!("{schema}\n{rows}"), @r#"
+        CREATE TABLE "results_1" ("compiled" INTEGER, "creators" TEXT, "id" TEXT, "language" TEXT, "rating" REAL, "year" INTEGER, "typing")
+        results_1: compiled | creators | id | language | rating | year | typing
+        Integer(1) | Text("[\"Graydon Hoare\"]") | Text("1") | Text("Rust") | Real(4.8) | Integer(2010) | Null
+        Integer(0) | Text("[]") | Text("py") | Text("Python") | Real(4.0) | Integer(1991) | Null
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sqlite_writer_appends_tables_to_an_existing_database() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("results.db");
+        let mut first = SqliteWriter::new(&path, None, query_record("RETURN 1 AS one"));
+        write_rows(vec![serde_json::json!({"one": 1})], &mut first)?;
+        first.finish()?;
+
+        // WHEN
+        let mut second = SqliteWriter::new(
+            &path,
+            Some("languages".to_string()),
+            query_record("MATCH (l: Language) RETURN l.name AS name"),
+        );
+        write_rows(vec![serde_json::json!({"name": "Rust"})], &mut second)?;
+        second.finish()?;
+
+        // THEN
+        let connection = Connection::open(&path)?;
+        let queries = dump_table(&connection, QUERIES_TABLE)?;
+        let languages = dump_table(&connection, "languages")?;
+        assert_snapshot!(format!("{queries}\n{languages}"), @r#"
+        queries: id | table_name | query | db_uri | started_at | duration_ms | num_rows
+        Integer(1) | Text("results_1") | Text("RETURN 1 AS one") | Text("bolt://127.0.0.1:7687") | Text("2025-01-15T10:30:00.000Z") | <duration> | Integer(1)
+        Integer(2) | Text("languages") | Text("MATCH (l: Language) RETURN l.name AS name") | Text("bolt://127.0.0.1:7687") | Text("2025-01-15T10:30:00.000Z") | <duration> | Integer(1)
+        languages: name
+        Text("Rust")
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sqlite_writer_does_nothing_when_there_are_no_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("results.db");
+        let mut writer = SqliteWriter::new(&path, None, query_record("RETURN 1"));
+
+        // WHEN
+        writer.finish()?;
+
+        // THEN
+        assert!(!path.exists());
+
+        Ok(())
+    }
+
+    #[test]
+    fn sqlite_writer_puts_rows_that_are_not_objects_in_a_value_column() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("results.db");
+        let mut writer =
+            SqliteWriter::new(&path, None, query_record("UNWIND [1, 2] AS n RETURN n"));
+
+        // WHEN
+        write_rows(
+            vec![
+                serde_json::json!(1),
+                serde_json::json!([2, 3]),
+                serde_json::json!({"name": "Rust"}),
+            ],
+            &mut writer,
+        )?;
+        writer.finish()?;
+
+        // THEN
+        let connection = Connection::open(&path)?;
+        let rows = dump_table(&connection, "results_1")?;
+        assert_snapshot!(rows, @r#"
+        results_1: value | name
+        Text("1") | Null
+        Text("[2,3]") | Null
+        Null | Text("Rust")
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn sqlite_writer_appends_to_an_existing_table() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("results.db");
+        let mut first = SqliteWriter::new(
+            &path,
+            Some("languages".to_string()),
+            query_record("RETURN 'Rust' AS name"),
+        );
+        write_rows(vec![serde_json::json!({"name": "Rust"})], &mut first)?;
+        first.finish()?;
+
+        // WHEN
+        let mut second = SqliteWriter::new(
+            &path,
+            Some("languages".to_string()),
+            query_record("RETURN 'Go' AS name, 2009 AS year"),
+        );
+        write_rows(
+            vec![serde_json::json!({"name": "Go", "year": 2009})],
+            &mut second,
+        )?;
+        second.finish()?;
+
+        // THEN
+        let connection = Connection::open(&path)?;
+        let schema: String = connection.query_row(
+            "SELECT sql FROM sqlite_master WHERE name = 'languages'",
+            [],
+            |r| r.get(0),
+        )?;
+        let languages = dump_table(&connection, "languages")?;
+        let num_queries: i64 =
+            connection.query_row(&format!("SELECT COUNT(*) FROM {QUERIES_TABLE}"), [], |r| {
+                r.get(0)
+            })?;
+        assert_snapshot!(format!("{schema}\n{languages}\nqueries: {num_queries}"), @r#"
+        CREATE TABLE "languages" ("name" TEXT, "year" INTEGER)
+        languages: name | year
+        Text("Rust") | Null
+        Text("Go") | Integer(2009)
+        queries: 2
+        "#);
+
+        Ok(())
+    }
+
+    fn query_record(query: &str) -> QueryRecord {
+        QueryRecord {
+            query: query.to_string(),
+            db_uri: "bolt://127.0.0.1:7687".to_string(),
+            started_at: DateTime::parse_from_rfc3339("2025-01-15T10:30:00Z")
+                .expect("timestamp should've been parsed")
+                .to_utc(),
+            timer: Instant::now(),
+        }
+    }
+
+    /// Rows of a table, with each value's storage class; durations are left out as they vary.
+    fn dump_table(connection: &Connection, table: &str) -> anyhow::Result<String> {
+        let mut statement = connection.prepare(&format!("SELECT * FROM {table}"))?;
+        let columns: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+        let mut lines = vec![format!("{table}: {}", columns.join(" | "))];
+        let mut rows = statement.query([])?;
+        while let Some(row) = rows.next()? {
+            let mut values = Vec::with_capacity(columns.len());
+            for (i, column) in columns.iter().enumerate() {
+                let value = match (column.as_str(), row.get::<_, SqlValue>(i)?) {
+                    ("duration_ms", _) => "<duration>".to_string(),
+                    (_, v) => format!("{v:?}"),
+                };
+                values.push(value);
+            }
+            lines.push(values.join(" | "));
+        }
+
+        Ok(lines.join("\n"))
+    }
+
+    fn sample_rows() -> Vec<Value> {
+        vec![
+            serde_json::json!({
+                "language": "Rust",
+                "year": 2010,
+                "compiled": true,
+                "creators": ["Graydon Hoare"],
+                "rating": 4.8,
+                "id": 1,
+            }),
+            serde_json::json!({
+                "language": "Python",
+                "year": 1991,
+                "compiled": false,
+                "creators": [],
+                "rating": 4,
+                "id": "py",
+                "typing": null,
+            }),
+        ]
+    }
+}
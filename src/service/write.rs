@@ -1,77 +1,338 @@
-use crate::domain::{NonEmptyResults, ResultsFormat};
+use super::{ColumnarFormat, ColumnarWriter, QueryRecord, SqliteWriter};
+use crate::domain::{ResultsFormat, RowSink, TableName, get_cell, get_headers, graph_display};
 use anyhow::Context;
 use chrono::{DateTime, Utc};
 use serde_json::Value;
 use std::fs::File;
-use std::io::Write;
+use std::io::{BufWriter, Write};
 use std::path::{Path, PathBuf};
+use std::time::Instant;
 
-pub fn write_results<P>(
-    results: &NonEmptyResults,
-    results_directory: P,
-    format: &ResultsFormat,
+/// Writes results to a file in the results directory as they arrive. The file is only created once
+/// the first row is received, so queries without results don't leave empty files behind.
+///
+/// SQLite databases are named after the reference time alone, so that the results of statements
+/// sharing it end up in tables of the same database.
+pub struct ResultsFile {
+    results_directory: PathBuf,
+    format: ResultsFormat,
     reference_time: DateTime<Utc>,
-) -> anyhow::Result<PathBuf>
-where
-    P: AsRef<Path>,
-{
-    std::fs::create_dir_all(&results_directory).with_context(|| {
-        format!(
-            "couldn't create results directory: {}",
-            results_directory.as_ref().to_string_lossy()
-        )
-    })?;
-
-    let file_name = reference_time.format("%Y-%m-%d-%H-%M-%S");
-    let results_file_path =
-        results_directory
-            .as_ref()
-            .join(format!("{}.{}", file_name, format.extension()));
-
-    let file = File::create(&results_file_path).with_context(|| {
-        format!(
-            "couldn't create results file: {}",
-            results_file_path.to_string_lossy()
-        )
-    })?;
+    name_suffix: Option<String>,
+    query: Option<String>,
+    db_uri: Option<String>,
+    table: Option<TableName>,
+    started_at: (DateTime<Utc>, Instant),
+    state: Option<(PathBuf, FileWriter)>,
+}
 
-    match format {
-        ResultsFormat::Csv => write_csv(results, file)?,
-        ResultsFormat::Json => write_json(results, file)?,
+impl ResultsFile {
+    pub fn new<P>(
+        results_directory: P,
+        format: &ResultsFormat,
+        reference_time: DateTime<Utc>,
+    ) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            results_directory: results_directory.as_ref().to_path_buf(),
+            format: format.clone(),
+            reference_time,
+            name_suffix: None,
+            query: None,
+            db_uri: None,
+            table: None,
+            started_at: (Utc::now(), Instant::now()),
+            state: None,
+        }
     }
 
-    Ok(results_file_path)
-}
+    /// Records the query the results are for, for formats that embed it (along with how long the
+    /// query took, measured from when the file was set up).
+    pub fn with_query(mut self, query: impl Into<String>) -> Self {
+        self.query = Some(query.into());
+        self
+    }
+
+    /// Records the database the query ran against, for formats that keep track of it.
+    pub fn with_db_uri(mut self, db_uri: impl Into<String>) -> Self {
+        self.db_uri = Some(db_uri.into());
+        self
+    }
+
+    /// Names the table results are written to, for formats that have tables.
+    pub fn with_table(mut self, table: Option<TableName>) -> Self {
+        self.table = table;
+        self
+    }
 
-fn write_csv<W>(results: &NonEmptyResults, writer: W) -> anyhow::Result<()>
-where
-    W: Write,
-{
-    let mut csv_writer = csv::Writer::from_writer(writer);
+    /// Appends a suffix to the file's name, so that the results of several statements run at the
+    /// same time end up in different files (or, for SQLite, in different tables).
+    pub fn with_name_suffix(mut self, suffix: impl Into<String>) -> Self {
+        self.name_suffix = Some(suffix.into());
+        self
+    }
+
+    pub fn path(&self) -> Option<&Path> {
+        self.state.as_ref().map(|(path, _)| path.as_path())
+    }
 
-    let Some(first) = results.first().as_object() else {
-        // this is alright as the result from the db is expected to be an array of objects, each
-        // having the same keys
-        anyhow::bail!("expected results to be an array of objects");
-    };
+    fn create(&self) -> anyhow::Result<(PathBuf, FileWriter)> {
+        std::fs::create_dir_all(&self.results_directory).with_context(|| {
+            format!(
+                "couldn't create results directory: {}",
+                self.results_directory.to_string_lossy()
+            )
+        })?;
 
-    let headers: Vec<&str> = first.keys().map(|s| s.as_str()).collect();
-    csv_writer.write_record(&headers)?;
+        let mut file_name = self.reference_time.format("%Y-%m-%d-%H-%M-%S").to_string();
 
-    for result in results.list() {
-        let Some(obj) = result.as_object() else {
-            anyhow::bail!("expected each result to be an object");
+        if let ResultsFormat::Sqlite = self.format {
+            let path =
+                self.results_directory
+                    .join(format!("{}.{}", file_name, self.format.extension()));
+            let table = self.table.as_ref().map(|t| match &self.name_suffix {
+                Some(suffix) => format!("{}_{suffix}", t.value()),
+                None => t.value().to_string(),
+            });
+            let record = QueryRecord {
+                query: self.query.clone().unwrap_or_default(),
+                db_uri: self.db_uri.clone().unwrap_or_default(),
+                started_at: self.started_at.0,
+                timer: self.started_at.1,
+            };
+
+            return Ok((
+                path.clone(),
+                FileWriter::Sqlite(SqliteWriter::new(path, table, record)),
+            ));
+        }
+
+        if let Some(suffix) = &self.name_suffix {
+            file_name.push('-');
+            file_name.push_str(suffix);
+        }
+        let results_file_path =
+            self.results_directory
+                .join(format!("{}.{}", file_name, self.format.extension()));
+
+        let file = File::create(&results_file_path).with_context(|| {
+            format!(
+                "couldn't create results file: {}",
+                results_file_path.to_string_lossy()
+            )
+        })?;
+
+        let mut writer = ResultsWriter::new(&self.format, BufWriter::new(file));
+        if let Some(query) = &self.query {
+            writer = writer.with_query(query, self.started_at.1);
+        }
+
+        Ok((results_file_path, FileWriter::Stream(writer)))
+    }
+}
+
+impl RowSink for ResultsFile {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        let (_, writer) = match &mut self.state {
+            Some(state) => state,
+            None => {
+                let state = self.create()?;
+                self.state.insert(state)
+            }
         };
 
-        let row: Vec<String> = headers
-            .iter()
-            .map(|&header| obj.get(header).map(value_to_csv_field).unwrap_or_default())
-            .collect();
+        match writer {
+            FileWriter::Stream(w) => w.write_row(row),
+            FileWriter::Sqlite(w) => w.write_row(row),
+        }
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        match &mut self.state {
+            Some((_, FileWriter::Stream(w))) => w.finish(),
+            Some((_, FileWriter::Sqlite(w))) => w.finish(),
+            None => Ok(()),
+        }
+    }
+}
+
+enum FileWriter {
+    Stream(ResultsWriter<BufWriter<File>>),
+    Sqlite(SqliteWriter),
+}
+
+/// Serializes rows in a results format as they arrive.
+pub enum ResultsWriter<W: Write> {
+    Csv(Box<CsvWriter<W>>),
+    Json(JsonWriter<W>),
+    Ndjson(NdjsonWriter<W>),
+    Yaml(YamlWriter<W>),
+    Markdown(MarkdownWriter<W>),
+    Html(HtmlWriter<W>),
+    Columnar(ColumnarWriter<W>),
+}
+
+impl<W: Write> ResultsWriter<W> {
+    pub fn new(format: &ResultsFormat, writer: W) -> Self {
+        match format {
+            ResultsFormat::Csv => Self::Csv(Box::new(CsvWriter::new(writer))),
+            ResultsFormat::Json => Self::Json(JsonWriter::new(writer)),
+            ResultsFormat::Ndjson => Self::Ndjson(NdjsonWriter::new(writer)),
+            ResultsFormat::Yaml => Self::Yaml(YamlWriter::new(writer)),
+            ResultsFormat::Markdown => Self::Markdown(MarkdownWriter::new(writer)),
+            ResultsFormat::Tsv => Self::Csv(Box::new(CsvWriter::tsv(writer))),
+            ResultsFormat::Html => Self::Html(HtmlWriter::new(writer)),
+            ResultsFormat::Parquet => {
+                Self::Columnar(ColumnarWriter::new(ColumnarFormat::Parquet, writer))
+            }
+            ResultsFormat::Arrow => {
+                Self::Columnar(ColumnarWriter::new(ColumnarFormat::Arrow, writer))
+            }
+            // SQLite databases can't be streamed to a writer (results files write them directly),
+            // so JSON is used instead
+            ResultsFormat::Sqlite => Self::Json(JsonWriter::new(writer)),
+        }
+    }
+
+    /// Embeds the query, and how long it took since it was started, in formats that support it;
+    /// other formats are left untouched.
+    pub fn with_query(self, query: &str, started_at: Instant) -> Self {
+        match self {
+            Self::Html(w) => Self::Html(w.with_query(query, started_at)),
+            other => other,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        match self {
+            Self::Csv(w) => w.inner.get_ref(),
+            Self::Json(w) => &w.inner,
+            Self::Ndjson(w) => &w.inner,
+            Self::Yaml(w) => &w.inner,
+            Self::Markdown(w) => &w.inner,
+            Self::Html(w) => &w.inner,
+            Self::Columnar(w) => &w.inner,
+        }
+    }
+}
+
+impl<W: Write> RowSink for ResultsWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        match self {
+            Self::Csv(w) => w.write_row(row),
+            Self::Json(w) => w.write_row(row),
+            Self::Ndjson(w) => w.write_row(row),
+            Self::Yaml(w) => w.write_row(row),
+            Self::Markdown(w) => w.write_row(row),
+            Self::Html(w) => w.write_row(row),
+            Self::Columnar(w) => w.write_row(row),
+        }
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        match self {
+            Self::Csv(w) => w.finish(),
+            Self::Json(w) => w.finish(),
+            Self::Ndjson(w) => w.finish(),
+            Self::Yaml(w) => w.finish(),
+            Self::Markdown(w) => w.finish(),
+            Self::Html(w) => w.finish(),
+            Self::Columnar(w) => w.finish(),
+        }
+    }
+}
+
+/// Number of rows that are buffered before the headers of a CSV, Markdown, or HTML table are
+/// decided.
+const LOOK_AHEAD_ROWS: usize = 100;
+
+/// Headers are the keys of the first few rows (with a "value" column for rows that aren't objects),
+/// which are buffered until then; keys that only later rows have are left out.
+pub struct CsvWriter<W: Write> {
+    inner: csv::Writer<W>,
+    look_ahead: usize,
+    pending: Vec<Value>,
+    headers: Option<Vec<String>>,
+}
+
+impl<W: Write> CsvWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self::from_writer(csv::Writer::from_writer(writer))
+    }
+
+    /// Separates fields with tabs instead of commas.
+    pub fn tsv(writer: W) -> Self {
+        Self::from_writer(
+            csv::WriterBuilder::new()
+                .delimiter(b'\t')
+                .from_writer(writer),
+        )
+    }
+
+    fn from_writer(inner: csv::Writer<W>) -> Self {
+        Self {
+            inner,
+            look_ahead: LOOK_AHEAD_ROWS,
+            pending: vec![],
+            headers: None,
+        }
+    }
 
-        csv_writer.write_record(&row)?;
+    fn write_pending(&mut self) -> anyhow::Result<()> {
+        let headers = get_headers(&self.pending);
+        self.inner.write_record(&headers)?;
+
+        for row in std::mem::take(&mut self.pending) {
+            write_csv_record(&mut self.inner, &headers, &row)?;
+        }
+        self.headers = Some(headers);
+
+        Ok(())
     }
+}
+
+impl<W: Write> RowSink for CsvWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        match &self.headers {
+            Some(headers) => write_csv_record(&mut self.inner, headers, &row)?,
+            None => {
+                self.pending.push(row);
+                if self.pending.len() >= self.look_ahead {
+                    self.write_pending()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if self.headers.is_none() && !self.pending.is_empty() {
+            self.write_pending()?;
+        }
+
+        self.inner.flush()?;
+        Ok(())
+    }
+}
+
+fn write_csv_record<W: Write>(
+    writer: &mut csv::Writer<W>,
+    headers: &[String],
+    row: &Value,
+) -> anyhow::Result<()> {
+    let record: Vec<String> = headers
+        .iter()
+        .map(|header| {
+            get_cell(row, header)
+                .map(value_to_csv_field)
+                .unwrap_or_default()
+        })
+        .collect();
+
+    writer.write_record(&record)?;
 
-    csv_writer.flush()?;
     Ok(())
 }
 
@@ -81,28 +342,418 @@ fn value_to_csv_field(value: &Value) -> String {
         Value::Bool(b) => b.to_string(),
         Value::Number(n) => n.to_string(),
         Value::String(s) => s.clone(),
-        _ => serde_json::to_string(value).unwrap_or_default(),
+        _ => {
+            graph_display(value).unwrap_or_else(|| serde_json::to_string(value).unwrap_or_default())
+        }
+    }
+}
+
+/// Writes a pretty-printed JSON array, one element at a time.
+pub struct JsonWriter<W: Write> {
+    inner: W,
+    num_rows: u64,
+}
+
+impl<W: Write> JsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: writer,
+            num_rows: 0,
+        }
+    }
+}
+
+impl<W: Write> RowSink for JsonWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        let json_string =
+            serde_json::to_string_pretty(&row).context("couldn't serialize results to JSON")?;
+
+        let separator = if self.num_rows == 0 { "[\n" } else { ",\n" };
+        let mut contents = String::from(separator);
+        for (i, line) in json_string.lines().enumerate() {
+            if i > 0 {
+                contents.push('\n');
+            }
+            contents.push_str("  ");
+            contents.push_str(line);
+        }
+
+        self.inner
+            .write_all(contents.as_bytes())
+            .context("couldn't write bytes to file")?;
+        self.num_rows += 1;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        let closing = if self.num_rows == 0 { "[]\n" } else { "\n]\n" };
+        self.inner
+            .write_all(closing.as_bytes())
+            .context("couldn't write bytes to file")?;
+        self.inner.flush().context("couldn't flush results")?;
+
+        Ok(())
+    }
+}
+
+/// Writes a compact JSON document per row, one per line.
+pub struct NdjsonWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> NdjsonWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { inner: writer }
+    }
+}
+
+impl<W: Write> RowSink for NdjsonWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        serde_json::to_writer(&mut self.inner, &row)
+            .context("couldn't serialize results to JSON")?;
+        self.inner
+            .write_all(b"\n")
+            .context("couldn't write bytes to file")?;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        self.inner.flush().context("couldn't flush results")?;
+
+        Ok(())
+    }
+}
+
+/// Writes a YAML sequence, one element at a time.
+pub struct YamlWriter<W: Write> {
+    inner: W,
+    num_rows: u64,
+}
+
+impl<W: Write> YamlWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: writer,
+            num_rows: 0,
+        }
+    }
+}
+
+impl<W: Write> RowSink for YamlWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        let yaml_string =
+            serde_yaml_ng::to_string(&row).context("couldn't serialize results to YAML")?;
+
+        let mut contents = String::new();
+        for (i, line) in yaml_string.lines().enumerate() {
+            contents.push_str(if i == 0 { "- " } else { "  " });
+            contents.push_str(line);
+            contents.push('\n');
+        }
+
+        self.inner
+            .write_all(contents.as_bytes())
+            .context("couldn't write bytes to file")?;
+        self.num_rows += 1;
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if self.num_rows == 0 {
+            self.inner
+                .write_all(b"[]\n")
+                .context("couldn't write bytes to file")?;
+        }
+        self.inner.flush().context("couldn't flush results")?;
+
+        Ok(())
+    }
+}
+
+/// Writes a Markdown table; like CSV, headers are taken from the first few rows.
+pub struct MarkdownWriter<W: Write> {
+    inner: W,
+    look_ahead: usize,
+    pending: Vec<Value>,
+    headers: Option<Vec<String>>,
+}
+
+impl<W: Write> MarkdownWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: writer,
+            look_ahead: LOOK_AHEAD_ROWS,
+            pending: vec![],
+            headers: None,
+        }
+    }
+
+    fn write_pending(&mut self) -> anyhow::Result<()> {
+        let headers = get_headers(&self.pending);
+        let header_cells: Vec<String> = headers.iter().map(|h| markdown_cell(h)).collect();
+        let separator = vec!["---"; headers.len()];
+        writeln!(
+            self.inner,
+            "| {} |\n| {} |",
+            header_cells.join(" | "),
+            separator.join(" | ")
+        )
+        .context("couldn't write bytes to file")?;
+
+        for row in std::mem::take(&mut self.pending) {
+            write_markdown_row(&mut self.inner, &headers, &row)?;
+        }
+        self.headers = Some(headers);
+
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for MarkdownWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        match &self.headers {
+            Some(headers) => write_markdown_row(&mut self.inner, headers, &row)?,
+            None => {
+                self.pending.push(row);
+                if self.pending.len() >= self.look_ahead {
+                    self.write_pending()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if self.headers.is_none() && !self.pending.is_empty() {
+            self.write_pending()?;
+        }
+
+        self.inner.flush().context("couldn't flush results")?;
+
+        Ok(())
+    }
+}
+
+fn write_markdown_row<W: Write>(
+    writer: &mut W,
+    headers: &[String],
+    row: &Value,
+) -> anyhow::Result<()> {
+    let cells: Vec<String> = headers
+        .iter()
+        .map(|header| {
+            get_cell(row, header)
+                .map(|v| markdown_cell(&value_to_csv_field(v)))
+                .unwrap_or_default()
+        })
+        .collect();
+    writeln!(writer, "| {} |", cells.join(" | ")).context("couldn't write bytes to file")?;
+
+    Ok(())
+}
+
+/// Pipes would end the cell, and line breaks the row.
+fn markdown_cell(contents: &str) -> String {
+    contents
+        .replace('|', "\\|")
+        .replace("\r\n", "<br>")
+        .replace('\n', "<br>")
+}
+
+const HTML_STYLE: &str = "body { font-family: sans-serif; margin: 2em; color: #222; }
+pre { background: #f5f5f5; padding: 1em; overflow-x: auto; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ddd; padding: 0.4em 0.8em; text-align: left; vertical-align: top; }
+th { background: #f0f0f0; cursor: pointer; user-select: none; }
+th[data-order=\"asc\"]::after { content: \" \\25B2\"; }
+th[data-order=\"desc\"]::after { content: \" \\25BC\"; }
+tbody tr:nth-child(even) { background: #fafafa; }
+footer { margin-top: 1em; color: #666; }";
+
+// sorts rows by the clicked column, numerically if every cell in it is a number
+const HTML_SCRIPT: &str = "document.querySelectorAll(\"th\").forEach((th, column) => {
+  th.addEventListener(\"click\", () => {
+    const tbody = th.closest(\"table\").querySelector(\"tbody\");
+    const rows = Array.from(tbody.rows);
+    const order = th.dataset.order === \"asc\" ? \"desc\" : \"asc\";
+    const cells = rows.map((row) => row.cells[column].textContent);
+    const numeric = cells.every((c) => c === \"\" || !isNaN(Number(c)));
+    rows.sort((a, b) => {
+      const x = a.cells[column].textContent;
+      const y = b.cells[column].textContent;
+      const result = numeric ? Number(x) - Number(y) : x.localeCompare(y);
+      return order === \"asc\" ? result : -result;
+    });
+    th.closest(\"tr\").querySelectorAll(\"th\").forEach((h) => delete h.dataset.order);
+    th.dataset.order = order;
+    rows.forEach((row) => tbody.appendChild(row));
+  });
+});";
+
+/// Writes a self-contained HTML page with the results in a table that can be sorted by clicking
+/// on its headers; like CSV, headers are taken from the first few rows.
+pub struct HtmlWriter<W: Write> {
+    inner: W,
+    query: Option<String>,
+    started_at: Option<Instant>,
+    look_ahead: usize,
+    pending: Vec<Value>,
+    headers: Option<Vec<String>>,
+    num_rows: u64,
+}
+
+impl<W: Write> HtmlWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            inner: writer,
+            query: None,
+            started_at: None,
+            look_ahead: LOOK_AHEAD_ROWS,
+            pending: vec![],
+            headers: None,
+            num_rows: 0,
+        }
+    }
+
+    /// Shows the query above the results, and how long it took below them.
+    pub fn with_query(mut self, query: &str, started_at: Instant) -> Self {
+        self.query = Some(query.to_string());
+        self.started_at = Some(started_at);
+        self
+    }
+
+    fn write_start(&mut self, headers: &[String]) -> anyhow::Result<()> {
+        let mut contents = format!(
+            "<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>grafq results</title>
+<style>
+{HTML_STYLE}
+</style>
+</head>
+<body>
+"
+        );
+        if let Some(query) = &self.query {
+            contents.push_str(&format!("<pre>{}</pre>\n", html_escape(query)));
+        }
+        contents.push_str("<table>\n<thead>\n<tr>");
+        for header in headers {
+            contents.push_str(&format!("<th>{}</th>", html_escape(header)));
+        }
+        contents.push_str("</tr>\n</thead>\n<tbody>\n");
+
+        self.inner
+            .write_all(contents.as_bytes())
+            .context("couldn't write bytes to file")?;
+
+        Ok(())
+    }
+
+    fn write_pending(&mut self) -> anyhow::Result<()> {
+        let headers = get_headers(&self.pending);
+        self.write_start(&headers)?;
+
+        for row in std::mem::take(&mut self.pending) {
+            write_html_row(&mut self.inner, &headers, &row)?;
+        }
+        self.headers = Some(headers);
+
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for HtmlWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        self.num_rows += 1;
+        match &self.headers {
+            Some(headers) => write_html_row(&mut self.inner, headers, &row)?,
+            None => {
+                self.pending.push(row);
+                if self.pending.len() >= self.look_ahead {
+                    self.write_pending()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if self.headers.is_none() {
+            self.write_pending()?;
+        }
+
+        let mut footer = match self.num_rows {
+            1 => "1 row".to_string(),
+            n => format!("{n} rows"),
+        };
+        if let Some(started_at) = self.started_at {
+            footer.push_str(&format!(", took {}ms", started_at.elapsed().as_millis()));
+        }
+
+        let contents = format!(
+            "</tbody>
+</table>
+<footer>{footer}</footer>
+<script>
+{HTML_SCRIPT}
+</script>
+</body>
+</html>
+"
+        );
+        self.inner
+            .write_all(contents.as_bytes())
+            .context("couldn't write bytes to file")?;
+        self.inner.flush().context("couldn't flush results")?;
+
+        Ok(())
     }
 }
 
-fn write_json<W>(results: &NonEmptyResults, mut writer: W) -> anyhow::Result<()>
-where
-    W: Write,
-{
-    let json_string = serde_json::to_string_pretty(results.list())
-        .context("couldn't serialize results to JSON")?;
+fn write_html_row<W: Write>(writer: &mut W, headers: &[String], row: &Value) -> anyhow::Result<()> {
+    let mut contents = String::from("<tr>");
+    for header in headers {
+        let cell = get_cell(row, header)
+            .map(value_to_csv_field)
+            .unwrap_or_default();
+        contents.push_str(&format!("<td>{}</td>", html_escape(&cell)));
+    }
+    contents.push_str("</tr>\n");
+
     writer
-        .write_all(json_string.as_bytes())
+        .write_all(contents.as_bytes())
         .context("couldn't write bytes to file")?;
 
     Ok(())
 }
 
+fn html_escape(contents: &str) -> String {
+    contents
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&#39;")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{NonEmptyResults, write_rows};
     use insta::assert_snapshot;
 
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
     #[test]
     fn write_csv_writes_correct_headers_and_rows() -> anyhow::Result<()> {
         // GIVEN
@@ -151,6 +802,387 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn write_json_writes_empty_array_when_there_are_no_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = JsonWriter::new(&mut buffer);
+
+        // WHEN
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @"[]");
+
+        Ok(())
+    }
+
+    #[test]
+    fn ndjson_writer_writes_a_row_per_line() -> anyhow::Result<()> {
+        // GIVEN
+        let results = results_sample_two();
+        let mut buffer = Vec::new();
+        let mut writer = NdjsonWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(results.list().to_vec(), &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @r#"
+        {"compiled":true,"creators":["Graydon Hoare"],"features":{"garbage_collection":false,"static_typing":true},"language":"Rust","year":2010}
+        {"compiled":true,"creators":["Robert Griesemer","Rob Pike","Ken Thompson",null],"features":{"garbage_collection":true,"static_typing":true},"language":"Go","year":2009}
+        {"compiled":false,"creator":null,"features":{"garbage_collection":true,"static_typing":null},"language":"Python","year":1991}
+        {"compiled":true,"creators":["Louis Pilfold"],"features":null,"language":"Gleam","year":2016}
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn tsv_writer_separates_fields_with_tabs() -> anyhow::Result<()> {
+        // GIVEN
+        let results = results_sample_one();
+        let mut buffer = Vec::new();
+        let mut writer = CsvWriter::tsv(&mut buffer);
+
+        // WHEN
+        write_rows(results.list().to_vec(), &mut writer)?;
+        writer.finish()?;
+        drop(writer);
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @"
+        creator	language	year
+        Graydon Hoare	Rust	2010
+        Guido van Rossum	Python	1991
+        Rob Pike	Go	2009
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn markdown_writer_escapes_pipes_and_line_breaks() -> anyhow::Result<()> {
+        // GIVEN
+        let rows = vec![
+            serde_json::json!({"name": "a|b", "notes": "first line\nsecond line", "year": 2010}),
+            serde_json::json!({"name": "c", "notes": null, "year": 2009}),
+        ];
+        let mut buffer = Vec::new();
+        let mut writer = MarkdownWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @r"
+        | name | notes | year |
+        | --- | --- | --- |
+        | a\|b | first line<br>second line | 2010 |
+        | c |  | 2009 |
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_yaml_works_as_expected() -> anyhow::Result<()> {
+        // GIVEN
+        let results = results_sample_two();
+        let mut buffer = Vec::new();
+        let mut writer = YamlWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(results.list().to_vec(), &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_yaml_writes_empty_sequence_when_there_are_no_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = YamlWriter::new(&mut buffer);
+
+        // WHEN
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @"[]");
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_html_works_as_expected() -> anyhow::Result<()> {
+        // GIVEN
+        let results = results_sample_two();
+        let mut buffer = Vec::new();
+        let mut writer = HtmlWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(results.list().to_vec(), &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_html_embeds_escaped_query_and_timing() -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = HtmlWriter::new(&mut buffer).with_query(
+            "MATCH (n) WHERE n.name <> 'Bob' RETURN n.name AS name",
+            Instant::now(),
+        );
+
+        // WHEN
+        writer.write_row(serde_json::json!({"name": "<Alice & Eve>"}))?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert!(result.contains(
+            "<pre>MATCH (n) WHERE n.name &lt;&gt; &#39;Bob&#39; RETURN n.name AS name</pre>"
+        ));
+        assert!(result.contains("<td>&lt;Alice &amp; Eve&gt;</td>"));
+        assert!(result.contains("<footer>1 row, took "));
+
+        Ok(())
+    }
+
+    #[test]
+    fn results_file_is_only_created_once_a_row_arrives() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let mut results_file = ResultsFile::new(&temp_dir, &ResultsFormat::Csv, Utc::now());
+
+        // WHEN
+        results_file.finish()?;
+        let path_before_rows = results_file.path().map(|p| p.to_path_buf());
+        results_file.write_row(serde_json::json!({"language": "Rust"}))?;
+        results_file.finish()?;
+
+        // THEN
+        assert!(path_before_rows.is_none());
+        let path = results_file
+            .path()
+            .expect("results file should've been created");
+        let result = std::fs::read_to_string(path)?;
+        assert_snapshot!(result, @r"
+        language
+        Rust
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn results_file_name_includes_the_suffix() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let reference_time = DateTime::parse_from_rfc3339("2025-01-15T10:30:00Z")?.to_utc();
+        let mut results_file =
+            ResultsFile::new(&temp_dir, &ResultsFormat::Json, reference_time).with_name_suffix("2");
+
+        // WHEN
+        results_file.write_row(serde_json::json!({"language": "Rust"}))?;
+        results_file.finish()?;
+
+        // THEN
+        let file_name = results_file
+            .path()
+            .and_then(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string());
+        assert_eq!(file_name.as_deref(), Some("2025-01-15-10-30-00-2.json"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn sqlite_results_files_sharing_a_reference_time_use_the_same_database() -> anyhow::Result<()> {
+        // GIVEN
+        let temp_dir = tempfile::tempdir()?;
+        let reference_time = DateTime::parse_from_rfc3339("2025-01-15T10:30:00Z")?.to_utc();
+        let mut paths = Vec::new();
+
+        // WHEN
+        for (query, table) in [("RETURN 1 AS one", None), ("RETURN 2 AS two", Some("twos"))] {
+            let mut results_file =
+                ResultsFile::new(&temp_dir, &ResultsFormat::Sqlite, reference_time)
+                    .with_query(query)
+                    .with_table(
+                        table
+                            .map(|t| t.parse())
+                            .transpose()
+                            .map_err(anyhow::Error::msg)?,
+                    );
+            results_file.write_row(serde_json::json!({"n": 1}))?;
+            results_file.finish()?;
+            paths.push(results_file.path().map(|p| p.to_path_buf()));
+        }
+
+        // THEN
+        let file_names: Vec<String> = paths
+            .iter()
+            .flatten()
+            .filter_map(|p| p.file_name())
+            .map(|n| n.to_string_lossy().to_string())
+            .collect();
+        assert_eq!(
+            file_names,
+            ["2025-01-15-10-30-00.db", "2025-01-15-10-30-00.db"]
+        );
+        let connection = rusqlite::Connection::open(temp_dir.path().join(&file_names[0]))?;
+        let tables: Vec<String> = connection
+            .prepare("SELECT table_name FROM queries ORDER BY id")?
+            .query_map([], |r| r.get(0))?
+            .collect::<Result<_, _>>()?;
+        assert_eq!(tables, ["results_1", "twos"]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_csv_writes_union_of_keys_and_non_object_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let rows = vec![
+            serde_json::json!({"language": "Rust"}),
+            serde_json::json!("Zig"),
+            serde_json::json!({"language": "Go", "year": 2009}),
+            serde_json::json!(["Python", 1991]),
+        ];
+        let mut buffer = Vec::new();
+        let mut writer = CsvWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+        drop(writer);
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @r#"
+        language,value,year
+        Rust,,
+        ,Zig,
+        Go,,2009
+        ,"[""Python"",1991]",
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_markdown_writes_union_of_keys_and_non_object_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let rows = vec![
+            serde_json::json!({"language": "Rust"}),
+            serde_json::json!("Zig"),
+            serde_json::json!({"language": "Go", "year": 2009}),
+        ];
+        let mut buffer = Vec::new();
+        let mut writer = MarkdownWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @"
+        | language | value | year |
+        | --- | --- | --- |
+        | Rust |  |  |
+        |  | Zig |  |
+        | Go |  | 2009 |
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn write_html_writes_union_of_keys_and_non_object_rows() -> anyhow::Result<()> {
+        // GIVEN
+        let rows = vec![
+            serde_json::json!({"language": "Rust"}),
+            serde_json::json!("Zig"),
+            serde_json::json!({"language": "Go", "year": 2009}),
+        ];
+        let mut buffer = Vec::new();
+        let mut writer = HtmlWriter::new(&mut buffer);
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert!(result.contains("<tr><th>language</th><th>value</th><th>year</th></tr>"));
+        assert!(result.contains("<tr><td></td><td>Zig</td><td></td></tr>"));
+        assert!(result.contains("<tr><td>Go</td><td></td><td>2009</td></tr>"));
+        assert!(result.contains("<footer>3 rows</footer>"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn csv_writer_leaves_out_keys_only_seen_past_the_look_ahead() -> anyhow::Result<()> {
+        // GIVEN
+        let rows = vec![
+            serde_json::json!({"language": "Go"}),
+            serde_json::json!({"language": "Rust", "year": 2010}),
+            serde_json::json!({"language": "Python", "creator": "Guido van Rossum"}),
+        ];
+        let mut buffer = Vec::new();
+        let mut writer = CsvWriter::new(&mut buffer);
+        writer.look_ahead = 2;
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+        drop(writer);
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @"
+        language,year
+        Go,
+        Rust,2010
+        Python,
+        ");
+
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(results: &NonEmptyResults, writer: W) -> anyhow::Result<()> {
+        let mut csv_writer = CsvWriter::new(writer);
+        write_rows(results.list().to_vec(), &mut csv_writer)?;
+        csv_writer.finish()
+    }
+
+    fn write_json<W: Write>(results: &NonEmptyResults, writer: W) -> anyhow::Result<()> {
+        let mut json_writer = JsonWriter::new(writer);
+        write_rows(results.list().to_vec(), &mut json_writer)?;
+        json_writer.finish()
+    }
+
     fn results_sample_one() -> NonEmptyResults {
         let results = vec![
             serde_json::json!({"language": "Rust", "creator": "Graydon Hoare", "year": 2010}),
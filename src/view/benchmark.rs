@@ -0,0 +1,329 @@
+use crate::domain::{BaselineComparison, BenchmarkRun, BenchmarkStats, ParamSetStats, ParamSets};
+use crate::service::BenchmarkEvent;
+use colored::Colorize;
+use std::time::Duration;
+
+const HISTOGRAM_MAX_BUCKETS: usize = 10;
+const HISTOGRAM_MAX_BAR_WIDTH: usize = 40;
+
+/// Formats a latency in milliseconds, with microsecond precision.
+pub fn format_latency(latency: Duration) -> String {
+    format!("{:.3}ms", latency.as_secs_f64() * 1000.0)
+}
+
+/// Renders latency statistics as aligned "name: value" lines.
+pub fn get_latency_statistics(stats: &BenchmarkStats) -> String {
+    [
+        ("min", format_latency(stats.min)),
+        ("max", format_latency(stats.max)),
+        ("mean", format_latency(stats.mean)),
+        ("median", format_latency(stats.median)),
+        ("p90", format_latency(stats.p90)),
+        ("p95", format_latency(stats.p95)),
+        ("p99", format_latency(stats.p99)),
+        ("std dev", format_latency(stats.std_dev)),
+        ("cv", format!("{:.2}%", stats.cv * 100.0)),
+    ]
+    .into_iter()
+    .map(|(name, value)| format!("{:<14}{}", format!("{name}:"), value.cyan()))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Prints the progress of a benchmark; retries go to stderr.
+pub fn print_benchmark_event(event: &BenchmarkEvent) {
+    match event {
+        BenchmarkEvent::WarmUpStarted(num_runs) => println!(
+            "{}",
+            format!("Warming up ({num_runs} runs) ...").yellow().bold()
+        ),
+        BenchmarkEvent::BenchmarkStarted(num_runs) => println!(
+            "{}",
+            format!("Benchmarking ({num_runs} runs) ...")
+                .yellow()
+                .bold()
+        ),
+        BenchmarkEvent::WarmUpRunFinished(run) | BenchmarkEvent::RunFinished(run) => {
+            println!("{}", get_benchmark_run(run))
+        }
+        BenchmarkEvent::WarmUpFinished => println!(),
+        BenchmarkEvent::Retrying(retry) => eprintln!("{}", retry.to_string().yellow()),
+    }
+}
+
+fn get_benchmark_run(run: &BenchmarkRun) -> String {
+    let param_set = run
+        .param_set
+        .map(|p| format!(" {}", format!("(parameter set {p})").bright_black()))
+        .unwrap_or_default();
+    let retries = match run.retries {
+        0 => String::new(),
+        1 => format!(" {}", "(1 retry)".yellow()),
+        n => format!(" {}", format!("({n} retries)").yellow()),
+    };
+
+    format!(
+        "run {:03}:      {}{}{}",
+        run.run,
+        format_latency(run.latency).cyan(),
+        param_set,
+        retries
+    )
+}
+
+/// Renders the statistics of a benchmark's runs, followed by their distribution, and, if the runs
+/// went through parameter sets from a file, the latency of each parameter set.
+pub fn get_benchmark_summary(
+    statistics: &BenchmarkStats,
+    runs: &[BenchmarkRun],
+    param_sets: &ParamSets,
+) -> String {
+    let times: Vec<Duration> = runs.iter().map(|r| r.latency).collect();
+    let num_retries: u32 = runs.iter().map(|r| u32::from(r.retries)).sum();
+
+    let mut summary = format!(
+        "
+{}
+{}
+retries:      {}
+
+{}
+{}
+",
+        "Statistics:".yellow().bold(),
+        get_latency_statistics(statistics),
+        num_retries.to_string().cyan(),
+        "Distribution:".yellow().bold(),
+        get_histogram(&times),
+    );
+
+    if param_sets.is_from_file() {
+        summary.push_str(&format!(
+            "
+{}
+{}
+",
+            "Latency per parameter set (slowest first):".yellow().bold(),
+            get_param_set_statistics(&ParamSetStats::from_runs(runs), param_sets),
+        ));
+    }
+
+    summary
+}
+
+/// Renders how the latency of a benchmark compares with its baseline; changes are only colored
+/// when the difference between the two is significant.
+pub fn get_baseline_comparison(comparison: &BaselineComparison) -> String {
+    let mut lines = vec![format!(
+        "{:<14}{:>12}  {:>12}  {:>9}",
+        "", "baseline", "current", "change"
+    )];
+
+    for metric in &comparison.metrics {
+        let change = metric.change();
+        let change_text = format!("{:>+8.2}%", change * 100.0);
+        let change_text = match (comparison.is_significant(), change) {
+            (true, c) if c > 0.0 => change_text.red(),
+            (true, c) if c < 0.0 => change_text.green(),
+            _ => change_text.normal(),
+        };
+
+        lines.push(format!(
+            "{:<14}{:>12}  {:>12}  {}",
+            format!("{}:", metric.name),
+            format_latency(metric.baseline),
+            format_latency(metric.current),
+            change_text,
+        ));
+    }
+
+    lines.push(format!(
+        "{:<14}{} ({})",
+        "p-value:",
+        format!("{:.4}", comparison.p_value).cyan(),
+        if comparison.is_significant() {
+            "the difference is significant at the 5% level"
+        } else {
+            "the difference is not significant at the 5% level"
+        }
+    ));
+
+    lines.join("\n")
+}
+
+/// Renders the latency of the runs of each parameter set, along with the parameters in it.
+pub fn get_param_set_statistics(stats: &[ParamSetStats], param_sets: &ParamSets) -> String {
+    let mut lines = vec![format!(
+        "{:>5}  {:>5}  {:>12}  {:>12}  {:>12}  params",
+        "set", "runs", "median", "p95", "max"
+    )];
+
+    for s in stats {
+        let params = param_sets
+            .get(s.param_set - 1)
+            .map(|p| serde_json::to_string(p.as_map()).unwrap_or_default())
+            .unwrap_or_default();
+
+        lines.push(format!(
+            "{:>5}  {:>5}  {:>12}  {:>12}  {:>12}  {}",
+            s.param_set,
+            s.statistics.num_runs,
+            format_latency(s.statistics.median),
+            format_latency(s.statistics.p95),
+            format_latency(s.statistics.max),
+            params,
+        ));
+    }
+
+    lines.join("\n")
+}
+
+/// Renders the distribution of benchmark runs as an ASCII histogram, with equally wide buckets
+/// between the fastest and the slowest run.
+pub fn get_histogram(times: &[Duration]) -> String {
+    let (Some(min), Some(max)) = (times.iter().min(), times.iter().max()) else {
+        return String::new();
+    };
+
+    let num_buckets = if min == max {
+        1
+    } else {
+        times.len().min(HISTOGRAM_MAX_BUCKETS)
+    };
+    let bucket_width = (*max - *min).as_secs_f64() / num_buckets as f64;
+
+    let mut counts = vec![0usize; num_buckets];
+    for time in times {
+        let bucket = if bucket_width > 0.0 {
+            ((*time - *min).as_secs_f64() / bucket_width) as usize
+        } else {
+            0
+        };
+        counts[bucket.min(num_buckets - 1)] += 1;
+    }
+
+    let bounds: Vec<(String, String)> = (0..num_buckets)
+        .map(|i| {
+            let lower = min.as_secs_f64() + bucket_width * i as f64;
+            (
+                format_latency(Duration::from_secs_f64(lower)),
+                format_latency(Duration::from_secs_f64(lower + bucket_width)),
+            )
+        })
+        .collect();
+    let lower_width = bounds
+        .iter()
+        .map(|(l, _)| l.len())
+        .max()
+        .unwrap_or_default();
+    let upper_width = bounds
+        .iter()
+        .map(|(_, u)| u.len())
+        .max()
+        .unwrap_or_default();
+    let max_count = counts.iter().max().copied().unwrap_or_default().max(1);
+
+    let mut lines = Vec::with_capacity(num_buckets);
+    for ((lower, upper), count) in bounds.iter().zip(counts) {
+        let bar_width = (count * HISTOGRAM_MAX_BAR_WIDTH).div_ceil(max_count);
+        let bar = match bar_width {
+            0 => String::new(),
+            n => format!("{} ", "#".repeat(n)),
+        };
+        lines.push(format!(
+            "{lower:>lower_width$} - {upper:>upper_width$} | {bar}{count}"
+        ));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::ParamSetOrder;
+    use insta::assert_snapshot;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn format_latency_uses_microsecond_precision() {
+        // GIVEN
+        // WHEN
+        let result = format_latency(Duration::from_micros(12_345));
+
+        // THEN
+        assert_eq!(result, "12.345ms");
+    }
+
+    #[test]
+    fn get_param_set_statistics_works() -> anyhow::Result<()> {
+        // GIVEN
+        let param_sets = ParamSets::from_jsonl(
+            "{\"id\": 1}\n{\"id\": 2, \"name\": \"Bob\"}\n",
+            ParamSetOrder::Cycle,
+        )?;
+        let runs: Vec<BenchmarkRun> = [(1, 1200), (2, 45_500), (1, 1800), (2, 39_000)]
+            .into_iter()
+            .enumerate()
+            .map(|(i, (param_set, micros))| BenchmarkRun {
+                run: i as u16 + 1,
+                latency: Duration::from_micros(micros),
+                retries: 0,
+                param_set: Some(param_set),
+            })
+            .collect();
+
+        // WHEN
+        let result = get_param_set_statistics(&ParamSetStats::from_runs(&runs), &param_sets);
+
+        // THEN
+        assert_snapshot!(result, @r#"
+        set   runs        median           p95           max  params
+          2      2      42.250ms      45.175ms      45.500ms  {"id":2,"name":"Bob"}
+          1      2       1.500ms       1.770ms       1.800ms  {"id":1}
+        "#);
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_histogram_works() {
+        // GIVEN
+        let times: Vec<Duration> = [12, 15, 11, 30, 14, 13, 16, 12, 14, 100, 13, 12]
+            .into_iter()
+            .map(Duration::from_millis)
+            .collect();
+
+        // WHEN
+        let result = get_histogram(&times);
+
+        // THEN
+        assert_snapshot!(result, @"
+        11.000ms -  19.900ms | ######################################## 10
+        19.900ms -  28.800ms | 0
+        28.800ms -  37.700ms | #### 1
+        37.700ms -  46.600ms | 0
+        46.600ms -  55.500ms | 0
+        55.500ms -  64.400ms | 0
+        64.400ms -  73.300ms | 0
+        73.300ms -  82.200ms | 0
+        82.200ms -  91.100ms | 0
+        91.100ms - 100.000ms | #### 1
+        ");
+    }
+
+    #[test]
+    fn get_histogram_works_when_all_runs_take_the_same_time() {
+        // GIVEN
+        let times = vec![Duration::from_millis(5); 3];
+
+        // WHEN
+        let result = get_histogram(&times);
+
+        // THEN
+        assert_snapshot!(result, @"5.000ms - 5.000ms | ######################################## 3");
+    }
+}
@@ -1,12 +1,25 @@
-use super::{QueryFilenameCompleter, get_results};
+use super::{
+    ConsoleHelper, OutputWriter, get_benchmark_summary, get_plan_tree, get_schema_tables,
+    is_console_command, print_benchmark_event,
+};
 use crate::config::DEFAULT_RESULTS_DIR;
-use crate::domain::{Pager, QueryResults, ResultsFormat};
+use crate::cypher::{is_input_complete, split_script};
+use crate::domain::{
+    BenchmarkNumRuns, BenchmarkStats, OnError, OutputFormat, Pager, ParamSets, PlanMode,
+    QueryLanguage, QueryParam, QueryParams, QueryTimeout, ResultsFormat, RetryPolicy, RowSink,
+    SchemaFormat, SchemaNames,
+};
 use crate::repository::QueryExecutor;
-use crate::service::{page_results, write_results};
+use crate::service::{
+    BenchmarkSettings, InterruptedQuery, Interruption, PagedResults, QueryOutcome, ResultsFile,
+    run_benchmark, run_interruptible, stream_with_retries,
+};
 use anyhow::Context;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use colored::Colorize;
+use rustyline::Editor;
 use rustyline::error::ReadlineError;
+use rustyline::history::FileHistory;
 use std::io::Write;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -16,7 +29,9 @@ use tokio::time::Instant;
 const BANNER: &str = include_str!("assets/logo.txt");
 const COMMANDS: &str = include_str!("assets/commands.txt");
 const KEYMAPS: &str = include_str!("assets/keymaps.txt");
+const CONTINUATION_PROMPT: &str = ".. ";
 const CTRL_C_QUIT_THRESHOLD_MILLIS: u64 = 1000;
+const DEFAULT_BENCH_NUM_WARMUP_RUNS: u16 = 3;
 
 pub struct ConsoleConfig {
     pub page_results: bool,
@@ -24,13 +39,22 @@ pub struct ConsoleConfig {
     pub results_directory: PathBuf,
     pub history_file_path: PathBuf,
     pub results_format: ResultsFormat,
+    pub display_format: OutputFormat,
+    pub query_language: QueryLanguage,
+    pub read_only: bool,
+    pub query_timeout: Option<QueryTimeout>,
+    pub retry_policy: RetryPolicy,
+    pub on_error: OnError,
 }
 
 pub struct Console<D: QueryExecutor> {
     db_client: D,
     config: ConsoleConfig,
+    params: QueryParams,
     pager: Option<Pager>,
     last_ctrl_c: Option<Instant>,
+    // the results of every query in a session go to the same SQLite database
+    started_at: DateTime<Utc>,
 }
 
 #[allow(unused)]
@@ -45,8 +69,10 @@ impl<D: QueryExecutor> Console<D> {
         Self {
             db_client,
             config,
+            params: QueryParams::default(),
             pager,
             last_ctrl_c: None,
+            started_at: Utc::now(),
         }
     }
 
@@ -60,11 +86,26 @@ impl<D: QueryExecutor> Console<D> {
         );
 
         let mut editor = rustyline::Editor::new()?;
-        editor.set_helper(Some(QueryFilenameCompleter::default()));
+        editor.set_helper(Some(ConsoleHelper::new(
+            self.config.query_language.clone(),
+            colored::control::SHOULD_COLORIZE.should_colorize(),
+        )));
         let _ = editor.load_history(&self.config.history_file_path);
+        if let Err(e) = self.refresh_completions(&mut editor).await {
+            print_hint(format!(
+                "{e:#}; completion won't include labels, relationship types, or property keys (use :refresh to try again)"
+            ));
+        }
 
+        let mut read_error = None;
         loop {
-            let user_input = match editor.readline(">> ") {
+            let prompt = if self.db_client.in_transaction().await {
+                "tx>> "
+            } else {
+                ">> "
+            };
+
+            let user_input = match read_input(&mut editor, prompt) {
                 Ok(input) => {
                     self.last_ctrl_c = None;
                     input
@@ -83,7 +124,9 @@ impl<D: QueryExecutor> Console<D> {
                     break;
                 }
                 Err(e) => {
-                    return Err(e).context("couldn't read input");
+                    // leaving the loop (rather than returning) still rolls back an open transaction
+                    read_error = Some(e);
+                    break;
                 }
             };
 
@@ -140,7 +183,23 @@ impl<D: QueryExecutor> Console<D> {
                         }
                     },
                     None => {
-                        print_error("Usage: format <csv/json>");
+                        print_error(
+                            "Usage: format <csv/json/ndjson/yaml/markdown/tsv/html/parquet/arrow/sqlite>",
+                        );
+                    }
+                },
+                cmd if cmd.starts_with("display") => match cmd.split_once(" ") {
+                    Some((_, arg)) => match OutputFormat::from_str(arg) {
+                        Ok(f) => {
+                            print_info(format!("display format set to: {f}"));
+                            self.config.display_format = f;
+                        }
+                        Err(e) => {
+                            print_error(e);
+                        }
+                    },
+                    None => {
+                        print_error("Usage: display <table/json/ndjson/csv/tsv/markdown>");
                     }
                 },
                 cmd if cmd.starts_with("dir") => match cmd.split_once(" ") {
@@ -162,6 +221,132 @@ impl<D: QueryExecutor> Console<D> {
                     },
                     None => print_error("Usage: dir <PATH> | dir reset"),
                 },
+                cmd if cmd.starts_with(":lang") => match cmd.split_once(" ") {
+                    Some((_, arg)) => match QueryLanguage::from_str(arg) {
+                        Ok(l) => {
+                            print_info(format!("query language set to: {}", &l));
+                            if let Some(helper) = editor.helper_mut() {
+                                helper.set_query_language(l.clone());
+                            }
+                            self.config.query_language = l;
+                        }
+                        Err(e) => {
+                            print_error(e);
+                        }
+                    },
+                    None => {
+                        print_error("Usage: :lang <cypher/gremlin>");
+                    }
+                },
+                ":timeout" => match self.config.query_timeout {
+                    Some(t) => print_info(format!("query timeout: {t}")),
+                    None => print_info("no query timeout set"),
+                },
+                cmd if cmd.starts_with(":timeout ") => match cmd.split_once(" ") {
+                    Some((_, "off")) => {
+                        self.config.query_timeout = None;
+                        print_info("query timeout turned OFF");
+                    }
+                    Some((_, arg)) => match QueryTimeout::from_str(arg) {
+                        Ok(t) => {
+                            print_info(format!("query timeout set to: {t}"));
+                            self.config.query_timeout = Some(t);
+                        }
+                        Err(e) => print_error(format!("Error: {e}")),
+                    },
+                    None => print_error("Usage: :timeout <duration/off>"),
+                },
+                ":on-error" => {
+                    print_info(format!("on a failing statement: {}", self.config.on_error))
+                }
+                cmd if cmd.starts_with(":on-error ") => match cmd.split_once(" ") {
+                    Some((_, arg)) => match OnError::from_str(arg) {
+                        Ok(o) => {
+                            print_info(format!("on a failing statement: {o}"));
+                            self.config.on_error = o;
+                        }
+                        Err(e) => print_error(format!("Error: {e}")),
+                    },
+                    None => print_error("Usage: :on-error <stop/continue>"),
+                },
+                ":refresh" => match self.refresh_completions(&mut editor).await {
+                    Ok(names) => print_info(format!("fetched schema for completion: {names}")),
+                    Err(e) => print_error(format!("Error: {:#}", e)),
+                },
+                cmd if cmd == "schema" || cmd.starts_with("schema ") => {
+                    let format = match cmd.split_once(" ") {
+                        Some((_, arg)) => SchemaFormat::from_str(arg),
+                        None => Ok(SchemaFormat::Table),
+                    };
+                    let format = match format {
+                        Ok(f) => f,
+                        Err(e) => {
+                            print_error(format!("Error: {e}"));
+                            continue;
+                        }
+                    };
+
+                    match self.db_client.fetch_schema().await {
+                        Ok(schema) => match format {
+                            SchemaFormat::Table => match get_schema_tables(&schema) {
+                                Ok(tables) => print!("{tables}"),
+                                Err(e) => print_error(format!("Error: {:#}", e)),
+                            },
+                            SchemaFormat::Json => match serde_json::to_string_pretty(&schema) {
+                                Ok(json) => println!("{json}"),
+                                Err(e) => print_error(format!("Error: {e}")),
+                            },
+                        },
+                        Err(e) => print_error(format!("Error: {:#}", e)),
+                    }
+                }
+                ":begin" => match self.db_client.begin_transaction().await {
+                    Ok(()) => print_info("transaction started"),
+                    Err(e) => print_error(format!("Error: {:#}", e)),
+                },
+                ":commit" => match self.db_client.commit_transaction().await {
+                    Ok(()) => print_info("transaction committed"),
+                    Err(e) => print_error(format!("Error: {:#}", e)),
+                },
+                ":rollback" => match self.db_client.rollback_transaction().await {
+                    Ok(()) => print_info("transaction rolled back"),
+                    Err(e) => print_error(format!("Error: {:#}", e)),
+                },
+                ":params" => {
+                    if self.params.is_empty() {
+                        print_info("no query parameters set");
+                    } else {
+                        print_info(self.params.to_string());
+                    }
+                }
+                cmd if cmd.starts_with(":params ") => match cmd.split_once(" ") {
+                    Some((_, "clear")) => {
+                        self.params.clear();
+                        print_info("query parameters cleared");
+                    }
+                    _ => print_error("Usage: :params | :params clear"),
+                },
+                cmd if cmd == ":param" || cmd.starts_with(":param ") => {
+                    let args = cmd.trim_start_matches(":param").trim();
+                    match args.split_once(char::is_whitespace) {
+                        Some((key, value)) => match QueryParam::new(key, value) {
+                            Ok(param) => {
+                                print_info(format!("query parameter set: {param}"));
+                                self.params.insert(param);
+                            }
+                            Err(e) => print_error(format!("Error: {e}")),
+                        },
+                        None => {
+                            if self.params.remove(args.trim_start_matches('$')) {
+                                print_info(format!("query parameter unset: {args}"));
+                            } else {
+                                print_error(
+                                    "Usage: :param <key> <value> | :param <key> (to unset)",
+                                );
+                            }
+                        }
+                    }
+                }
                 cmd if cmd.starts_with("write") => match cmd.split_once(" ") {
                     Some((_, "on")) => {
                         self.config.write_results = true;
@@ -173,110 +358,377 @@ impl<D: QueryExecutor> Console<D> {
                     }
                     _ => print_error("Usage: write on/off"),
                 },
-                user_input => {
-                    if let Err(e) = editor.add_history_entry(user_input) {
+                cmd if get_bench_request(cmd).is_some() => {
+                    if let Err(e) = editor.add_history_entry(cmd) {
                         println!("Error: {e}");
                     }
 
-                    let query_to_execute = match get_query_from_user_input(user_input) {
+                    let Some(args) = get_bench_request(cmd) else {
+                        continue;
+                    };
+
+                    let args = match get_bench_args(args) {
+                        Ok(a) => a,
+                        Err(e) => {
+                            print_error(e);
+                            continue;
+                        }
+                    };
+
+                    let input = match get_query_from_user_input(args.query_input) {
+                        Ok(q) => q,
+                        Err(e) => {
+                            print_error(format!("Error: {:#}", e));
+                            continue;
+                        }
+                    };
+                    let query_to_execute =
+                        match split_script(&input, &self.config.query_language).as_slice() {
+                            [statement] => statement.text.to_string(),
+                            statements => {
+                                print_error(format!(
+                                    "Error: only a single statement can be benchmarked (found {})",
+                                    statements.len()
+                                ));
+                                continue;
+                            }
+                        };
+
+                    self.benchmark(&query_to_execute, args.num_runs, args.num_warmup_runs)
+                        .await;
+                }
+                ":plan" | ":profile" => {
+                    print_error("Usage: :plan <query/@path> | :profile <query/@path>")
+                }
+                cmd if get_plan_request(cmd).is_some() => {
+                    if let Err(e) = editor.add_history_entry(cmd) {
+                        println!("Error: {e}");
+                    }
+
+                    let Some((mode, query_input)) = get_plan_request(cmd) else {
+                        continue;
+                    };
+
+                    if self.config.query_language != QueryLanguage::Cypher {
+                        print_error("Error: query plans are only supported for cypher queries");
+                        continue;
+                    }
+
+                    let input = match get_query_from_user_input(query_input) {
                         Ok(q) => q,
                         Err(e) => {
                             print_error(format!("Error: {:#}", e));
                             continue;
                         }
                     };
+                    let query_to_execute = match split_script(&input, &self.config.query_language)
+                        .as_slice()
+                    {
+                        [statement] => statement.text.to_string(),
+                        statements => {
+                            print_error(format!(
+                                "Error: query plans can only be shown for a single statement (found {})",
+                                statements.len()
+                            ));
+                            continue;
+                        }
+                    };
 
                     let start = Instant::now();
 
-                    let results = tokio::select! {
-                        res = self.db_client.execute_query(&query_to_execute) => res,
-                        _ = tokio::signal::ctrl_c() => {
-                            print_hint("\nquery cancelled");
+                    let plan = match run_interruptible(
+                        &self.db_client,
+                        &self.config.query_language,
+                        &query_to_execute,
+                        self.config.query_timeout,
+                        self.db_client
+                            .explain_query(&query_to_execute, &self.params, mode, false),
+                    )
+                    .await
+                    {
+                        QueryOutcome::Finished(plan) => plan,
+                        QueryOutcome::Interrupted(i) => {
+                            println!();
+                            print_interruption(&i);
                             continue;
                         }
                     };
                     print_time(Instant::now().saturating_duration_since(start));
 
-                    match results {
-                        Ok(QueryResults::Empty) => {
-                            println!("\nNo results\n");
-                        }
-                        Ok(QueryResults::NonEmpty(results)) => {
-                            if self.config.write_results {
-                                match write_results(
-                                    &results,
-                                    &self.config.results_directory,
-                                    &self.config.results_format,
-                                    Utc::now(),
-                                ) {
-                                    Ok(p) => {
-                                        print_info(format!(
-                                            "wrote results to {}",
-                                            p.to_string_lossy()
-                                        ));
-
-                                        if self.config.page_results
-                                            && let Some(pager) = &self.pager
-                                            && let Err(e) = page_results(&p, pager)
-                                        {
-                                            print_error(format!(
-                                                "Error: couldn't display results via pager: {:#}",
-                                                e
-                                            ));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        print_error(format!(
-                                            "Error: couldn't write results: {:#}",
-                                            e
-                                        ));
-                                    }
-                                }
-                            } else if self.config.page_results
-                                && let Some(pager) = &self.pager
-                            {
-                                let temp_results_directory = tempfile::tempdir().context(
-                                    "couldn't create temporary directory for paging results",
-                                )?;
-
-                                match write_results(
-                                    &results,
-                                    &temp_results_directory,
-                                    &self.config.results_format,
-                                    Utc::now(),
-                                ) {
-                                    Ok(p) => {
-                                        if let Err(e) = page_results(&p, pager) {
-                                            print_error(format!(
-                                                "Error: couldn't display results via pager: {:#}",
-                                                e
-                                            ));
-                                        }
-                                    }
-                                    Err(e) => {
-                                        print_error(format!(
-                                            "Error: couldn't write results to temporary directory: {:#}",
-                                            e
-                                        ));
-                                    }
-                                }
-                            } else {
-                                let results_str = get_results(&results);
-                                println!("\n{}\n", results_str);
-                            }
-                        }
-                        Err(e) => print_error(format!("Error: couldn't get results: {:#}", e)),
+                    match plan {
+                        Ok(p) => println!("\n{}\n", get_plan_tree(&p)),
+                        Err(e) => print_error(format!("Error: couldn't get query plan: {:#}", e)),
                     }
                 }
+                user_input => {
+                    if let Err(e) = editor.add_history_entry(user_input) {
+                        println!("Error: {e}");
+                    }
+
+                    let input = match get_query_from_user_input(user_input) {
+                        Ok(q) => q,
+                        Err(e) => {
+                            print_error(format!("Error: {:#}", e));
+                            continue;
+                        }
+                    };
+
+                    self.execute_statements(&input).await;
+                }
+            }
+        }
+
+        if self.db_client.in_transaction().await {
+            print_hint("transaction was not committed; rolling it back");
+            if let Err(e) = self.db_client.rollback_transaction().await {
+                print_error(format!("Error: {:#}", e));
             }
         }
 
         let _ = editor.save_history(&self.config.history_file_path);
 
-        Ok(())
+        match read_error {
+            Some(e) => Err(e).context("couldn't read input"),
+            None => Ok(()),
+        }
+    }
+
+    /// Fetches the labels, relationship types, and property keys that queries are completed with.
+    async fn refresh_completions(
+        &self,
+        editor: &mut Editor<ConsoleHelper, FileHistory>,
+    ) -> anyhow::Result<SchemaNames> {
+        let schema_names = self
+            .db_client
+            .fetch_schema_names()
+            .await
+            .context("couldn't fetch schema")?;
+
+        if let Some(helper) = editor.helper_mut() {
+            helper.set_schema_names(schema_names.clone());
+        }
+
+        Ok(schema_names)
+    }
+
+    /// Benchmarks a query with the parameters set in the console. Pressing Ctrl+C stops the
+    /// benchmark, and prints the statistics of the runs that finished before that.
+    async fn benchmark(&self, query: &str, num_runs: BenchmarkNumRuns, num_warmup_runs: u16) {
+        let params = ParamSets::single(self.params.clone());
+        let settings = BenchmarkSettings {
+            num_runs,
+            num_warmup_runs,
+            timeout: self.config.query_timeout,
+            retry_policy: self.config.retry_policy,
+        };
+
+        println!();
+        let benchmark = match run_benchmark(
+            &self.db_client,
+            &self.config.query_language,
+            query,
+            &params,
+            &settings,
+            |e| print_benchmark_event(&e),
+        )
+        .await
+        {
+            Ok(b) => b,
+            Err(e) => {
+                print_error(format!("Error: {:#}", e));
+                return;
+            }
+        };
+
+        if let Some(interruption) = &benchmark.interruption {
+            println!();
+            print_interruption(interruption);
+            if !benchmark.runs.is_empty() {
+                print_hint(format!(
+                    "statistics of the {} of {num_runs} runs that finished:",
+                    benchmark.runs.len()
+                ));
+            }
+        }
+
+        let times: Vec<Duration> = benchmark.runs.iter().map(|r| r.latency).collect();
+        if let Some(statistics) = BenchmarkStats::new(&times) {
+            print!(
+                "{}",
+                get_benchmark_summary(&statistics, &benchmark.runs, &params)
+            );
+        }
+        println!();
+    }
+
+    /// Executes the statements in the input one after the other. Once a statement fails, the rest
+    /// are skipped, unless the console is set to continue on errors; interrupting a statement via
+    /// Ctrl+C always skips the rest.
+    async fn execute_statements(&mut self, input: &str) {
+        let statements = split_script(input, &self.config.query_language);
+        let total = statements.len();
+        if total == 0 {
+            print_error("Error: input doesn't contain any statements");
+            return;
+        }
+
+        if total == 1 {
+            self.execute_statement(statements[0].text, None).await;
+            return;
+        }
+
+        let mut failed = 0;
+        for (i, statement) in statements.iter().enumerate() {
+            let number = i + 1;
+            println!();
+            print_hint(format!(
+                "statement {number}/{total} (line {})",
+                statement.line
+            ));
+
+            match self
+                .execute_statement(statement.text, Some(number.to_string()))
+                .await
+            {
+                StatementOutcome::Succeeded => {}
+                StatementOutcome::Failed if self.config.on_error == OnError::Continue => {
+                    failed += 1;
+                }
+                StatementOutcome::Failed | StatementOutcome::Cancelled => {
+                    if number < total {
+                        print_hint(format!(
+                            "skipped the remaining {} statement(s)",
+                            total - number
+                        ));
+                    }
+                    return;
+                }
+            }
+        }
+
+        if failed > 0 {
+            print_error(format!("{failed} of {total} statements failed"));
+        }
+    }
+
+    async fn execute_statement(
+        &mut self,
+        query: &str,
+        results_file_suffix: Option<String>,
+    ) -> StatementOutcome {
+        let pager = if self.config.page_results {
+            self.pager.as_ref()
+        } else {
+            None
+        };
+        let reference_time = match self.config.results_format {
+            ResultsFormat::Sqlite => self.started_at,
+            _ => Utc::now(),
+        };
+        let mut results_file = self.config.write_results.then(|| {
+            let file = ResultsFile::new(
+                &self.config.results_directory,
+                &self.config.results_format,
+                reference_time,
+            )
+            .with_query(query)
+            .with_db_uri(self.db_client.db_uri());
+            match &results_file_suffix {
+                Some(suffix) => file.with_name_suffix(suffix.as_str()),
+                None => file,
+            }
+        });
+        let mut paged_results = pager.map(|p| PagedResults::new(p, &self.config.results_format));
+        let mut displayed = (!self.config.write_results && pager.is_none())
+            .then(|| OutputWriter::new(self.config.display_format, std::io::stdout()));
+
+        let in_transaction = self.db_client.in_transaction().await;
+
+        println!();
+        let start = Instant::now();
+
+        let mut sink = (&mut results_file, (&mut paged_results, &mut displayed));
+        let outcome = run_interruptible(
+            &self.db_client,
+            &self.config.query_language,
+            query,
+            self.config.query_timeout,
+            stream_with_retries(
+                &self.db_client,
+                &self.config.query_language,
+                query,
+                &self.params,
+                &mut sink,
+                self.config.retry_policy,
+                |r| print_hint(r.to_string()),
+            ),
+        )
+        .await;
+        let mut finished = sink.finish();
+        if let Some(paged_results) = paged_results.as_mut() {
+            finished = finished.and(paged_results.wait().await);
+        }
+        let elapsed = Instant::now().saturating_duration_since(start);
+
+        if let Err(e) = finished {
+            print_error(format!("Error: couldn't write results: {:#}", e));
+        }
+
+        if let Some(p) = results_file.as_ref().and_then(|f| f.path()) {
+            print_info(format!("wrote results to {}", p.to_string_lossy()));
+        }
+
+        match outcome {
+            QueryOutcome::Finished(Ok(r)) if r.value == 0 => {
+                println!("No results\n");
+                print_time(elapsed);
+                StatementOutcome::Succeeded
+            }
+            QueryOutcome::Finished(Ok(_)) => {
+                println!();
+                print_time(elapsed);
+                StatementOutcome::Succeeded
+            }
+            QueryOutcome::Finished(Err(e)) => {
+                print_error(format!("Error: couldn't get results: {:#}", e));
+                StatementOutcome::Failed
+            }
+            QueryOutcome::Interrupted(i) => {
+                print_interruption(&i);
+                if in_transaction && !self.db_client.in_transaction().await {
+                    print_hint("the open transaction was discarded along with the query");
+                }
+                match i.reason {
+                    Interruption::CtrlC => StatementOutcome::Cancelled,
+                    Interruption::TimedOut(_) => StatementOutcome::Failed,
+                }
+            }
+        }
     }
 }
 
+enum StatementOutcome {
+    Succeeded,
+    Failed,
+    Cancelled,
+}
+
+/// Queries are read over as many lines as it takes to close their brackets, strings, and comments
+/// (or to terminate them with a `;`), with lines after the first read after a continuation prompt.
+fn read_input(
+    editor: &mut Editor<ConsoleHelper, FileHistory>,
+    prompt: &str,
+) -> rustyline::Result<String> {
+    let mut input = editor.readline(prompt)?;
+    while !is_console_command(&input) && !is_input_complete(&input) {
+        let line = editor.readline(CONTINUATION_PROMPT)?;
+        input.push('\n');
+        input.push_str(&line);
+    }
+
+    Ok(input)
+}
+
 fn print_error<S: AsRef<str>>(contents: S) {
     println!("{}", contents.as_ref().red());
 }
@@ -293,6 +745,14 @@ fn print_hint<S: AsRef<str>>(contents: S) {
     println!("{}", contents.as_ref().yellow());
 }
 
+fn print_interruption(interrupted: &InterruptedQuery) {
+    print_hint(interrupted.reason.to_string());
+    match interrupted.server_cancellation {
+        Ok(_) => print_hint(interrupted.server_cancellation_summary()),
+        Err(_) => print_error(interrupted.server_cancellation_summary()),
+    }
+}
+
 fn print_banner(mut writer: impl Write, color: bool) {
     if color {
         let _ = writeln!(writer, "{}\n", BANNER.blue());
@@ -304,14 +764,29 @@ fn print_banner(mut writer: impl Write, color: bool) {
 fn print_help(mut writer: impl Write, db_uri: &str, config: &ConsoleConfig, color: bool) {
     let config_help = format!(
         " config
+   read-only mode                          {}
+   query timeout                           {}
+   retries (transient errors)              {}
+   on a failing statement                  {}
+   query language                          {}
    page results                            {}
    write results to filesystem             {}
    results format                          {}
-   results directory                       {}",
+   results directory                       {}
+   display format                          {}",
+        if config.read_only { "ON" } else { "OFF" },
+        config
+            .query_timeout
+            .map(|t| t.to_string())
+            .unwrap_or_else(|| "OFF".to_string()),
+        config.retry_policy.max_retries(),
+        config.on_error,
+        config.query_language,
         if config.page_results { "ON" } else { "OFF" },
         if config.write_results { "ON" } else { "OFF" },
         config.results_format,
         config.results_directory.to_string_lossy(),
+        config.display_format,
     );
 
     let help = if color {
@@ -344,8 +819,80 @@ fn print_help(mut writer: impl Write, db_uri: &str, config: &ConsoleConfig, colo
     let _ = write!(writer, "{}", help);
 }
 
+/// Returns the plan mode and the query input if the user input is a `:plan` or `:profile` command.
+fn get_plan_request(contents: &str) -> Option<(PlanMode, &str)> {
+    let (command, rest) = contents.split_once(char::is_whitespace)?;
+    let rest = rest.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    match command {
+        ":plan" => Some((PlanMode::Explain, rest)),
+        ":profile" => Some((PlanMode::Profile, rest)),
+        _ => None,
+    }
+}
+
+/// Returns the arguments of the user input if it's a request to benchmark a query.
+fn get_bench_request(contents: &str) -> Option<&str> {
+    match contents.split_once(char::is_whitespace) {
+        Some(("bench", args)) => Some(args.trim()),
+        None if contents == "bench" => Some(""),
+        _ => None,
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct BenchArgs<'a> {
+    num_runs: BenchmarkNumRuns,
+    num_warmup_runs: u16,
+    query_input: &'a str,
+}
+
+/// Parses "[N] [W] <query/@path>", where N is the number of benchmark runs, and W the number of
+/// warmup runs.
+fn get_bench_args(args: &str) -> Result<BenchArgs<'_>, String> {
+    let mut numbers = vec![];
+    let mut rest = args.trim();
+    while numbers.len() < 2 {
+        let (word, remaining) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if word.is_empty() || !word.chars().all(|c| c.is_ascii_digit()) {
+            break;
+        }
+        numbers.push(word);
+        rest = remaining.trim_start();
+    }
+
+    if rest.is_empty() {
+        return Err("Usage: bench [<runs>] [<warmup runs>] <query/@path>".to_string());
+    }
+
+    let num_runs = match numbers.first() {
+        Some(n) => BenchmarkNumRuns::from_str(n)
+            .map_err(|e| format!("Error: invalid number of runs: {e}"))?,
+        None => BenchmarkNumRuns::default(),
+    };
+    let num_warmup_runs = match numbers.get(1) {
+        Some(n) => n
+            .parse()
+            .map_err(|_| "Error: invalid number of warmup runs: value is not a valid number")?,
+        None => DEFAULT_BENCH_NUM_WARMUP_RUNS,
+    };
+
+    Ok(BenchArgs {
+        num_runs,
+        num_warmup_runs,
+        query_input: rest,
+    })
+}
+
 fn get_query_from_user_input(contents: &str) -> anyhow::Result<String> {
-    let query_to_execute = if let Some(file_path) = contents.strip_prefix('@').map(|p| p.trim()) {
+    // a `;` after the path (eg. when it's typed on a continuation line) isn't part of it
+    let query_to_execute = if let Some(file_path) = contents
+        .strip_prefix('@')
+        .map(|p| p.trim().trim_end_matches(';').trim_end())
+    {
         if file_path.is_empty() {
             anyhow::bail!("no file path provided after '@'");
         }
@@ -384,8 +931,14 @@ mod tests {
         let console_config = ConsoleConfig {
             page_results: false,
             results_format: ResultsFormat::Csv,
+            display_format: OutputFormat::Table,
             results_directory: PathBuf::new().join(DEFAULT_RESULTS_DIR),
             write_results: false,
+            query_language: QueryLanguage::Cypher,
+            read_only: false,
+            query_timeout: None,
+            retry_policy: RetryPolicy::default(),
+            on_error: OnError::Stop,
             history_file_path: PathBuf::new(),
         };
 
@@ -473,10 +1026,139 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn get_plan_request_parses_plan_and_profile_commands() {
+        // GIVEN
+        // WHEN
+        let plan = get_plan_request(":plan MATCH (n) RETURN n");
+        let profile = get_plan_request(":profile   @query.cypher");
+
+        // THEN
+        assert_eq!(plan, Some((PlanMode::Explain, "MATCH (n) RETURN n")));
+        assert_eq!(profile, Some((PlanMode::Profile, "@query.cypher")));
+    }
+
+    #[test]
+    fn get_plan_request_ignores_other_input() {
+        // GIVEN
+        // WHEN
+        let query = get_plan_request("MATCH (n) RETURN n");
+        let native_explain = get_plan_request("EXPLAIN MATCH (n) RETURN n");
+        let bare_command = get_plan_request(":plan");
+
+        // THEN
+        assert!(query.is_none());
+        assert!(native_explain.is_none());
+        assert!(bare_command.is_none());
+    }
+
+    #[test]
+    fn get_bench_request_only_matches_the_bench_command() {
+        // GIVEN
+        // WHEN
+        let with_args = get_bench_request("bench 10 MATCH (n) RETURN n");
+        let bare_command = get_bench_request("bench");
+        let query = get_bench_request("benchmark MATCH (n) RETURN n");
+
+        // THEN
+        assert_eq!(with_args, Some("10 MATCH (n) RETURN n"));
+        assert_eq!(bare_command, Some(""));
+        assert!(query.is_none());
+    }
+
+    #[test]
+    fn bench_reads_the_query_from_a_file_terminated_by_a_semicolon() -> anyhow::Result<()> {
+        // GIVEN
+        let inputs = [
+            format!("20 @{QUERY_FILE_PATH}"),
+            format!("20 @{QUERY_FILE_PATH};"),
+            format!("20 @{QUERY_FILE_PATH}\n;"),
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|input| {
+                let args = get_bench_args(input).map_err(anyhow::Error::msg)?;
+                get_query_from_user_input(args.query_input)
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        // THEN
+        assert!(
+            result
+                .iter()
+                .all(|q| q == "MATCH (n:Node) return n.id, n.name LIMIT 5;"),
+            "{result:?}"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_bench_args_uses_defaults_for_missing_numbers() -> anyhow::Result<()> {
+        // GIVEN
+        // WHEN
+        let query_only = get_bench_args("MATCH (n) RETURN count(n)").map_err(anyhow::Error::msg)?;
+        let runs_only = get_bench_args("20 @query.cypher").map_err(anyhow::Error::msg)?;
+        let runs_and_warmup_runs =
+            get_bench_args("20   0 MATCH (n)\nRETURN 1").map_err(anyhow::Error::msg)?;
+
+        // THEN
+        assert_eq!(
+            query_only,
+            BenchArgs {
+                num_runs: BenchmarkNumRuns::default(),
+                num_warmup_runs: DEFAULT_BENCH_NUM_WARMUP_RUNS,
+                query_input: "MATCH (n) RETURN count(n)",
+            }
+        );
+        assert_eq!(
+            (runs_only.num_runs.value(), runs_only.num_warmup_runs),
+            (20, DEFAULT_BENCH_NUM_WARMUP_RUNS)
+        );
+        assert_eq!(runs_only.query_input, "@query.cypher");
+        assert_eq!(
+            (
+                runs_and_warmup_runs.num_runs.value(),
+                runs_and_warmup_runs.num_warmup_runs
+            ),
+            (20, 0)
+        );
+        assert_eq!(runs_and_warmup_runs.query_input, "MATCH (n)\nRETURN 1");
+
+        Ok(())
+    }
+
     //------------//
     //  FAILURES  //
     //------------//
 
+    #[test]
+    fn get_bench_args_fails_for_invalid_input() {
+        // GIVEN
+        let inputs = [
+            "",
+            "10 3",
+            "0 MATCH (n) RETURN n",
+            "5 70000 MATCH (n) RETURN n",
+        ];
+
+        // WHEN
+        let result = inputs.map(|i| get_bench_args(i).expect_err("parsing should've failed"));
+
+        // THEN
+        assert_eq!(
+            result,
+            [
+                "Usage: bench [<runs>] [<warmup runs>] <query/@path>",
+                "Usage: bench [<runs>] [<warmup runs>] <query/@path>",
+                "Error: invalid number of runs: needs to be greater than 0",
+                "Error: invalid number of warmup runs: value is not a valid number",
+            ]
+        );
+    }
+
     #[test]
     fn get_query_from_user_input_fails_if_no_file_path_provided() {
         // GIVEN
@@ -0,0 +1,322 @@
+use super::{apply_highlights, highlight_spans};
+use crate::cypher::{CompletionKind, FUNCTIONS, KEYWORDS, completion_context, is_input_complete};
+use crate::domain::{QueryLanguage, SchemaNames, escape_name};
+use rustyline::completion::{Completer, FilenameCompleter, Pair};
+use rustyline::highlight::{CmdKind, Highlighter};
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Context, Helper, Result};
+use std::borrow::Cow;
+use std::cell::Cell;
+
+/// Console commands are always single-line, and are run as soon as they're entered.
+const COMMANDS: [&str; 11] = [
+    "bye", "clear", "dir", "display", "exit", "format", "help", "page", "quit", "schema", "write",
+];
+
+/// Completes `@file` paths and queries, highlights queries as they're typed, and keeps the input
+/// open over multiple lines until a query is complete.
+pub struct ConsoleHelper {
+    inner: FilenameCompleter,
+    query_language: QueryLanguage,
+    schema_names: SchemaNames,
+    color: bool,
+    // the matching bracket is only highlighted while the input is being edited
+    highlight_bracket: Cell<bool>,
+}
+
+impl ConsoleHelper {
+    pub fn new(query_language: QueryLanguage, color: bool) -> Self {
+        Self {
+            inner: FilenameCompleter::new(),
+            query_language,
+            schema_names: SchemaNames::default(),
+            color,
+            highlight_bracket: Cell::new(false),
+        }
+    }
+
+    pub fn set_query_language(&mut self, query_language: QueryLanguage) {
+        self.query_language = query_language;
+    }
+
+    pub fn set_schema_names(&mut self, schema_names: SchemaNames) {
+        self.schema_names = schema_names;
+    }
+
+    /// Completes labels, relationship types, and property keys (from the database's schema), and
+    /// keywords and functions, based on what precedes the cursor.
+    fn complete_query(&self, input: &str) -> (usize, Vec<Pair>) {
+        let Some(context) = completion_context(input) else {
+            return (input.len(), vec![]);
+        };
+
+        let candidates = match context.kind {
+            CompletionKind::Label => names_with_prefix(&self.schema_names.labels, context.prefix),
+            CompletionKind::RelationshipType => {
+                names_with_prefix(&self.schema_names.relationship_types, context.prefix)
+            }
+            CompletionKind::PropertyKey => {
+                names_with_prefix(&self.schema_names.property_keys, context.prefix)
+            }
+            CompletionKind::Keyword => {
+                // keywords follow the case the user types them in
+                let lowercase = context.prefix.chars().all(|c| !c.is_uppercase());
+                let keywords = KEYWORDS
+                    .iter()
+                    .filter(|k| has_prefix(k, context.prefix))
+                    .map(|k| {
+                        let keyword = if lowercase {
+                            k.to_lowercase()
+                        } else {
+                            k.to_string()
+                        };
+                        Pair {
+                            display: keyword.clone(),
+                            replacement: keyword,
+                        }
+                    });
+                let functions = FUNCTIONS
+                    .iter()
+                    .filter(|f| has_prefix(f, context.prefix))
+                    .map(|f| Pair {
+                        display: format!("{f}()"),
+                        replacement: format!("{f}("),
+                    });
+
+                keywords.chain(functions).collect()
+            }
+        };
+
+        (context.start, candidates)
+    }
+}
+
+impl Completer for ConsoleHelper {
+    type Candidate = Pair;
+
+    /// Complete file paths for input starting with `@`, and Cypher queries otherwise (see
+    /// [`ConsoleHelper::complete_query`]).
+    ///
+    /// Example: user types `@quer|` (cursor is `|`)
+    ///
+    /// ```text
+    /// @quer|
+    /// ^----^ line = "@quer"
+    ///  ^---^ path_segment = "quer" (line[1..5])
+    /// 012345
+    ///      ^ pos = 5
+    /// ```
+    ///
+    /// `"quer"` is passed to rustyline's built-in FilenameCompleter, which returns
+    /// `(0, vec!["queries/"])`. 1 is added to the start index to accommodate for the '@'.
+    ///
+    /// Returning `(1, vec!["queries/"])` tells rustyline to replace from position 1,
+    /// resulting in `@queries/`.
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        ctx: &Context<'_>,
+    ) -> Result<(usize, Vec<Self::Candidate>)> {
+        if !line.starts_with('@') {
+            if self.query_language != QueryLanguage::Cypher || is_console_command(line) {
+                return Ok((pos, vec![]));
+            }
+
+            return Ok(self.complete_query(&line[..pos]));
+        }
+
+        // Only complete when cursor is at the end of the line
+        if pos != line.len() {
+            return Ok((pos, vec![]));
+        }
+
+        let path_segment = &line[1..pos];
+
+        let (start, candidates) = self.inner.complete(path_segment, path_segment.len(), ctx)?;
+
+        Ok((start + 1, candidates))
+    }
+}
+
+impl Hinter for ConsoleHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ConsoleHelper {
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        if !self.color || is_console_command(line) {
+            return Cow::Borrowed(line);
+        }
+
+        let cursor = self.highlight_bracket.get().then_some(pos);
+        let spans = highlight_spans(line, &self.query_language, cursor);
+        if spans.is_empty() {
+            return Cow::Borrowed(line);
+        }
+
+        Cow::Owned(apply_highlights(line, &spans))
+    }
+
+    fn highlight_char(&self, _line: &str, _pos: usize, kind: CmdKind) -> bool {
+        self.highlight_bracket.set(kind != CmdKind::ForcedRefresh);
+        self.color
+    }
+}
+
+impl Validator for ConsoleHelper {
+    /// Input that already spans several lines (eg. a recalled history entry, or a paste) is kept
+    /// open, so that it can be edited as a whole, until it's complete. Single lines are accepted,
+    /// and the console reads the rest of the query after a continuation prompt.
+    fn validate(&self, ctx: &mut ValidationContext) -> Result<ValidationResult> {
+        let input = ctx.input();
+        if !input.contains('\n') || is_console_command(input) || is_input_complete(input) {
+            Ok(ValidationResult::Valid(None))
+        } else {
+            Ok(ValidationResult::Incomplete)
+        }
+    }
+}
+
+pub(super) fn is_console_command(input: &str) -> bool {
+    let input = input.trim_start();
+    if input.starts_with(':') || input.starts_with('@') {
+        return true;
+    }
+
+    let mut words = input.split_whitespace();
+    let first_word = words.next().unwrap_or_default();
+
+    // benchmarking the query in a file (`bench [N] [W] @path`) is as single-line as running it
+    if first_word == "bench" {
+        return words
+            .find(|w| !w.chars().all(|c| c.is_ascii_digit()))
+            .is_some_and(|w| w.starts_with('@'));
+    }
+
+    COMMANDS.contains(&first_word)
+}
+
+impl Helper for ConsoleHelper {}
+
+fn has_prefix(name: &str, prefix: &str) -> bool {
+    name.len() >= prefix.len()
+        && name.is_char_boundary(prefix.len())
+        && name[..prefix.len()].eq_ignore_ascii_case(prefix)
+}
+
+fn names_with_prefix(names: &[String], prefix: &str) -> Vec<Pair> {
+    names
+        .iter()
+        .filter(|n| has_prefix(n, prefix))
+        .map(|n| Pair {
+            display: n.clone(),
+            replacement: escape_name(n),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn console_commands_are_recognised() {
+        // GIVEN
+        let inputs = [
+            ":param name 'unterminated",
+            "@query.cypher",
+            "  page on",
+            "help",
+            "dir (",
+            "bench 20 @query.cypher",
+            "bench @query.cypher",
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| is_console_command(i))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert!(result.iter().all(|r| *r), "{result:?}");
+    }
+
+    #[test]
+    fn queries_are_not_console_commands() {
+        // GIVEN
+        let inputs = [
+            "MATCH (n:Person",
+            "explain MATCH (n",
+            "bench 10 MATCH (n",
+            "pages",
+            "g.V().has('a'",
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| is_console_command(i))
+            .collect::<Vec<_>>();
+
+        // THEN
+        assert!(result.iter().all(|r| !*r), "{result:?}");
+    }
+
+    #[test]
+    fn queries_are_completed_using_the_schema() {
+        // GIVEN
+        let mut helper = ConsoleHelper::new(QueryLanguage::Cypher, false);
+        helper.set_schema_names(SchemaNames::new(
+            [
+                "Person".to_string(),
+                "Film Star".to_string(),
+                "Movie".to_string(),
+            ],
+            ["ACTED_IN".to_string(), "DIRECTED".to_string()],
+            ["name".to_string(), "born".to_string(), "title".to_string()],
+        ));
+        let inputs = [
+            "MATCH (n:",
+            "MATCH (n:f",
+            "MATCH (n)-[:D",
+            "MATCH (n) RETURN n.b",
+            "MATCH (n) wh",
+            "MATCH (n) RETURN cou",
+            "MATCH (n {name: ",
+        ];
+
+        // WHEN
+        let result = inputs
+            .iter()
+            .map(|i| {
+                let (start, candidates) = helper.complete_query(i);
+                let replacements = candidates
+                    .iter()
+                    .map(|c| c.replacement.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{i} -> {start}: [{replacements}]")
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @"
+        MATCH (n: -> 9: [`Film Star`, Movie, Person]
+        MATCH (n:f -> 9: [`Film Star`]
+        MATCH (n)-[:D -> 12: [DIRECTED]
+        MATCH (n) RETURN n.b -> 19: [born]
+        MATCH (n) wh -> 10: [when, where]
+        MATCH (n) RETURN cou -> 17: [count(]
+        MATCH (n {name:  -> 16: []
+        ");
+    }
+}
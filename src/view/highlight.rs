@@ -0,0 +1,311 @@
+use crate::cypher::{Token, TokenKind, is_keyword, tokenize};
+use crate::domain::QueryLanguage;
+use colored::Colorize;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Highlight {
+    Keyword,
+    Label,
+    RelationshipType,
+    String,
+    Number,
+    Parameter,
+    Comment,
+    MatchingBracket,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HighlightSpan {
+    pub highlight: Highlight,
+    /// Byte range of the span in the input
+    pub range: Range<usize>,
+}
+
+/// Returns the spans of a query that should be highlighted, in order.
+///
+/// Keywords, labels, and relationship types are only recognised in Cypher queries. If `cursor` is
+/// on (or right after) a bracket, the bracket matching it is highlighted as well.
+pub fn highlight_spans(
+    query: &str,
+    language: &QueryLanguage,
+    cursor: Option<usize>,
+) -> Vec<HighlightSpan> {
+    let tokens = tokenize(query);
+    let mut spans = vec![];
+
+    // open brackets enclosing the current token
+    let mut brackets: Vec<&str> = vec![];
+    let mut previous: Option<&Token> = None;
+    let mut previous_was_type = false;
+
+    for token in &tokens {
+        let highlight = match token.kind {
+            TokenKind::Whitespace => continue,
+            TokenKind::Comment => Some(Highlight::Comment),
+            TokenKind::String => Some(Highlight::String),
+            TokenKind::Number => Some(Highlight::Number),
+            TokenKind::Parameter => Some(Highlight::Parameter),
+            TokenKind::Word | TokenKind::QuotedIdentifier if *language == QueryLanguage::Cypher => {
+                let follows_colon = previous.is_some_and(|p| p.is_punctuation(":"));
+                // labels and relationship types can be combined, eg. (n:A&B), [:KNOWS|LIKES]
+                let follows_type = previous_was_type
+                    && previous.is_some_and(|p| p.is_punctuation("|") || p.is_punctuation("&"));
+
+                let type_highlight = if follows_colon || follows_type {
+                    match brackets.last() {
+                        None | Some(&"(") => Some(Highlight::Label),
+                        Some(&"[") => Some(Highlight::RelationshipType),
+                        _ => None,
+                    }
+                } else {
+                    None
+                };
+
+                type_highlight.or_else(|| {
+                    (token.kind == TokenKind::Word
+                        && is_keyword(token.text)
+                        && !previous.is_some_and(|p| p.is_punctuation(".")))
+                    .then_some(Highlight::Keyword)
+                })
+            }
+            TokenKind::Word | TokenKind::QuotedIdentifier => None,
+            TokenKind::Punctuation => {
+                match token.text {
+                    "(" | "[" | "{" => brackets.push(token.text),
+                    ")" | "]" | "}" => {
+                        brackets.pop();
+                    }
+                    _ => {}
+                }
+                None
+            }
+        };
+
+        if token.kind != TokenKind::Comment {
+            previous_was_type = matches!(
+                highlight,
+                Some(Highlight::Label | Highlight::RelationshipType)
+            ) || (previous_was_type
+                && (token.is_punctuation("|") || token.is_punctuation("&")));
+            previous = Some(token);
+        }
+
+        if let Some(highlight) = highlight {
+            spans.push(HighlightSpan {
+                highlight,
+                range: token.start..token.start + token.text.len(),
+            });
+        }
+    }
+
+    if let Some(bracket) = cursor.and_then(|c| matching_bracket(&tokens, c)) {
+        let position = spans.partition_point(|s| s.range.start < bracket);
+        spans.insert(
+            position,
+            HighlightSpan {
+                highlight: Highlight::MatchingBracket,
+                range: bracket..bracket + 1,
+            },
+        );
+    }
+
+    spans
+}
+
+/// Applies the colours for the spans to the query.
+pub fn apply_highlights(query: &str, spans: &[HighlightSpan]) -> String {
+    let mut output = String::with_capacity(query.len() * 2);
+    let mut end = 0;
+
+    for span in spans {
+        output.push_str(&query[end..span.range.start]);
+        let text = &query[span.range.clone()];
+        let styled = match span.highlight {
+            Highlight::Keyword => text.blue().bold(),
+            Highlight::Label => text.yellow(),
+            Highlight::RelationshipType => text.magenta(),
+            Highlight::String => text.green(),
+            Highlight::Number => text.cyan(),
+            Highlight::Parameter => text.bright_cyan().italic(),
+            Highlight::Comment => text.bright_black(),
+            Highlight::MatchingBracket => text.bold().reversed(),
+        };
+        output.push_str(&styled.to_string());
+        end = span.range.end;
+    }
+    output.push_str(&query[end..]);
+
+    output
+}
+
+/// Returns the byte offset of the bracket matching the one at, or right before, the cursor.
+fn matching_bracket(tokens: &[Token], cursor: usize) -> Option<usize> {
+    let brackets: Vec<&Token> = tokens
+        .iter()
+        .filter(|t| {
+            t.kind == TokenKind::Punctuation
+                && (closing(t.text).is_some() || opening(t.text).is_some())
+        })
+        .collect();
+
+    let index = brackets
+        .iter()
+        .position(|t| t.start == cursor)
+        .or_else(|| brackets.iter().position(|t| t.start + 1 == cursor))?;
+    let bracket = brackets[index];
+
+    let mut depth = 0;
+    if let Some(close) = closing(bracket.text) {
+        for other in &brackets[index + 1..] {
+            if other.text == bracket.text {
+                depth += 1;
+            } else if other.text == close {
+                if depth == 0 {
+                    return Some(other.start);
+                }
+                depth -= 1;
+            }
+        }
+    } else if let Some(open) = opening(bracket.text) {
+        for other in brackets[..index].iter().rev() {
+            if other.text == bracket.text {
+                depth += 1;
+            } else if other.text == open {
+                if depth == 0 {
+                    return Some(other.start);
+                }
+                depth -= 1;
+            }
+        }
+    }
+
+    None
+}
+
+fn closing(bracket: &str) -> Option<&'static str> {
+    match bracket {
+        "(" => Some(")"),
+        "[" => Some("]"),
+        "{" => Some("}"),
+        _ => None,
+    }
+}
+
+fn opening(bracket: &str) -> Option<&'static str> {
+    match bracket {
+        ")" => Some("("),
+        "]" => Some("["),
+        "}" => Some("{"),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use insta::assert_snapshot;
+
+    fn describe(query: &str, language: QueryLanguage, cursor: Option<usize>) -> String {
+        highlight_spans(query, &language, cursor)
+            .iter()
+            .map(|s| format!("{:?}({})", s.highlight, &query[s.range.clone()]))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn highlight_spans_works_for_cypher_queries() {
+        // GIVEN
+        let query = "match (n:Person:`Film Star`)-[r:ACTED_IN|DIRECTED]->(m {title: $title}) // recent\nWHERE n.end > 2000 AND n:Actor RETURN [x IN r.roles | x], 'done'";
+
+        // WHEN
+        let result = describe(query, QueryLanguage::Cypher, None);
+
+        // THEN
+        assert_snapshot!(result, @"
+        Keyword(match)
+        Label(Person)
+        Label(`Film Star`)
+        RelationshipType(ACTED_IN)
+        RelationshipType(DIRECTED)
+        Parameter($title)
+        Comment(// recent)
+        Keyword(WHERE)
+        Number(2000)
+        Keyword(AND)
+        Label(Actor)
+        Keyword(RETURN)
+        Keyword(IN)
+        String('done')
+        ");
+    }
+
+    #[test]
+    fn highlight_spans_only_highlights_literals_and_comments_for_gremlin() {
+        // GIVEN
+        let query = "g.V().has('name', $name).limit(10) // first ten";
+
+        // WHEN
+        let result = describe(query, QueryLanguage::Gremlin, None);
+
+        // THEN
+        assert_snapshot!(result, @"
+        String('name')
+        Parameter($name)
+        Number(10)
+        Comment(// first ten)
+        ");
+    }
+
+    #[test]
+    fn highlight_spans_includes_the_bracket_matching_the_one_at_the_cursor() {
+        // GIVEN
+        let query = "RETURN size([(a)-->(b) | b])";
+        let cursors = [11, 12, 28, 13, 16, 27, 0];
+
+        // WHEN
+        let result = cursors
+            .iter()
+            .map(|c| {
+                let bracket = highlight_spans(query, &QueryLanguage::Cypher, Some(*c))
+                    .into_iter()
+                    .find(|s| s.highlight == Highlight::MatchingBracket);
+                format!("{c} -> {:?}", bracket.map(|b| b.range.start))
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        // THEN
+        assert_snapshot!(result, @"
+        11 -> Some(27)
+        12 -> Some(26)
+        28 -> Some(11)
+        13 -> Some(15)
+        16 -> Some(13)
+        27 -> Some(11)
+        0 -> None
+        ");
+    }
+
+    #[test]
+    fn highlight_spans_ignores_brackets_inside_strings() {
+        // GIVEN
+        let query = "RETURN ('(' + \")\")";
+
+        // WHEN
+        let result = describe(query, QueryLanguage::Cypher, Some(7));
+
+        // THEN
+        assert_snapshot!(result, @r#"
+        Keyword(RETURN)
+        String('(')
+        String(")")
+        MatchingBracket())
+        "#);
+    }
+}
@@ -1,7 +1,15 @@
-mod completer;
+mod benchmark;
 mod console;
+mod helper;
+mod highlight;
+mod plan;
 mod results;
+mod schema;
 
-use completer::*;
+pub use benchmark::*;
 pub use console::*;
+use helper::*;
+use highlight::*;
+pub use plan::*;
 pub use results::*;
+pub use schema::*;
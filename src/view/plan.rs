@@ -0,0 +1,107 @@
+use crate::domain::{PlanOperator, QueryPlan};
+
+pub fn get_plan_tree(plan: &QueryPlan) -> String {
+    let mut lines = vec![];
+    push_operator(&mut lines, &plan.root, "", "");
+
+    lines.join("\n")
+}
+
+fn push_operator(lines: &mut Vec<String>, op: &PlanOperator, prefix: &str, child_prefix: &str) {
+    let mut line = format!("{prefix}{}", op.name);
+
+    if !op.details.is_empty() {
+        line.push_str(&format!(" [{}]", op.details.join(", ")));
+    }
+
+    let mut stats = vec![];
+    if let Some(estimated_rows) = op.estimated_rows {
+        stats.push(format!("est. rows: {}", estimated_rows.round()));
+    }
+    if let Some(rows) = op.rows {
+        stats.push(format!("rows: {rows}"));
+    }
+    if let Some(db_hits) = op.db_hits {
+        stats.push(format!("db hits: {db_hits}"));
+    }
+    if let Some(time_ms) = op.time_ms {
+        stats.push(format!("time: {time_ms:.2}ms"));
+    }
+    if !stats.is_empty() {
+        line.push_str(&format!(" ({})", stats.join(", ")));
+    }
+
+    lines.push(line);
+
+    let num_children = op.children.len();
+    for (i, child) in op.children.iter().enumerate() {
+        let is_last = i == num_children - 1;
+        let (branch, continuation) = if is_last {
+            ("└─ ", "   ")
+        } else {
+            ("├─ ", "│  ")
+        };
+
+        push_operator(
+            lines,
+            child,
+            &format!("{child_prefix}{branch}"),
+            &format!("{child_prefix}{continuation}"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::PlanMode;
+    use insta::assert_snapshot;
+
+    #[test]
+    fn get_plan_tree_renders_nested_operators_with_statistics() {
+        // GIVEN
+        let plan = QueryPlan {
+            mode: PlanMode::Profile,
+            root: PlanOperator {
+                name: "ProduceResults".to_string(),
+                details: vec!["n.name".to_string()],
+                estimated_rows: Some(10.0),
+                rows: Some(3),
+                db_hits: Some(6),
+                children: vec![PlanOperator {
+                    name: "Filter".to_string(),
+                    details: vec!["n.age > 30".to_string()],
+                    rows: Some(3),
+                    children: vec![
+                        PlanOperator {
+                            name: "NodeByLabelScan".to_string(),
+                            details: vec!["n:Person".to_string()],
+                            estimated_rows: Some(100.0),
+                            rows: Some(100),
+                            db_hits: Some(101),
+                            ..Default::default()
+                        },
+                        PlanOperator {
+                            name: "Argument".to_string(),
+                            time_ms: Some(0.5),
+                            ..Default::default()
+                        },
+                    ],
+                    ..Default::default()
+                }],
+                ..Default::default()
+            },
+        };
+
+        // WHEN
+        let result = get_plan_tree(&plan);
+
+        // THEN
+        assert_snapshot!(result, @"
+        ProduceResults [n.name] (est. rows: 10, rows: 3, db hits: 6)
+        └─ Filter [n.age > 30] (rows: 3)
+           ├─ NodeByLabelScan [n:Person] (est. rows: 100, rows: 100, db hits: 101)
+           └─ Argument (time: 0.50ms)
+        ");
+    }
+}
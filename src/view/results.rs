@@ -1,45 +1,225 @@
+use crate::domain::{OutputFormat, RowSink, get_cell, get_headers, graph_display};
+use crate::service::{CsvWriter, JsonWriter, MarkdownWriter, NdjsonWriter, ResultsWriter};
+use anyhow::Context;
 use serde_json::Value;
-use tabled::builder::Builder;
-use tabled::settings::style::Style;
-
-use crate::domain::NonEmptyResults;
-
-pub fn get_results(results: &NonEmptyResults) -> String {
-    let mut builder = Builder::default();
-
-    if let Value::Object(first) = results.first() {
-        let headers: Vec<String> = first.keys().cloned().collect();
-        builder.push_record(&headers);
-
-        for result in results.list() {
-            if let Value::Object(row) = result {
-                let cells: Vec<String> = headers
-                    .iter()
-                    .map(|h| {
-                        row.get(h)
-                            .map(|v| match v {
-                                Value::String(s) => s.clone(),
-                                Value::Null => "null".to_string(),
-                                _ => v.to_string(),
-                            })
-                            .unwrap_or_else(|| "".to_string())
-                    })
-                    .collect();
-                builder.push_record(cells);
+use std::io::Write;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// Number of rows that are buffered before the table's headers and column widths are decided.
+const TABLE_LOOK_AHEAD_ROWS: usize = 100;
+
+/// Renders rows as a table as they arrive.
+///
+/// The first few rows are buffered to figure out the headers (the keys of all of them, and a "value"
+/// column for rows that aren't objects) and column widths; subsequent rows are written right away,
+/// with cells wider than their column cut short with an ellipsis, and keys that no buffered row had
+/// left out.
+pub struct TableWriter<W: Write> {
+    writer: W,
+    look_ahead: usize,
+    pending: Vec<Value>,
+    layout: Option<TableLayout>,
+}
+
+struct TableLayout {
+    headers: Vec<String>,
+    widths: Vec<usize>,
+}
+
+impl<W: Write> TableWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            look_ahead: TABLE_LOOK_AHEAD_ROWS,
+            pending: vec![],
+            layout: None,
+        }
+    }
+
+    fn write_pending(&mut self) -> anyhow::Result<()> {
+        let headers = get_headers(&self.pending);
+
+        let mut widths: Vec<usize> = headers.iter().map(|h| cell_width(h)).collect();
+        let rows: Vec<Vec<String>> = self
+            .pending
+            .drain(..)
+            .map(|row| get_cells(&headers, &row))
+            .collect();
+
+        for row in &rows {
+            for (width, cell) in widths.iter_mut().zip(row) {
+                *width = (*width).max(cell_width(cell));
+            }
+        }
+
+        let layout = TableLayout { headers, widths };
+
+        if !layout.headers.is_empty() {
+            write_line(&mut self.writer, &layout.headers, &layout.widths)?;
+            let separator: Vec<String> = layout.widths.iter().map(|w| "-".repeat(w + 2)).collect();
+            writeln!(self.writer, "{}", separator.join("+"))
+                .context("couldn't write results table")?;
+        }
+
+        for row in &rows {
+            write_line(&mut self.writer, row, &layout.widths)?;
+        }
+
+        self.layout = Some(layout);
+
+        Ok(())
+    }
+}
+
+impl<W: Write> RowSink for TableWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        match &self.layout {
+            Some(layout) => {
+                let cells = get_cells(&layout.headers, &row);
+                write_line(&mut self.writer, &cells, &layout.widths)?;
+            }
+            None => {
+                self.pending.push(row);
+                if self.pending.len() >= self.look_ahead {
+                    self.write_pending()?;
+                }
             }
         }
+
+        Ok(())
     }
 
-    let mut table = builder.build();
+    fn finish(&mut self) -> anyhow::Result<()> {
+        if self.layout.is_none() && !self.pending.is_empty() {
+            self.write_pending()?;
+        }
 
-    table.with(Style::psql());
+        self.writer
+            .flush()
+            .context("couldn't write results table")?;
 
-    table.to_string()
+        Ok(())
+    }
+}
+
+/// Prints rows in an output format as they arrive.
+pub enum OutputWriter<W: Write> {
+    Table(TableWriter<W>),
+    Serialized(ResultsWriter<W>),
+}
+
+impl<W: Write> OutputWriter<W> {
+    pub fn new(format: OutputFormat, writer: W) -> Self {
+        match format {
+            OutputFormat::Table => Self::Table(TableWriter::new(writer)),
+            OutputFormat::Json => Self::Serialized(ResultsWriter::Json(JsonWriter::new(writer))),
+            OutputFormat::Ndjson => {
+                Self::Serialized(ResultsWriter::Ndjson(NdjsonWriter::new(writer)))
+            }
+            OutputFormat::Csv => {
+                Self::Serialized(ResultsWriter::Csv(Box::new(CsvWriter::new(writer))))
+            }
+            OutputFormat::Tsv => {
+                Self::Serialized(ResultsWriter::Csv(Box::new(CsvWriter::tsv(writer))))
+            }
+            OutputFormat::Markdown => {
+                Self::Serialized(ResultsWriter::Markdown(MarkdownWriter::new(writer)))
+            }
+        }
+    }
+}
+
+impl<W: Write> RowSink for OutputWriter<W> {
+    fn write_row(&mut self, row: Value) -> anyhow::Result<()> {
+        match self {
+            Self::Table(w) => w.write_row(row),
+            Self::Serialized(w) => w.write_row(row),
+        }
+    }
+
+    fn finish(&mut self) -> anyhow::Result<()> {
+        match self {
+            Self::Table(w) => w.finish(),
+            Self::Serialized(w) => w.finish(),
+        }
+    }
+}
+
+fn get_cells(headers: &[String], row: &Value) -> Vec<String> {
+    headers
+        .iter()
+        .map(|h| {
+            get_cell(row, h)
+                .map(|v| match v {
+                    Value::String(s) => s.clone(),
+                    Value::Null => "null".to_string(),
+                    _ => graph_display(v).unwrap_or_else(|| v.to_string()),
+                })
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+fn cell_width(cell: &str) -> usize {
+    cell.lines().map(|l| l.width()).max().unwrap_or_default()
+}
+
+/// Cells spanning multiple lines make the row span as many lines; lines wider than their column
+/// are truncated.
+fn write_line<W: Write>(writer: &mut W, cells: &[String], widths: &[usize]) -> anyhow::Result<()> {
+    let cell_lines: Vec<Vec<&str>> = cells.iter().map(|c| c.lines().collect()).collect();
+    let height = cell_lines
+        .iter()
+        .map(|l| l.len())
+        .max()
+        .unwrap_or_default()
+        .max(1);
+
+    for i in 0..height {
+        let parts: Vec<String> = cell_lines
+            .iter()
+            .zip(widths)
+            .map(|(lines, width)| {
+                let line = truncate(lines.get(i).copied().unwrap_or_default(), *width);
+                let padding = width.saturating_sub(line.width());
+                format!(" {}{} ", line, " ".repeat(padding))
+            })
+            .collect();
+
+        writeln!(writer, "{}", parts.join("|")).context("couldn't write results table")?;
+    }
+
+    Ok(())
+}
+
+fn truncate(line: &str, width: usize) -> String {
+    if line.width() <= width {
+        return line.to_string();
+    }
+
+    // leaves room for the ellipsis
+    let mut truncated = String::new();
+    let mut truncated_width = 0;
+    for c in line.chars() {
+        let char_width = c.width().unwrap_or_default();
+        if truncated_width + char_width >= width {
+            break;
+        }
+        truncated.push(c);
+        truncated_width += char_width;
+    }
+
+    if width > 0 {
+        truncated.push('…');
+    }
+
+    truncated
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{NonEmptyResults, write_rows};
     use insta::assert_snapshot;
 
     #[test]
@@ -108,11 +288,12 @@ mod tests {
     }
 
     #[test]
-    fn get_results_skips_non_object_array_elements() {
+    fn get_results_shows_non_object_rows_in_a_value_column() {
         // GIVEN
         let results = vec![
             serde_json::json!({"language": "Rust", "creator": "Graydon Hoare"}),
-            serde_json::json!("invalid"),
+            serde_json::json!(42),
+            serde_json::json!(["Go", "Python"]),
             serde_json::json!({"language": "Python", "creator": "Guido van Rossum"}),
         ];
         let results = NonEmptyResults::try_from(results).expect("results should've been created");
@@ -121,11 +302,36 @@ mod tests {
         let result = get_results(&results);
 
         // THEN
-        assert_snapshot!(result, @r"
-         creator          | language 
-        ------------------+----------
-         Graydon Hoare    | Rust     
-         Guido van Rossum | Python
+        assert_snapshot!(result, @r#"
+         creator          | language | value           
+        ------------------+----------+-----------------
+         Graydon Hoare    | Rust     |                 
+                          |          | 42              
+                          |          | ["Go","Python"] 
+         Guido van Rossum | Python   |
+        "#);
+    }
+
+    #[test]
+    fn get_results_shows_columns_that_only_later_rows_have() {
+        // GIVEN
+        let results = vec![
+            serde_json::json!({"language": "Rust"}),
+            serde_json::json!({"language": "Python", "creator": "Guido van Rossum"}),
+            serde_json::json!({"language": "Go", "year": 2009}),
+        ];
+        let results = NonEmptyResults::try_from(results).expect("results should've been created");
+
+        // WHEN
+        let result = get_results(&results);
+
+        // THEN
+        assert_snapshot!(result, @"
+         language | creator          | year 
+        ----------+------------------+------
+         Rust     |                  |      
+         Python   | Guido van Rossum |      
+         Go       |                  | 2009
         ");
     }
 
@@ -151,4 +357,131 @@ mod tests {
                           | Go       | 2009
         ");
     }
+
+    #[test]
+    fn table_writer_truncates_cells_past_the_look_ahead_to_existing_column_widths()
+    -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = TableWriter::new(&mut buffer);
+        writer.look_ahead = 2;
+        let rows = vec![
+            serde_json::json!({"language": "Go", "year": 2009}),
+            serde_json::json!({"language": "Rust", "year": 2010}),
+            serde_json::json!({"language": "JavaScript", "year": 1995}),
+        ];
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @r"
+         language | year 
+        ----------+------
+         Go       | 2009 
+         Rust     | 2010 
+         JavaScr… | 1995
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_writer_leaves_out_keys_only_seen_past_the_look_ahead() -> anyhow::Result<()> {
+        // GIVEN
+        let mut buffer = Vec::new();
+        let mut writer = TableWriter::new(&mut buffer);
+        writer.look_ahead = 2;
+        let rows = vec![
+            serde_json::json!({"language": "Go"}),
+            serde_json::json!({"language": "Rust", "year": 2010}),
+            serde_json::json!({"language": "Python", "creator": "Guido van Rossum"}),
+        ];
+
+        // WHEN
+        write_rows(rows, &mut writer)?;
+        writer.finish()?;
+
+        // THEN
+        let result = String::from_utf8(buffer)?;
+        assert_snapshot!(result, @"
+         language | year 
+        ----------+------
+         Go       |      
+         Rust     | 2010 
+         Python   |
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn table_writer_splits_multi_line_cells_over_lines() -> anyhow::Result<()> {
+        // GIVEN
+        let results = vec![
+            serde_json::json!({"language": "Rust", "motto": "fast\nreliable\nproductive"}),
+            serde_json::json!({"language": "Go", "motto": "simple"}),
+        ];
+        let results = NonEmptyResults::try_from(results).expect("results should've been created");
+
+        // WHEN
+        let result = get_results(&results);
+
+        // THEN
+        assert_snapshot!(result, @r"
+         language | motto      
+        ----------+------------
+         Rust     | fast       
+                  | reliable   
+                  | productive 
+         Go       | simple
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_results_renders_graph_entities_as_cypher_literals() {
+        // GIVEN
+        let keanu = serde_json::json!({
+            "~id": "1",
+            "~entityType": "node",
+            "~labels": ["Person"],
+            "~properties": {"name": "Keanu Reeves"}
+        });
+        let results = vec![serde_json::json!({
+            "n": keanu,
+            "r": {
+                "~id": "10",
+                "~entityType": "relationship",
+                "~type": "ACTED_IN",
+                "~start": "1",
+                "~end": "2",
+                "~properties": {"roles": ["Neo"]}
+            },
+            "friends": [keanu],
+        })];
+        let results = NonEmptyResults::try_from(results).expect("results should've been created");
+
+        // WHEN
+        let result = get_results(&results);
+
+        // THEN
+        assert_snapshot!(result, @r#"
+         friends                            | n                                | r                            
+        ------------------------------------+----------------------------------+------------------------------
+         [(:Person {name: "Keanu Reeves"})] | (:Person {name: "Keanu Reeves"}) | [:ACTED_IN {roles: ["Neo"]}]
+        "#);
+    }
+
+    fn get_results(results: &NonEmptyResults) -> String {
+        let mut buffer = Vec::new();
+        let mut writer = TableWriter::new(&mut buffer);
+        write_rows(results.list().to_vec(), &mut writer).expect("rows should've been written");
+        writer.finish().expect("table should've been finished");
+
+        String::from_utf8(buffer).expect("table should've been valid utf-8")
+    }
 }
@@ -0,0 +1,248 @@
+use super::TableWriter;
+use crate::domain::{RowSink, Schema};
+use serde_json::{Value, json};
+
+/// Renders each part of the schema as a table of its own.
+pub fn get_schema_tables(schema: &Schema) -> anyhow::Result<String> {
+    let count = |count: Option<i64>| count.map(Value::from).unwrap_or_else(|| json!("-"));
+
+    let sections = [
+        (
+            "Labels",
+            schema
+                .labels
+                .iter()
+                .map(|l| json!({"label": l.label, "nodes": count(l.count)}))
+                .collect::<Vec<_>>(),
+        ),
+        (
+            "Relationship types",
+            schema
+                .relationship_types
+                .iter()
+                .map(|r| {
+                    json!({"relationship type": r.relationship_type, "relationships": count(r.count)})
+                })
+                .collect(),
+        ),
+        (
+            "Patterns",
+            schema
+                .patterns
+                .iter()
+                .map(|p| json!({"pattern": p.to_string()}))
+                .collect(),
+        ),
+        (
+            "Properties",
+            schema
+                .properties
+                .iter()
+                .map(|p| {
+                    json!({"on": p.on.to_string(), "property": p.key, "types": p.types.join(", ")})
+                })
+                .collect(),
+        ),
+        (
+            "Indexes",
+            schema
+                .indexes
+                .iter()
+                .map(|i| {
+                    json!({
+                        "name": i.name,
+                        "on": i.on.to_string(),
+                        "properties": i.properties.join(", "),
+                        "state": i.state,
+                        "type": i.index_type,
+                    })
+                })
+                .collect(),
+        ),
+        (
+            "Constraints",
+            schema
+                .constraints
+                .iter()
+                .map(|c| {
+                    json!({
+                        "name": c.name,
+                        "on": c.on.to_string(),
+                        "properties": c.properties.join(", "),
+                        "type": c.constraint_type,
+                    })
+                })
+                .collect(),
+        ),
+    ];
+
+    let mut output = vec![];
+    for (i, (title, rows)) in sections.into_iter().enumerate() {
+        if i > 0 {
+            output.push(b'\n');
+        }
+        output.extend_from_slice(format!("{title}\n\n").as_bytes());
+
+        if rows.is_empty() {
+            output.extend_from_slice(b"none\n");
+            continue;
+        }
+
+        let mut writer = TableWriter::new(&mut output);
+        for row in rows {
+            writer.write_row(row)?;
+        }
+        writer.finish()?;
+    }
+
+    Ok(String::from_utf8_lossy(&output).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        ConstraintSchema, IndexSchema, LabelSchema, PropertySchema, RelationshipTypeSchema,
+        SchemaElement, SchemaPattern,
+    };
+    use insta::assert_snapshot;
+
+    //-------------//
+    //  SUCCESSES  //
+    //-------------//
+
+    #[test]
+    fn get_schema_tables_works() -> anyhow::Result<()> {
+        // GIVEN
+        let schema = Schema {
+            labels: vec![
+                LabelSchema {
+                    label: "Movie".to_string(),
+                    count: Some(38),
+                },
+                LabelSchema {
+                    label: "Person".to_string(),
+                    count: Some(133),
+                },
+            ],
+            relationship_types: vec![RelationshipTypeSchema {
+                relationship_type: "ACTED_IN".to_string(),
+                count: None,
+            }],
+            patterns: vec![SchemaPattern {
+                from: "Person".to_string(),
+                relationship_type: "ACTED_IN".to_string(),
+                to: "Movie".to_string(),
+            }],
+            properties: vec![
+                PropertySchema {
+                    on: SchemaElement::node(["Person".to_string()]),
+                    key: "name".to_string(),
+                    types: vec!["String".to_string()],
+                },
+                PropertySchema {
+                    on: SchemaElement::relationship(["ACTED_IN".to_string()]),
+                    key: "roles".to_string(),
+                    types: vec!["StringArray".to_string()],
+                },
+            ],
+            indexes: vec![IndexSchema {
+                name: "person_name".to_string(),
+                index_type: "RANGE".to_string(),
+                on: SchemaElement::node(["Person".to_string()]),
+                properties: vec!["name".to_string()],
+                state: "ONLINE".to_string(),
+            }],
+            constraints: vec![ConstraintSchema {
+                name: "movie_title".to_string(),
+                constraint_type: "UNIQUENESS".to_string(),
+                on: SchemaElement::node(["Movie".to_string()]),
+                properties: vec!["title".to_string()],
+            }],
+        };
+
+        // WHEN
+        let result = get_schema_tables(&schema)?;
+
+        // THEN
+        assert_snapshot!(result, @"
+        Labels
+
+         label  | nodes 
+        --------+-------
+         Movie  | 38    
+         Person | 133   
+
+        Relationship types
+
+         relationship type | relationships 
+        -------------------+---------------
+         ACTED_IN          | -             
+
+        Patterns
+
+         pattern                         
+        ---------------------------------
+         (:Person)-[:ACTED_IN]->(:Movie) 
+
+        Properties
+
+         on          | property | types       
+        -------------+----------+-------------
+         (:Person)   | name     | String      
+         [:ACTED_IN] | roles    | StringArray 
+
+        Indexes
+
+         name        | on        | properties | state  | type  
+        -------------+-----------+------------+--------+-------
+         person_name | (:Person) | name       | ONLINE | RANGE 
+
+        Constraints
+
+         name        | on       | properties | type       
+        -------------+----------+------------+------------
+         movie_title | (:Movie) | title      | UNIQUENESS
+        ");
+
+        Ok(())
+    }
+
+    #[test]
+    fn get_schema_tables_works_for_an_empty_schema() -> anyhow::Result<()> {
+        // GIVEN
+        let schema = Schema::default();
+
+        // WHEN
+        let result = get_schema_tables(&schema)?;
+
+        // THEN
+        assert_snapshot!(result, @"
+        Labels
+
+        none
+
+        Relationship types
+
+        none
+
+        Patterns
+
+        none
+
+        Properties
+
+        none
+
+        Indexes
+
+        none
+
+        Constraints
+
+        none
+        ");
+
+        Ok(())
+    }
+}
@@ -1,8 +1,10 @@
 use insta_cmd::get_cargo_bin;
 use std::{ffi::OsStr, path::PathBuf, process::Command};
+use tempfile::TempDir;
 
 pub struct Fixture {
     bin_path: PathBuf,
+    config_dir: Option<TempDir>,
 }
 
 #[cfg(test)]
@@ -10,7 +12,24 @@ impl Fixture {
     pub fn new() -> Self {
         let bin_path = get_cargo_bin("grafq");
 
-        Self { bin_path }
+        Self {
+            bin_path,
+            config_dir: None,
+        }
+    }
+
+    #[allow(unused)]
+    pub fn with_config(contents: &str) -> Self {
+        let config_dir = tempfile::tempdir().expect("temporary directory should've been created");
+        let grafq_dir = config_dir.path().join("grafq");
+        std::fs::create_dir_all(&grafq_dir).expect("config directory should've been created");
+        std::fs::write(grafq_dir.join("config.toml"), contents)
+            .expect("config file should've been written");
+
+        Self {
+            bin_path: get_cargo_bin("grafq"),
+            config_dir: Some(config_dir),
+        }
     }
 
     pub fn cmd<I, S>(&self, args: I) -> Command
@@ -21,6 +40,9 @@ impl Fixture {
         let mut command = Command::new(&self.bin_path);
         command.args(args);
         command.env_clear();
+        if let Some(config_dir) = &self.config_dir {
+            command.env("XDG_CONFIG_HOME", config_dir.path());
+        }
 
         command
     }
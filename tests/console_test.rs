@@ -28,7 +28,14 @@ fn shows_help() {
       -w, --write-results            Write results to filesystem
       -d, --results-dir <DIRECTORY>  Directory to write results in [default: .grafq]
           --debug                    Output debug information without doing anything
-      -f, --results-format <FORMAT>  Format to write results in [default: json] [possible values: csv, json]
+      -f, --results-format <FORMAT>  Format to write results in [default: json] [possible values: csv, json, ndjson, yaml, markdown, tsv, html, parquet, arrow, sqlite]
+          --profile <NAME>           Connection profile to use (from grafq's config file)
+          --read-only                Reject queries that write to the database
+          --timeout <DURATION>       Cancel queries (on the server as well) that run for longer than this (eg. 500ms, 30s, 2m)
+          --retries <NUMBER>         Number of times to retry read queries that fail with transient errors (eg. throttling) [default: 3]
+          --stop-on-error            Stop running a script's statements once one of them fails (the default)
+          --continue-on-error        Keep running a script's statements even if some of them fail
+          --no-color                 Don't use colors in output (also turned off when $NO_COLOR is set)
       -h, --help                     Print help
 
     ----- stderr -----
@@ -50,6 +57,10 @@ fn debug_flag_works_for_defaults() {
     DEBUG INFO
 
     command:                    console
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
     display results via pager:  false
     write results:              false
     results directory:          .grafq
@@ -82,6 +93,10 @@ fn debug_flag_works_for_overridden_flags() {
     DEBUG INFO
 
     command:                    console
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
     display results via pager:  false
     write results:              true
     results directory:          path/to/results/dir
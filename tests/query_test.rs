@@ -4,6 +4,21 @@ use common::Fixture;
 use insta_cmd::assert_cmd_snapshot;
 
 const QUERY: &str = "MATCH (c: Candidate) RETURN c.id LIMIT 5";
+const PROFILES_CONFIG: &str = r#"
+[profiles.local]
+uri = "bolt://127.0.0.1:7687"
+user = "neo4j"
+password = "secret"
+database = "neo4j"
+
+[profiles.incomplete]
+uri = "bolt://127.0.0.1:7687"
+
+[profiles.prod]
+uri = "https://abc.xyz.us-east-1.neptune.amazonaws.com:8182"
+aws_region = "us-east-1"
+read_only = true
+"#;
 
 //-------------//
 //  SUCCESSES  //
@@ -23,21 +38,46 @@ fn shows_help() {
     ----- stdout -----
     Execute a one-off query
 
-    Usage: grafq query [OPTIONS] <QUERY>
+    Usage: grafq query [OPTIONS] [QUERY]
 
     Arguments:
-      <QUERY>  Cypher query to execute
+      [QUERY]  Query to execute ("-" reads it from stdin); can contain several ";"-separated statements
 
     Options:
       -p, --page-results                    Display results via a pager ("less", by default, can be overridden by $GRAFQ_PAGER)
-      -b, --bench                           Whether to benchmark the query
+          --file <FILE>                     File to read the query (or several ";"-separated statements) from
+      -l, --lang <LANGUAGE>                 Language the query is written in [default: cypher] [possible values: cypher, gremlin]
           --debug                           Output debug information without doing anything
+          --param <KEY=VALUE>               Query parameter, as key=value (values are parsed as JSON, falling back to strings)
+          --params-file <FILE>              JSON file containing an object of query parameters
+          --profile <NAME>                  Connection profile to use (from grafq's config file)
+          --plan <MODE>                     Show the query's plan instead of its results ("profile" executes the query to gather runtime statistics) [possible values: explain, profile]
+          --read-only                       Reject queries that write to the database
+          --plan-details                    Request a detailed plan (AWS Neptune only; executes the query)
+          --timeout <DURATION>              Cancel queries (on the server as well) that run for longer than this (eg. 500ms, 30s, 2m)
+          --plan-format <FORMAT>            Format to print the query plan in [default: tree] [possible values: tree, json]
+          --retries <NUMBER>                Number of times to retry read queries that fail with transient errors (eg. throttling) [default: 3]
+      -b, --bench                           Whether to benchmark the query
+          --stop-on-error                   Stop running a script's statements once one of them fails (the default)
+          --continue-on-error               Keep running a script's statements even if some of them fail
       -n, --bench-num-runs <NUMBER>         Number of benchmark runs [default: 5]
+          --no-color                        Don't use colors in output (also turned off when $NO_COLOR is set)
       -W, --bench-num-warmup-runs <NUMBER>  Number of benchmark warmup runs [default: 3]
+          --bench-output <FORMAT>           Write a benchmark report (with every run's latency) to the results directory in this format [possible values: csv, json]
+          --concurrency <NUMBER>            Number of tasks to run the query from at the same time (turns the benchmark into a load test)
+          --duration <DURATION>             How long to run a load test for (eg. 30s, 5m); the number of benchmark runs is used otherwise
+          --rate <QPS>                      Number of queries to start per second during a load test, across all tasks
+          --save-baseline <NAME>            Save the benchmark as a baseline with this name (replacing an existing one)
+          --compare-baseline <NAME>         Compare the benchmark with the baseline with this name
+          --fail-if-slower <PERCENTAGE>     Exit with code 3 if the median latency is significantly higher than the compared baseline's by more than this percentage (eg. 20%)
+          --bench-params <FILE>             File with a parameter set per row (.csv with a header of parameter keys, or .jsonl with an object per line) for benchmark runs to go through
+          --bench-params-order <ORDER>      Order in which benchmark runs go through parameter sets [default: cycle] [possible values: cycle, sample]
       -P, --print-query                     Print query
       -w, --write-results                   Write results to filesystem
       -d, --results-dir <DIRECTORY>         Directory to write results in [default: .grafq]
-      -f, --results-format <FORMAT>         Format to write results in [default: json] [possible values: csv, json]
+      -f, --results-format <FORMAT>         Format to write results in [default: json] [possible values: csv, json, ndjson, yaml, markdown, tsv, html, parquet, arrow, sqlite]
+          --table <NAME>                    Table to write results to (when writing results in the sqlite format); named after the query's id in the "queries" table otherwise
+      -o, --output-format <FORMAT>          Format to print results to stdout in (for formats other than "table", status messages like "No results" go to stderr) [possible values: table, json, ndjson, csv, tsv, markdown]
       -h, --help                            Print help
 
     ----- stderr -----
@@ -59,6 +99,11 @@ fn debug_flag_works_for_defaults() {
     DEBUG INFO
 
     command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
     display results via pager:  false
     benchmark:                  false
     print query:                false
@@ -95,10 +140,16 @@ fn debug_flag_works_with_overridden_flags() {
     DEBUG INFO
 
     command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
     display results via pager:  false
     benchmark:                  true
     benchmark num runs:         10
     benchmark num warmup runs:  5
+    benchmark output:           none
     print query:                true
     write results:              false
 
@@ -112,16 +163,18 @@ fn debug_flag_works_with_overridden_flags() {
 }
 
 #[test]
-fn debug_flag_works_for_write_results_flags() {
+fn debug_flag_works_for_load_test_flags() {
     // GIVEN
     let fx = Fixture::new();
     let mut cmd = fx.cmd([
         "query",
-        "--write-results",
-        "--results-dir",
-        "path/to/results/dir",
-        "--results-format",
-        "json",
+        "--bench",
+        "--concurrency",
+        "8",
+        "--duration",
+        "30s",
+        "--rate",
+        "200",
         "--debug",
         QUERY,
     ]);
@@ -135,12 +188,21 @@ fn debug_flag_works_for_write_results_flags() {
     DEBUG INFO
 
     command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
     display results via pager:  false
-    benchmark:                  false
+    benchmark:                  true
+    benchmark num runs:         5
+    benchmark num warmup runs:  3
+    benchmark output:           none
+    load test concurrency:      8
+    load test duration:         30s
+    load test rate:             200 qps
     print query:                false
-    write results:              true
-    results directory:          path/to/results/dir
-    results format:             json
+    write results:              false
 
     query:
     ---
@@ -151,131 +213,1109 @@ fn debug_flag_works_for_write_results_flags() {
     ");
 }
 
-//-------------//
-//  FAILURES   //
-//-------------//
-
 #[test]
-fn fails_if_provided_with_no_db_uri() {
+fn debug_flag_works_for_baseline_flags() {
     // GIVEN
     let fx = Fixture::new();
-    let mut cmd = fx.cmd(["query", QUERY]);
+    let mut cmd = fx.cmd([
+        "query",
+        "--bench",
+        "--save-baseline",
+        "feature-x",
+        "--compare-baseline",
+        "main",
+        "--fail-if-slower",
+        "20%",
+        "--debug",
+        QUERY,
+    ]);
 
     // WHEN
     // THEN
-    assert_cmd_snapshot!(cmd, @r#"
-    success: false
-    exit_code: 1
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
     ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  true
+    benchmark num runs:         5
+    benchmark num warmup runs:  3
+    benchmark output:           none
+    save baseline:              feature-x
+    compare baseline:           main
+    fail if slower by:          20%
+    print query:                false
+    write results:              false
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
 
     ----- stderr -----
-    Error: couldn't build db client
+    ");
+}
 
-    Caused by:
-        DB_URI is not set
+#[test]
+fn debug_flag_works_for_bench_params_flags() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--bench",
+        "--bench-params",
+        "params.csv",
+        "--bench-params-order",
+        "sample",
+        "--debug",
+        QUERY,
+    ]);
 
-    grafq requires the environment variable DB_URI to be set.
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
 
-    - For an AWS Neptune database, use the https scheme. Neptune uses IAM
-        authentication, so ensure your AWS credentials are configured correctly (via
-        environment variables or the AWS shared config file):
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  true
+    benchmark num runs:         5
+    benchmark num warmup runs:  3
+    benchmark output:           none
+    benchmark params file:      params.csv
+    benchmark params order:     sample
+    print query:                false
+    write results:              false
 
-        DB_URI="https://abc.xyz.us-east-1.neptune.amazonaws.com:8182"
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
 
-    - For a Neo4j database, use the bolt scheme and provide authentication details:
+    ----- stderr -----
+    ");
+}
 
-        DB_URI="bolt://127.0.0.1:7687"
-        NEO4J_USER="neo4j"
-        NEO4J_PASSWORD="your-password"
-        NEO4J_DB="neo4j"
-    "#);
+#[test]
+fn debug_flag_works_for_output_format_flag() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "-o", "ndjson", "--debug", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+    output format:              ndjson
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    ----- stderr -----
+    ");
 }
 
 #[test]
-fn fails_if_provided_with_invalid_db_uri_scheme() {
+fn debug_flag_works_for_table_flag() {
     // GIVEN
     let fx = Fixture::new();
-    let mut cmd = fx.cmd(["query", QUERY]);
-    cmd.env("DB_URI", "invalid://abc.xyz:8182");
+    let mut cmd = fx.cmd([
+        "query",
+        "--write-results",
+        "--results-format",
+        "sqlite",
+        "--table",
+        "candidates",
+        "--debug",
+        QUERY,
+    ]);
 
     // WHEN
     // THEN
-    assert_cmd_snapshot!(cmd, @r#"
-    success: false
-    exit_code: 1
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
     ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              true
+    results directory:          .grafq
+    results format:             sqlite
+    results table:              candidates
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
 
     ----- stderr -----
-    Error: couldn't build db client
+    ");
+}
 
-    Caused by:
-        DB_URI has unsupported scheme: "invalid"
+#[test]
+fn debug_flag_works_for_write_results_flags() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--write-results",
+        "--results-dir",
+        "path/to/results/dir",
+        "--results-format",
+        "json",
+        "--debug",
+        QUERY,
+    ]);
 
-    Only 'bolt' and 'https' schemes are supported by grafq.
-    Use bolt for neo4j, and https for AWS Neptune.
-    "#);
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              true
+    results directory:          path/to/results/dir
+    results format:             json
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    ----- stderr -----
+    ");
 }
 
 #[test]
-fn fails_if_not_provided_with_neo4j_auth() {
+fn debug_flag_works_for_query_params() {
     // GIVEN
     let fx = Fixture::new();
-    let mut cmd = fx.cmd(["query", QUERY]);
-    cmd.env("DB_URI", "bolt://127.0.0.1:7687");
+    let mut cmd = fx.cmd([
+        "query",
+        "--param",
+        "name=Alice",
+        "--param",
+        "ids=[1, 2]",
+        "--params-file",
+        "params.json",
+        "--debug",
+        "MATCH (n: Node {name: $name}) WHERE n.id IN $ids RETURN n",
+    ]);
 
     // WHEN
     // THEN
     assert_cmd_snapshot!(cmd, @r#"
-    success: false
-    exit_code: 1
+    success: true
+    exit_code: 0
     ----- stdout -----
+    DEBUG INFO
 
-    ----- stderr -----
-    Error: couldn't build db client
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    params:                     name="Alice", ids=[1,2]
+    params file:                params.json
+    write results:              false
 
-    Caused by:
-        environment variable "NEO4J_USER" is missing
+    query:
+    ---
+    MATCH (n: Node {name: $name}) WHERE n.id IN $ids RETURN n
+    ---
 
-    The environment variables NEO4J_USER, NEO4J_PASSWORD, and NEO4J_DB need to be set when connecting
-    to a neo4j database (which was determined by the bolt scheme in DB_URI).
+    ----- stderr -----
     "#);
 }
 
 #[test]
-fn fails_if_provided_with_incorrect_benchmark_num_runs() {
+fn debug_flag_works_for_plan_flags() {
     // GIVEN
     let fx = Fixture::new();
-    let mut cmd = fx.cmd(["query", "--bench", "--bench-num-runs", "0", QUERY]);
+    let mut cmd = fx.cmd([
+        "query",
+        "--plan",
+        "profile",
+        "--plan-details",
+        "--plan-format",
+        "json",
+        "--debug",
+        QUERY,
+    ]);
 
     // WHEN
     // THEN
     assert_cmd_snapshot!(cmd, @r"
-    success: false
-    exit_code: 2
+    success: true
+    exit_code: 0
     ----- stdout -----
+    DEBUG INFO
 
-    ----- stderr -----
-    error: invalid value '0' for '--bench-num-runs <NUMBER>': needs to be greater than 0
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    plan:                       profile
+    plan details:               true
+    plan format:                json
+    write results:              false
 
-    For more information, try '--help'.
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    ----- stderr -----
     ");
 }
 
 #[test]
-fn fails_if_both_benchmark_and_write_flags_are_provided() {
+fn debug_flag_works_for_read_only_flag() {
     // GIVEN
     let fx = Fixture::new();
-    let mut cmd = fx.cmd(["query", "--bench", "--write-results", QUERY]);
+    let mut cmd = fx.cmd(["query", "--read-only", "--debug", QUERY]);
 
     // WHEN
     // THEN
     assert_cmd_snapshot!(cmd, @r"
-    success: false
-    exit_code: 1
+    success: true
+    exit_code: 0
     ----- stdout -----
+    DEBUG INFO
 
-    ----- stderr -----
-    Error: cannot benchmark and write results at the same time
+    command:                    query
+    read only:                  true
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_works_for_timeout_flag() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--timeout", "90s", "--debug", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    90s
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_works_for_query_file_and_error_policy() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--file",
+        "migrations.cypher",
+        "--continue-on-error",
+        "--debug",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   continue
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+
+    query file:                 migrations.cypher
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn last_error_policy_flag_wins() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--continue-on-error",
+        "--stop-on-error",
+        "--debug",
+        "-",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+
+    query:                      -
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_prints_read_only_mode_turned_on_by_profile() {
+    // GIVEN
+    let fx = Fixture::with_config(PROFILES_CONFIG);
+    let mut cmd = fx.cmd(["query", "--profile", "prod", "--debug", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  true
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    profile:                    prod
+    db uri:                     https://abc.xyz.us-east-1.neptune.amazonaws.com:8182
+    user:                       (not set)
+    password:                   (not set)
+    database:                   (not set)
+    aws region:                 us-east-1
+    aws profile:                (not set)
+    read only:                  true
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_prints_resolved_profile_with_secrets_redacted() {
+    // GIVEN
+    let fx = Fixture::with_config(PROFILES_CONFIG);
+    let mut cmd = fx.cmd(["query", "--profile", "local", "--debug", QUERY]);
+    cmd.env("NEO4J_DB", "overridden");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    query
+    read only:                  false
+    timeout:                    none
+    retries:                    3
+    on error:                   stop
+    query language:             cypher
+    display results via pager:  false
+    benchmark:                  false
+    print query:                false
+    write results:              false
+
+    query:
+    ---
+    MATCH (c: Candidate) RETURN c.id LIMIT 5
+    ---
+
+    profile:                    local
+    db uri:                     bolt://127.0.0.1:7687
+    user:                       neo4j
+    password:                   ********
+    database:                   overridden
+    aws region:                 (not set)
+    aws profile:                (not set)
+    read only:                  false
+
+    ----- stderr -----
+    ");
+}
+
+//-------------//
+//  FAILURES   //
+//-------------//
+
+#[test]
+fn fails_if_provided_with_malformed_query_param() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--param", "name", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'name' for '--param <KEY=VALUE>': parameter needs to be in the form key=value
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn fails_if_benchmark_output_is_requested_without_benchmarking() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench-output", "json", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: a benchmark report can only be written when benchmarking
+    ");
+}
+
+#[test]
+fn fails_if_load_test_flags_are_provided_without_benchmarking() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--concurrency", "4", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: load test options (concurrency, duration, rate) can only be used when benchmarking
+    ");
+}
+
+#[test]
+fn fails_if_load_test_duration_is_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench", "--duration", "0s", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '0s' for '--duration <DURATION>': needs to be greater than 0
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn fails_if_slowdown_threshold_is_provided_without_a_baseline_to_compare_with() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench", "--fail-if-slower", "20%", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: --fail-if-slower needs a baseline to compare with (via --compare-baseline)
+    ");
+}
+
+#[test]
+fn fails_if_baselines_are_used_for_load_tests() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--bench",
+        "--concurrency",
+        "4",
+        "--save-baseline",
+        "main",
+        QUERY,
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: baselines aren't supported for load tests
+    ");
+}
+
+#[test]
+fn fails_if_bench_params_are_provided_without_benchmarking() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench-params", "params.csv", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: parameter sets can only be used when benchmarking
+    ");
+}
+
+#[test]
+fn fails_if_bench_params_are_used_for_load_tests() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--bench",
+        "--concurrency",
+        "4",
+        "--bench-params",
+        "params.csv",
+        QUERY,
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: parameter sets aren't supported for load tests
+    ");
+}
+
+#[test]
+fn fails_if_output_format_is_provided_along_with_write_results() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--write-results", "--output-format", "csv", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: an output format can only be used when results are printed to stdout (not when paging, writing, benchmarking, or showing a query plan)
+    ");
+}
+
+#[test]
+fn fails_if_table_is_provided_without_writing_results_to_sqlite() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--write-results",
+        "--results-format",
+        "csv",
+        "--table",
+        "candidates",
+        QUERY,
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: a table name can only be used when writing results in the sqlite format
+    ");
+}
+
+#[test]
+fn fails_if_table_name_is_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--write-results",
+        "--results-format",
+        "sqlite",
+        "--table",
+        "queries",
+        QUERY,
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'queries' for '--table <NAME>': name is reserved (it cannot be "queries" or start with "sqlite_")
+
+    For more information, try '--help'.
+    "#);
+}
+
+#[test]
+fn fails_if_baseline_name_is_invalid() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench", "--save-baseline", "../main", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '../main' for '--save-baseline <NAME>': name can only contain alphanumeric characters, "-", "_", and "." (and cannot start with ".")
+
+    For more information, try '--help'.
+    "#);
+}
+
+#[test]
+fn fails_if_query_params_are_provided_for_gremlin() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd([
+        "query",
+        "--lang",
+        "gremlin",
+        "--param",
+        "name=Alice",
+        "g.V().has('name', name)",
+    ]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: query parameters are not supported for gremlin queries
+    ");
+}
+
+#[test]
+fn fails_if_profile_is_not_defined() {
+    // GIVEN
+    let fx = Fixture::with_config(PROFILES_CONFIG);
+    let mut cmd = fx.cmd(["query", "--profile", "unknown", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't load config
+
+    Caused by:
+        profile "unknown" is not defined in the config file
+
+    Available profiles: incomplete, local, prod
+    "#);
+}
+
+#[test]
+fn fails_if_profile_is_missing_neo4j_auth() {
+    // GIVEN
+    let fx = Fixture::with_config(PROFILES_CONFIG);
+    let mut cmd = fx.cmd(["query", "--profile", "incomplete", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't build db client
+
+    Caused by:
+        profile "incomplete" doesn't set "user", and environment variable "NEO4J_USER" is missing
+
+    A user, password, and database need to be set when connecting to a neo4j database, either via the
+    profile or the environment variables NEO4J_USER, NEO4J_PASSWORD, and NEO4J_DB.
+    "#);
+}
+
+#[test]
+fn fails_if_provided_with_no_db_uri() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't build db client
+
+    Caused by:
+        DB_URI is not set
+
+    grafq requires the environment variable DB_URI to be set.
+
+    - For an AWS Neptune database, use the https scheme. Neptune uses IAM
+        authentication, so ensure your AWS credentials are configured correctly (via
+        environment variables or the AWS shared config file):
+
+        DB_URI="https://abc.xyz.us-east-1.neptune.amazonaws.com:8182"
+
+    - For a Neo4j database, use the bolt scheme and provide authentication details:
+
+        DB_URI="bolt://127.0.0.1:7687"
+        NEO4J_USER="neo4j"
+        NEO4J_PASSWORD="your-password"
+        NEO4J_DB="neo4j"
+
+    Alternatively, define a connection profile in grafq's config file and select it
+    via --profile.
+    "#);
+}
+
+#[test]
+fn fails_if_provided_with_invalid_db_uri_scheme() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", QUERY]);
+    cmd.env("DB_URI", "invalid://abc.xyz:8182");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't build db client
+
+    Caused by:
+        DB_URI has unsupported scheme: "invalid"
+
+    Only 'bolt' and 'https' schemes are supported by grafq.
+    Use bolt for neo4j, and https for AWS Neptune.
+    "#);
+}
+
+#[test]
+fn fails_if_not_provided_with_neo4j_auth() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", QUERY]);
+    cmd.env("DB_URI", "bolt://127.0.0.1:7687");
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r#"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: couldn't build db client
+
+    Caused by:
+        environment variable "NEO4J_USER" is missing
+
+    The environment variables NEO4J_USER, NEO4J_PASSWORD, and NEO4J_DB need to be set when connecting
+    to a neo4j database (which was determined by the bolt scheme in DB_URI).
+    "#);
+}
+
+#[test]
+fn fails_if_provided_with_incorrect_benchmark_num_runs() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench", "--bench-num-runs", "0", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '0' for '--bench-num-runs <NUMBER>': needs to be greater than 0
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn fails_if_provided_with_invalid_timeout() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--timeout", "10d", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '10d' for '--timeout <DURATION>': invalid unit provided; allowed values: [ms, s, m, h]
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn fails_if_neither_query_nor_query_file_is_provided() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the following required arguments were not provided:
+      <QUERY>
+
+    Usage: grafq query <QUERY>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn fails_if_both_query_and_query_file_are_provided() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--file", "migrations.cypher", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--file <FILE>' cannot be used with '[QUERY]'
+
+    Usage: grafq query --file <FILE> [QUERY]
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn fails_if_both_benchmark_and_write_flags_are_provided() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--bench", "--write-results", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: cannot benchmark and write results at the same time
+    ");
+}
+
+#[test]
+fn fails_if_plan_is_requested_while_benchmarking() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--plan", "explain", "--bench", QUERY]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: cannot show a query plan while benchmarking or writing results
+    ");
+}
+
+#[test]
+fn fails_if_plan_is_requested_for_gremlin() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["query", "--plan", "explain", "--lang", "gremlin", "g.V()"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    Error: query plans are only supported for cypher queries
     ");
 }
 
@@ -300,7 +1340,7 @@ fn fails_if_incorrect_results_format_provided() {
 
     ----- stderr -----
     error: invalid value 'unknown' for '--results-format <FORMAT>'
-      [possible values: csv, json]
+      [possible values: csv, json, ndjson, yaml, markdown, tsv, html, parquet, arrow, sqlite]
 
     For more information, try '--help'.
     ");
@@ -26,11 +26,19 @@ fn shows_help() {
     Commands:
       console  Open grafq's console
       query    Execute a one-off query
+      schema   Show the labels, relationship types, properties, indexes, and constraints in the database
       help     Print this message or the help of the given subcommand(s)
 
     Options:
-          --debug  Output debug information without doing anything
-      -h, --help   Print help
+          --debug               Output debug information without doing anything
+          --profile <NAME>      Connection profile to use (from grafq's config file)
+          --read-only           Reject queries that write to the database
+          --timeout <DURATION>  Cancel queries (on the server as well) that run for longer than this (eg. 500ms, 30s, 2m)
+          --retries <NUMBER>    Number of times to retry read queries that fail with transient errors (eg. throttling) [default: 3]
+          --stop-on-error       Stop running a script's statements once one of them fails (the default)
+          --continue-on-error   Keep running a script's statements even if some of them fail
+          --no-color            Don't use colors in output (also turned off when $NO_COLOR is set)
+      -h, --help                Print help
 
     ----- stderr -----
     ");
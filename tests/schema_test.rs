@@ -0,0 +1,87 @@
+mod common;
+
+use common::Fixture;
+use insta_cmd::assert_cmd_snapshot;
+
+//-------------//
+//  SUCCESSES  //
+//-------------//
+
+#[test]
+fn shows_help() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["schema", "--help"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Show the labels, relationship types, properties, indexes, and constraints in the database
+
+    Usage: grafq schema [OPTIONS]
+
+    Options:
+          --format <FORMAT>     Format to print the schema in [default: table] [possible values: table, json]
+          --debug               Output debug information without doing anything
+          --profile <NAME>      Connection profile to use (from grafq's config file)
+          --read-only           Reject queries that write to the database
+          --timeout <DURATION>  Cancel queries (on the server as well) that run for longer than this (eg. 500ms, 30s, 2m)
+          --retries <NUMBER>    Number of times to retry read queries that fail with transient errors (eg. throttling) [default: 3]
+          --stop-on-error       Stop running a script's statements once one of them fails (the default)
+          --continue-on-error   Keep running a script's statements even if some of them fail
+          --no-color            Don't use colors in output (also turned off when $NO_COLOR is set)
+      -h, --help                Print help
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn debug_flag_works() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["schema", "--format", "json", "--debug"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    DEBUG INFO
+
+    command:                    schema
+    read only:                  false
+    format:                     json
+
+    ----- stderr -----
+    ");
+}
+
+//-------------//
+//  FAILURES   //
+//-------------//
+
+#[test]
+fn fails_for_an_invalid_format() {
+    // GIVEN
+    let fx = Fixture::new();
+    let mut cmd = fx.cmd(["schema", "--format", "yaml"]);
+
+    // WHEN
+    // THEN
+    assert_cmd_snapshot!(cmd, @r"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'yaml' for '--format <FORMAT>'
+      [possible values: table, json]
+
+    For more information, try '--help'.
+    ");
+}